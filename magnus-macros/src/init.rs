@@ -1,10 +1,62 @@
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{Error, ItemFn};
+use syn::{
+    meta::ParseNestedMeta, parenthesized, punctuated::Punctuated, spanned::Spanned, Error, Expr,
+    ItemFn, LitStr, Path, Token,
+};
 
-pub fn expand(name: Option<String>, input: ItemFn) -> Result<TokenStream, Error> {
-    let crate_name = match name {
-        Some(v) => v,
+#[derive(Default)]
+pub struct Attrs {
+    name: Option<String>,
+    modules: Vec<LitStr>,
+    classes: Vec<Path>,
+    constants: Vec<(LitStr, Expr)>,
+}
+
+impl Attrs {
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else if meta.path.is_ident("modules") {
+            let content;
+            parenthesized!(content in meta.input);
+            self.modules
+                .extend(Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?);
+            Ok(())
+        } else if meta.path.is_ident("classes") {
+            let content;
+            parenthesized!(content in meta.input);
+            self.classes
+                .extend(Punctuated::<Path, Token![,]>::parse_terminated(&content)?);
+            Ok(())
+        } else if meta.path.is_ident("constants") {
+            meta.parse_nested_meta(|constant| {
+                let path = constant
+                    .path
+                    .segments
+                    .iter()
+                    .map(|s| s.ident.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::");
+                let path = LitStr::new(&path, constant.path.span());
+                self.constants
+                    .push((path, constant.value()?.parse::<Expr>()?));
+                Ok(())
+            })
+        } else {
+            Err(meta.error("unsupported attribute"))
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.modules.is_empty() && self.classes.is_empty() && self.constants.is_empty()
+    }
+}
+
+pub fn expand(attrs: Attrs, input: ItemFn) -> Result<TokenStream, Error> {
+    let crate_name = match attrs.name {
+        Some(ref v) => v.clone(),
         None => match std::env::var("CARGO_PKG_NAME") {
             Ok(v) => v,
             Err(_) => {
@@ -22,6 +74,25 @@ pub fn expand(name: Option<String>, input: ItemFn) -> Result<TokenStream, Error>
     );
     let init_name = input.sig.ident.clone();
 
+    let hierarchy = if attrs.is_empty() {
+        quote! {}
+    } else {
+        let modules = &attrs.modules;
+        let classes = &attrs.classes;
+        let (constant_paths, constant_values): (Vec<_>, Vec<_>) =
+            attrs.constants.iter().cloned().unzip();
+        quote! {
+            (|ruby: &magnus::Ruby| {
+                let mut hierarchy = magnus::hierarchy::Hierarchy::new();
+                #(hierarchy.module(#modules);)*
+                #(hierarchy.wrapped::<#classes>();)*
+                #(hierarchy.constant(#constant_paths, #constant_values);)*
+                hierarchy.define(ruby)
+            })
+            .call_handle_error();
+        }
+    };
+
     Ok(quote! {
         #input
 
@@ -29,6 +100,7 @@ pub fn expand(name: Option<String>, input: ItemFn) -> Result<TokenStream, Error>
         #[no_mangle]
         pub unsafe extern "C" fn #extern_init_name() {
             use magnus::method::{Init, RubyInit};
+            #hierarchy
             #init_name.call_handle_error()
         }
     })
@@ -20,6 +20,8 @@ use proc_macro::TokenStream;
 use syn::parse_macro_input;
 
 mod init;
+mod ruby_errors;
+mod symbol_enum;
 mod typed_data;
 mod util;
 
@@ -35,6 +37,17 @@ mod util;
 ///   This default's to the current crate's name. The name will be prepended
 ///   with `Init_` and `-` will be replaced with `_`. This (minus the `Init_`
 ///   prefix) must match the name of the final `.so`/`.bundle` file.
+/// * `modules("...", ...)` - declares modules, by path, e.g. `"Foo::Bar"`.
+/// * `classes(Type, ...)` - declares the classes of types wrapped with
+///   [`macro@wrap`] or deriving [`TypedData`], using their `class = "..."`
+///   and `superclass = "..."` attributes.
+/// * `constants(Path::NAME = value, ...)` - declares constants, where `value`
+///   is an expression of a type implementing `IntoValueFromNative`.
+///
+/// Declared modules, classes, and constants are defined before the init
+/// function is called, in dependency order; namespaces before the classes and
+/// modules nested in them, and superclasses before their subclasses. See
+/// `magnus::hierarchy::Hierarchy`.
 ///
 /// # Examples
 ///
@@ -89,21 +102,54 @@ mod util;
 ///     ()
 /// }
 /// ```
+///
+/// Declaring classes.
+/// ```
+/// use magnus::{function, method, prelude::*, Error, Ruby, TypedData};
+///
+/// #[magnus::wrap(class = "Euclid::Point")]
+/// struct Point {
+///     x: isize,
+///     y: isize,
+/// }
+///
+/// #[magnus::wrap(class = "Euclid::Point3D", superclass = "Euclid::Point")]
+/// struct Point3D {
+///     x: isize,
+///     y: isize,
+///     z: isize,
+/// }
+///
+/// impl Point3D {
+///     fn new(x: isize, y: isize, z: isize) -> Self {
+///         Self { x, y, z }
+///     }
+///
+///     fn z(&self) -> isize {
+///         self.z
+///     }
+/// }
+///
+/// #[magnus::init(
+///     modules("Euclid"),
+///     classes(Point3D, Point),
+///     constants(Euclid::VERSION = "1.0.0"),
+/// )]
+/// fn init(ruby: &Ruby) -> Result<(), Error> {
+///     let class = Point3D::class(ruby);
+///     class.define_singleton_method("new", function!(Point3D::new, 3))?;
+///     class.define_method("z", method!(Point3D::z, 0))?;
+///     Ok(())
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn init(attrs: TokenStream, item: TokenStream) -> TokenStream {
-    let mut name = None;
+    let mut init_attrs = init::Attrs::default();
     if !attrs.is_empty() {
-        let attr_parser = syn::meta::parser(|meta| {
-            if meta.path.is_ident("name") {
-                name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
-                Ok(())
-            } else {
-                Err(meta.error("unsupported attribute"))
-            }
-        });
+        let attr_parser = syn::meta::parser(|meta| init_attrs.parse(meta));
         parse_macro_input!(attrs with attr_parser);
     }
-    match init::expand(name, parse_macro_input!(item)) {
+    match init::expand(init_attrs, parse_macro_input!(item)) {
         Ok(tokens) => tokens,
         Err(e) => e.into_compile_error(),
     }
@@ -127,6 +173,11 @@ pub fn init(attrs: TokenStream, item: TokenStream) -> TokenStream {
 ///   Specifies a debug name for the type. This name must be unique and
 ///   defaults to the class name if not explicitly provided.
 ///
+/// * `superclass = "..."`:  
+///   Specifies the superclass of the class, used when the class is declared
+///   with the `classes(...)` attribute of [`macro@init`]. Defaults to
+///   `Object`.
+///
 /// * `free_immediately`:  
 ///   Indicates that the Rust type should be dropped as soon as the Ruby object
 ///   is garbage collected. This is only safe if the type's [`Drop`]
@@ -137,11 +188,36 @@ pub fn init(attrs: TokenStream, item: TokenStream) -> TokenStream {
 ///   Reports the [`std::mem::size_of_val`] of the type to Ruby, helping Ruby's
 ///   garbage collector determine when to run.
 ///
+/// * `mutable`:  
+///   Checks borrows of the type at runtime, allowing functions bound with
+///   `method!`/`function!` to take `&mut self`. A conflicting borrow, such as
+///   a block calling back into a method on the same object, raises a
+///   `RuntimeError`.
+///   Can not be combined with `mark` or `compact`, so the type can not hold
+///   Ruby values that need marking.
+///
+/// * `deconstruct`:  
+///   Implements `typed_data::Deconstruct` for a struct with named fields,
+///   providing `deconstruct` and `deconstruct_keys` methods for pattern
+///   matching.
+///
+/// * `attr_readers`:  
+///   Implements `typed_data::AttrReaders` for a struct with named fields,
+///   providing a reader method for each field.
+///
 /// * `unsafe_generics`:  
 ///   Disables compile-time checks for types with generics, allowing their use
 ///   with `#[wrap]`. This should only be used if you are confident that the
 ///   derived implementation of [`TypedData`] is correct for your generic type.
 ///
+/// # Field Attributes
+///
+/// The `#[magnus(mark)]` and `#[magnus(compact)]` attributes can be set on
+/// fields containing Ruby values to have them marked during garbage
+/// collection, `#[magnus(skip_deconstruct)]` to omit a field from pattern
+/// matching, and `#[magnus(skip_attr_reader)]` to omit a field's reader
+/// method, see [`TypedData`](macro@TypedData#field-attributes).
+///
 /// # Variant Attributes
 ///
 /// When wrapping enums, the `#[magnus(...)]` attribute can also be applied to
@@ -209,6 +285,36 @@ pub fn init(attrs: TokenStream, item: TokenStream) -> TokenStream {
 ///
 /// [`examples/mut_point.rs`]: https://github.com/matsadler/magnus/blob/main/examples/mut_point.rs
 ///
+/// Or with runtime borrow checking:
+///
+/// ```
+/// use magnus::{method, prelude::*, wrap, Error, Ruby};
+///
+/// #[wrap(class = "Point", mutable)]
+/// struct Point {
+///     x: isize,
+///     y: isize,
+/// }
+///
+/// impl Point {
+///     fn x(&self) -> isize {
+///         self.x
+///     }
+///
+///     fn set_x(&mut self, i: isize) {
+///         self.x = i;
+///     }
+/// }
+///
+/// #[magnus::init]
+/// fn init(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Point", ruby.class_object())?;
+///     class.define_method("x", method!(Point::x, 0))?;
+///     class.define_method("x=", method!(Point::set_x, 1))?;
+///     Ok(())
+/// }
+/// ```
+///
 /// Supporting subclassing:
 ///
 /// ```
@@ -336,6 +442,8 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///
 /// For simpler use cases, consider using [`macro@wrap`].
 ///
+/// The type's RBS type (see `magnus::rbs`) will be its Ruby class.
+///
 /// # Attributes
 ///
 /// The `#[magnus(...)]` attribute supports the following values to configure
@@ -349,6 +457,11 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///   Specifies a debug name for the type. This name must be unique and
 ///   defaults to the class name if not explicitly provided.
 ///
+/// * `superclass = "..."`:  
+///   Specifies the superclass of the class, used when the class is declared
+///   with the `classes(...)` attribute of [`macro@init`]. Defaults to
+///   `Object`.
+///
 /// * `free_immediately`:  
 ///   Indicates that the Rust type should be dropped as soon as the Ruby object
 ///   is garbage collected. This is only safe if the type's [`Drop`] and
@@ -382,6 +495,22 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///   concurrently from multiple threads when frozen. This allows objects to
 ///   shared between Ractors.
 ///
+/// * `mutable`:  
+///   Sets `TypedData::MUTABLE`, checking borrows at runtime and allowing
+///   functions bound with `method!`/`function!` to take `&mut Self`.
+///   Can not be combined with `mark` or `compact`, or `#[magnus(mark)]` and
+///   `#[magnus(compact)]` field attributes.
+///
+/// * `deconstruct`:  
+///   Implements `typed_data::Deconstruct` for a struct with named fields.
+///   Each field must implement `Clone` and `IntoValue`, unless skipped with
+///   the `skip_deconstruct` field attribute.
+///
+/// * `attr_readers`:  
+///   Implements `typed_data::AttrReaders` for a struct with named fields.
+///   Each field must implement `Clone` and `IntoValue`, unless skipped with
+///   the `skip_attr_reader` field attribute.
+///
 /// * `unsafe_generics`:  
 ///   Disables compile-time checks for types with generics, allowing their use
 ///   with `#[magnus(...)]`. Use this only if you are confident the derived
@@ -396,6 +525,30 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///   For a Ruby value wrapped in `Opaque`, creates an accessor method that
 ///   returns the unwrapped Ruby value.
 ///
+/// * `mark`:  
+///   Marks the Ruby values contained in the field during garbage collection.
+///   The field's type must implement `gc::Markable`, such as `Opaque<T>`,
+///   `Vec<Opaque<T>>`, or `HashMap<K, Opaque<T>>`. This enables the `mark`
+///   flag, and the fields are marked before calling
+///   `DataTypeFunctions::mark`, so this also works with a hand written
+///   `DataTypeFunctions` implementation.
+///
+/// * `compact`:  
+///   As `mark`, but allows values that can be updated after being moved by
+///   GC compaction (such as `Cell<Opaque<T>>`) to be moved. Enables the
+///   `mark` and `compact` flags, the fields are updated before calling
+///   `DataTypeFunctions::compact`.
+///
+/// * `skip_deconstruct`:  
+///   Omits the field from the `typed_data::Deconstruct` implementation
+///   generated by the `deconstruct` attribute.
+///
+/// * `skip_attr_reader`:  
+///   Omits the field from the `typed_data::AttrReaders` implementation
+///   generated by the `attr_readers` attribute.
+///
+/// Field attributes can also be set on the fields of enum variants.
+///
 /// # Variant Attributes
 ///
 /// The `#[magnus(...)]` attribute can be set on enum variants with the
@@ -535,6 +688,28 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///     Ok(())
 /// }
 /// ```
+///
+/// Marking fields with field attributes:
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use magnus::{
+///     block::Proc, value::Opaque, DataTypeFunctions, TypedData, Value,
+/// };
+///
+/// #[derive(DataTypeFunctions, TypedData)]
+/// #[magnus(class = "Registry", free_immediately)]
+/// struct Registry {
+///     #[magnus(mark)]
+///     callback: Opaque<Proc>,
+///     #[magnus(mark)]
+///     values: Vec<Opaque<Value>>,
+///     #[magnus(mark)]
+///     named: HashMap<String, Opaque<Value>>,
+///     count: usize,
+/// }
+/// ```
 #[proc_macro_derive(TypedData, attributes(magnus))]
 pub fn derive_typed_data(input: TokenStream) -> TokenStream {
     match typed_data::expand_derive_typed_data(parse_macro_input!(input)) {
@@ -543,3 +718,152 @@ pub fn derive_typed_data(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Derives `TryConvert` and `IntoValue` for a field-less enum, mapping each
+/// variant to a Ruby Symbol.
+///
+/// Conversion from Ruby accepts either a Symbol or a String. Any other value
+/// results in a `TypeError`, and a Symbol or String that does not match a
+/// variant results in an `ArgumentError` listing the allowed values.
+///
+/// Conversion to Ruby returns a `StaticSymbol`.
+///
+/// The RBS type of the enum (see `magnus::rbs`) is a union of its Symbols.
+///
+/// Variant names are converted to `snake_case` to produce the Symbol name.
+///
+/// # Variant Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on enum variants with the
+/// following values:
+///
+/// * `name = "..."`:  
+///   Sets the Symbol name for the variant, overriding the default.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, Error, Ruby, SymbolEnum};
+///
+/// #[derive(SymbolEnum)]
+/// enum Mode {
+///     Fast,
+///     Safe,
+///     #[magnus(name = "very_safe")]
+///     Paranoid,
+/// }
+///
+/// fn describe(mode: Mode) -> &'static str {
+///     match mode {
+///         Mode::Fast => "fast",
+///         Mode::Safe => "safe",
+///         Mode::Paranoid => "paranoid",
+///     }
+/// }
+///
+/// fn default_mode() -> Mode {
+///     Mode::Safe
+/// }
+///
+/// #[magnus::init]
+/// fn init(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("describe", function!(describe, 1));
+///     ruby.define_global_function("default_mode", function!(default_mode, 0));
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_derive(SymbolEnum, attributes(magnus))]
+pub fn derive_symbol_enum(input: TokenStream) -> TokenStream {
+    match symbol_enum::expand_derive_symbol_enum(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
+
+/// Derives `magnus::error::RubyErrors` and `IntoError` for an enum, mapping
+/// it to a hierarchy of Ruby exception classes.
+///
+/// The enum must implement `Display`, which provides the exception message.
+///
+/// Call `RubyErrors::define_errors` from your init function to define a base
+/// class, and a subclass of it for each variant, in the same namespace. Each
+/// variant's named fields are converted with `IntoValue` and exposed as
+/// reader methods on its class.
+///
+/// # Attributes
+///
+/// The `#[magnus(...)]` attribute must be set on the enum with the following
+/// values:
+///
+/// * `class = "..."`:  
+///   The path of the base class, e.g. `"MyGem::Error"`.
+/// * `superclass = "..."`:  
+///   Optional. The path of the base class's superclass. Defaults to
+///   `"StandardError"`.
+///
+/// # Variant Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on enum variants with the
+/// following values:
+///
+/// * `name = "..."`:  
+///   Sets the name of the variant's class, overriding the default of the
+///   variant name with an `Error` suffix, e.g. `TimeoutError` for `Timeout`.
+///
+/// # Field Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on named fields with the
+/// following values:
+///
+/// * `skip_attr_reader`:  
+///   Don't define a reader method for this field. The field will not be
+///   converted to Ruby.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt;
+///
+/// use magnus::{error::RubyErrors, function, Error, Ruby};
+///
+/// #[derive(Debug, magnus::RubyErrors)]
+/// #[magnus(class = "MyGem::Error")]
+/// enum MyGemError {
+///     Timeout { seconds: u64 },
+///     #[magnus(name = "IOError")]
+///     Io {
+///         path: String,
+///         #[magnus(skip_attr_reader)]
+///         source: std::io::Error,
+///     },
+/// }
+///
+/// impl fmt::Display for MyGemError {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         match self {
+///             Self::Timeout { seconds } => write!(f, "timed out after {}s", seconds),
+///             Self::Io { path, source } => write!(f, "{}: {}", path, source),
+///         }
+///     }
+/// }
+///
+/// fn wait(seconds: u64) -> Result<(), MyGemError> {
+///     Err(MyGemError::Timeout { seconds })
+/// }
+///
+/// #[magnus::init]
+/// fn init(ruby: &Ruby) -> Result<(), Error> {
+///     MyGemError::define_errors(ruby)?;
+///     ruby.define_global_function("wait", function!(wait, 1));
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_derive(RubyErrors, attributes(magnus))]
+pub fn derive_ruby_errors(input: TokenStream) -> TokenStream {
+    match ruby_errors::expand_derive_ruby_errors(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
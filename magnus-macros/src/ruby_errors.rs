@@ -0,0 +1,187 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{ext::IdentExt, spanned::Spanned, Data, DataEnum, DeriveInput, Error, Fields, LitStr};
+
+use crate::{typed_data, util};
+
+pub fn expand_derive_ruby_errors(input: DeriveInput) -> Result<TokenStream, Error> {
+    let variants = match input.data {
+        Data::Enum(DataEnum { ref variants, .. }) => variants,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "RubyErrors can only be derived for enums",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "RubyErrors can not be derived for enums with generics",
+        ));
+    }
+    if variants.is_empty() {
+        return Err(Error::new(
+            input.span(),
+            "RubyErrors can not be derived for enums with no variants",
+        ));
+    }
+
+    let attrs = match util::get_magnus_attribute(&input.attrs)? {
+        Some(v) => v,
+        None => {
+            return Err(Error::new(
+                input.span(),
+                "missing #[magnus] attribute, e.g. #[magnus(class = \"MyGem::Error\")]",
+            ))
+        }
+    };
+    let mut class = None;
+    let mut superclass = None;
+    attrs.parse_nested_meta(|meta| {
+        if meta.path.is_ident("class") {
+            class = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else if meta.path.is_ident("superclass") {
+            superclass = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else {
+            Err(meta.error("unsupported attribute"))
+        }
+    })?;
+    let class = match class {
+        Some(c) => c.trim_start_matches("::").to_owned(),
+        None => return Err(Error::new(attrs.span(), "missing attribute: `class`")),
+    };
+    let namespace = class.rsplit_once("::").map(|(namespace, _)| namespace);
+
+    let mut idents = Vec::new();
+    let mut names = Vec::new();
+    let mut members = Vec::new();
+    for variant in variants {
+        let mut name = None;
+        if let Some(attrs) = util::get_magnus_attribute(&variant.attrs)? {
+            attrs.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    name = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported attribute"))
+                }
+            })?;
+        }
+        let name = name.unwrap_or_else(|| {
+            let ident = variant.ident.unraw().to_string();
+            if ident.ends_with("Error") {
+                ident
+            } else {
+                format!("{}Error", ident)
+            }
+        });
+        if !name.starts_with(|c: char| c.is_ascii_uppercase())
+            || !name.chars().all(|c| c == '_' || c.is_alphanumeric())
+        {
+            return Err(Error::new(
+                variant.span(),
+                format!("`{}` is not a valid class name", name),
+            ));
+        }
+        if names.contains(&name) {
+            return Err(Error::new(
+                variant.span(),
+                format!("duplicate class name `{}`", name),
+            ));
+        }
+
+        let mut variant_members = Vec::new();
+        if let Fields::Named(ref fields) = variant.fields {
+            for field in &fields.named {
+                if typed_data::field_attrs(field)?.skip_attr_reader {
+                    continue;
+                }
+                variant_members.push(field.ident.clone().unwrap());
+            }
+        }
+
+        idents.push(&variant.ident);
+        names.push(name);
+        members.push(variant_members);
+    }
+
+    let ident = &input.ident;
+    let statics = (0..idents.len())
+        .map(|i| Ident::new(&format!("VARIANT_{}", i), Span::call_site()))
+        .collect::<Vec<_>>();
+    let paths = names
+        .iter()
+        .map(|n| match namespace {
+            Some(namespace) => format!("{}::{}", namespace, n),
+            None => n.clone(),
+        })
+        .collect::<Vec<_>>();
+    let attr_names = members
+        .iter()
+        .map(|m| {
+            m.iter()
+                .map(|member| LitStr::new(&member.unraw().to_string(), member.span()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let superclass = match superclass {
+        Some(v) => quote! { const SUPERCLASS: Option<&'static str> = Some(#v); },
+        None => quote! {},
+    };
+
+    let tokens = quote! {
+        const _: () = {
+            use magnus::{
+                error::{IntoError, RubyErrors},
+                value::{Lazy, ReprValue},
+                Error, ExceptionClass, IntoValue, Ruby, Value,
+            };
+
+            static BASE: Lazy<ExceptionClass> = Lazy::new(|ruby| {
+                #ident::class_at_path(ruby, #class).unwrap()
+            });
+            #(static #statics: Lazy<ExceptionClass> = Lazy::new(|ruby| {
+                #ident::class_at_path(ruby, #paths).unwrap()
+            });)*
+
+            impl RubyErrors for #ident {
+                const CLASS: &'static str = #class;
+                #superclass
+                const VARIANTS: &'static [(&'static str, &'static [&'static str])] =
+                    &[#((#names, &[#(#attr_names),*])),*];
+
+                fn base_class(ruby: &Ruby) -> ExceptionClass {
+                    ruby.get_inner(&BASE)
+                }
+
+                fn class(&self, ruby: &Ruby) -> ExceptionClass {
+                    ruby.get_inner(match self {
+                        #(Self::#idents { .. } => &#statics,)*
+                    })
+                }
+
+                #[allow(unused_variables)]
+                fn into_attrs(self, ruby: &Ruby) -> Vec<(&'static str, Value)> {
+                    match self {
+                        #(Self::#idents { #(#members,)* .. } => vec![
+                            #((#attr_names, IntoValue::into_value_with(#members, ruby))),*
+                        ],)*
+                    }
+                }
+            }
+
+            impl IntoError for #ident {
+                fn into_error(self, ruby: &Ruby) -> Error {
+                    match RubyErrors::into_exception(self, ruby) {
+                        Ok(e) => Error::from(e),
+                        Err(e) => e,
+                    }
+                }
+            }
+        };
+    };
+    Ok(tokens)
+}
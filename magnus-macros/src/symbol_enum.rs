@@ -0,0 +1,169 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{spanned::Spanned, Data, DataEnum, DeriveInput, Error, Fields, LitByteStr, LitStr};
+
+use crate::util;
+
+pub fn expand_derive_symbol_enum(input: DeriveInput) -> Result<TokenStream, Error> {
+    let variants = match input.data {
+        Data::Enum(DataEnum { ref variants, .. }) => variants,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "SymbolEnum can only be derived for enums",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "SymbolEnum can not be derived for enums with generics",
+        ));
+    }
+    if variants.is_empty() {
+        return Err(Error::new(
+            input.span(),
+            "SymbolEnum can not be derived for enums with no variants",
+        ));
+    }
+
+    let mut idents = Vec::new();
+    let mut names = Vec::new();
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(
+                variant.span(),
+                "SymbolEnum can only be derived for enums with field-less variants",
+            ));
+        }
+        let mut name = None;
+        if let Some(attrs) = util::get_magnus_attribute(&variant.attrs)? {
+            attrs.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    name = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported attribute"))
+                }
+            })?;
+        }
+        let name = name.unwrap_or_else(|| util::to_snake_case(&variant.ident.to_string()));
+        if name.contains('\0') {
+            return Err(Error::new(
+                variant.span(),
+                "name should not contain null byte",
+            ));
+        }
+        if names.contains(&name) {
+            return Err(Error::new(
+                variant.span(),
+                format!("duplicate symbol name `{}`", name),
+            ));
+        }
+        idents.push(&variant.ident);
+        names.push(name);
+    }
+
+    let ident = &input.ident;
+    let statics = (0..idents.len())
+        .map(|i| Ident::new(&format!("VARIANT_{}", i), Span::call_site()))
+        .collect::<Vec<_>>();
+    let name_strs = names
+        .iter()
+        .map(|n| LitStr::new(n, Span::call_site()))
+        .collect::<Vec<_>>();
+    let name_bytes = names
+        .iter()
+        .map(|n| LitByteStr::new(n.as_bytes(), Span::call_site()))
+        .collect::<Vec<_>>();
+    let expected = names
+        .iter()
+        .map(|n| format!(":{}", n))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let expected = LitStr::new(&expected, Span::call_site());
+    let rbs = names
+        .iter()
+        .map(|n| rbs_symbol(n))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let rbs = LitStr::new(&rbs, Span::call_site());
+
+    let tokens = quote! {
+        const _: () = {
+            use magnus::{
+                value::{LazyId, ReprValue},
+                Error, IntoValue, RString, Ruby, StaticSymbol, Symbol, TryConvert, Value,
+            };
+
+            #(static #statics: LazyId = LazyId::new(#name_strs);)*
+
+            impl TryConvert for #ident {
+                fn try_convert(val: Value) -> Result<Self, Error> {
+                    let ruby = Ruby::get_with(val);
+                    if let Some(sym) = Symbol::from_value(val) {
+                        // make sure the ids exist before the comparison so
+                        // dynamic symbols with a matching name are pinned
+                        #(LazyId::force(&#statics, &ruby);)*
+                        #(if sym == #statics {
+                            return Ok(Self::#idents);
+                        })*
+                    } else if let Some(s) = RString::from_value(val) {
+                        // safe as we don't call Ruby while holding the slice
+                        let bytes = unsafe { s.as_slice() };
+                        #(if bytes == #name_bytes {
+                            return Ok(Self::#idents);
+                        })*
+                    } else {
+                        return Err(Error::new(
+                            ruby.exception_type_error(),
+                            format!("no implicit conversion of {} into Symbol", unsafe {
+                                val.classname()
+                            }),
+                        ));
+                    }
+                    Err(Error::new(
+                        ruby.exception_arg_error(),
+                        format!("invalid value {} (expected one of {})", val.inspect(), #expected),
+                    ))
+                }
+            }
+
+            unsafe impl magnus::try_convert::TryConvertOwned for #ident {}
+
+            impl IntoValue for #ident {
+                fn into_value_with(self, ruby: &Ruby) -> Value {
+                    let id = match self {
+                        #(Self::#idents => &#statics,)*
+                    };
+                    StaticSymbol::from(LazyId::get_inner_with(id, ruby)).into_value_with(ruby)
+                }
+            }
+
+            unsafe impl magnus::IntoValueFromNative for #ident {}
+
+            impl magnus::rbs::RbsType for #ident {
+                fn rbs_type() -> String {
+                    String::from(#rbs)
+                }
+            }
+        };
+    };
+    Ok(tokens)
+}
+
+// RBS symbol literal for `name`, quoted if it isn't a plain identifier
+fn rbs_symbol(name: &str) -> String {
+    let ident = name.trim_end_matches(['?', '!', '=']);
+    let plain = name.len() - ident.len() <= 1
+        && ident
+            .chars()
+            .next()
+            .is_some_and(|c| c == '_' || c.is_alphabetic())
+        && ident.chars().all(|c| c == '_' || c.is_alphanumeric());
+    if plain {
+        format!(":{}", name)
+    } else {
+        format!(":{:?}", name)
+    }
+}
@@ -1,9 +1,10 @@
 use std::ffi::CString;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{
-    spanned::Spanned, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, LitStr,
+    ext::IdentExt, spanned::Spanned, Data, DataEnum, DataStruct, DeriveInput, Error, Field, Fields,
+    FieldsNamed, Index, LitStr, Member,
 };
 
 use crate::util;
@@ -16,6 +17,84 @@ pub fn expand(attrs: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    pub(crate) opaque_attr_reader: bool,
+    pub(crate) mark: bool,
+    pub(crate) compact: bool,
+    pub(crate) skip_deconstruct: bool,
+    pub(crate) skip_attr_reader: bool,
+}
+
+pub(crate) fn field_attrs(field: &Field) -> Result<FieldAttrs, Error> {
+    let mut field_attrs = FieldAttrs::default();
+    let attrs = match util::get_magnus_attribute(&field.attrs)? {
+        Some(v) => v,
+        None => return Ok(field_attrs),
+    };
+    attrs.parse_nested_meta(|meta| {
+        if meta.path.is_ident("opaque_attr_reader") {
+            field_attrs.opaque_attr_reader = true;
+            Ok(())
+        } else if meta.path.is_ident("mark") {
+            field_attrs.mark = true;
+            Ok(())
+        } else if meta.path.is_ident("compact") {
+            field_attrs.compact = true;
+            Ok(())
+        } else if meta.path.is_ident("skip_deconstruct") {
+            field_attrs.skip_deconstruct = true;
+            Ok(())
+        } else if meta.path.is_ident("skip_attr_reader") {
+            field_attrs.skip_attr_reader = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported attribute"))
+        }
+    })?;
+    Ok(field_attrs)
+}
+
+/// Marked fields of `fields`, as `(member, field index, compact)`.
+fn marked_fields(fields: &Fields) -> Result<Vec<(Member, usize, bool)>, Error> {
+    let mut marked = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let attrs = field_attrs(field)?;
+        if attrs.mark || attrs.compact {
+            let member = match field.ident {
+                Some(ref ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(i)),
+            };
+            marked.push((member, i, attrs.compact));
+        }
+    }
+    Ok(marked)
+}
+
+/// Body statements for `MarkFields::mark_fields`/`compact_fields` for the
+/// marked `fields`, with `access` producing an expression referencing the
+/// field.
+fn mark_compact_stmts<F>(
+    fields: &[(Member, usize, bool)],
+    access: F,
+) -> (Vec<TokenStream>, Vec<TokenStream>)
+where
+    F: Fn(&Member, usize) -> TokenStream,
+{
+    let mut mark = Vec::new();
+    let mut compact = Vec::new();
+    for (member, i, c) in fields {
+        let field = access(member, *i);
+        if *c {
+            mark.push(quote! { magnus::gc::Markable::mark_movable(#field, marker); });
+            compact.push(quote! { magnus::gc::Markable::compact(#field, compactor); });
+        } else {
+            mark.push(quote! { magnus::gc::Markable::mark(#field, marker); });
+        }
+    }
+    (mark, compact)
+}
+
 pub fn expand_derive_data_type_functions(input: DeriveInput) -> TokenStream {
     let ident = input.ident;
     let generics = input.generics;
@@ -24,6 +103,112 @@ pub fn expand_derive_data_type_functions(input: DeriveInput) -> TokenStream {
     }
 }
 
+/// Implementation of `MarkFields` for the fields with `#[magnus(mark)]` or
+/// `#[magnus(compact)]`, and whether any fields are marked or compacted.
+fn mark_fields_impl(input: &DeriveInput) -> Result<(TokenStream, bool, bool), Error> {
+    let ident = &input.ident;
+    let generics = &input.generics;
+
+    let (mark, compact) = match input.data {
+        Data::Struct(DataStruct { ref fields, .. }) => {
+            let (mark, compact) = mark_compact_stmts(&marked_fields(fields)?, |member, _| {
+                quote! { &self.#member }
+            });
+            (quote! { #(#mark)* }, quote! { #(#compact)* })
+        }
+        Data::Enum(DataEnum { ref variants, .. }) => {
+            let mut mark_arms = Vec::new();
+            let mut compact_arms = Vec::new();
+            for variant in variants {
+                let marked = marked_fields(&variant.fields)?;
+                if marked.is_empty() {
+                    continue;
+                }
+                let binding = |i: usize| Ident::new(&format!("__field{}", i), Span::call_site());
+                let (mark, compact) = mark_compact_stmts(&marked, |_, i| {
+                    let binding = binding(i);
+                    quote! { #binding }
+                });
+                let variant_ident = &variant.ident;
+                let pattern = match variant.fields {
+                    Fields::Named(_) => {
+                        let bindings = marked.iter().map(|(member, i, _)| {
+                            let binding = binding(*i);
+                            quote! { #member: #binding }
+                        });
+                        quote! { Self::#variant_ident { #(#bindings,)* .. } }
+                    }
+                    Fields::Unnamed(ref fields) => {
+                        let bindings = (0..fields.unnamed.len()).map(|i| {
+                            if marked.iter().any(|(_, m, _)| *m == i) {
+                                let binding = binding(i);
+                                quote! { #binding }
+                            } else {
+                                quote! { _ }
+                            }
+                        });
+                        quote! { Self::#variant_ident(#(#bindings),*) }
+                    }
+                    Fields::Unit => unreachable!(),
+                };
+                mark_arms.push(quote! { #pattern => { #(#mark)* } });
+                if !compact.is_empty() {
+                    compact_arms.push(quote! { #pattern => { #(#compact)* } });
+                }
+            }
+            let mark = if mark_arms.is_empty() {
+                quote! {}
+            } else {
+                quote! {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#mark_arms,)*
+                        _ => (),
+                    }
+                }
+            };
+            let compact = if compact_arms.is_empty() {
+                quote! {}
+            } else {
+                quote! {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#compact_arms,)*
+                        _ => (),
+                    }
+                }
+            };
+            (mark, compact)
+        }
+        Data::Union(_) => (quote! {}, quote! {}),
+    };
+
+    if mark.is_empty() {
+        return Ok((quote! {}, false, false));
+    }
+    let has_compact = !compact.is_empty();
+    let compact = if has_compact {
+        quote! {
+            fn compact_fields(&self, compactor: &magnus::gc::Compactor) {
+                #compact
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let tokens = quote! {
+        impl #generics magnus::typed_data::MarkFields for #ident #generics {
+            fn mark_fields(&self, marker: &magnus::gc::Marker) {
+                #mark
+            }
+
+            #compact
+        }
+    };
+    Ok((tokens, true, has_compact))
+}
+
 pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error> {
     let attrs = match util::get_magnus_attribute(&input.attrs)? {
         Some(v) => v,
@@ -31,6 +216,7 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
     };
 
     let mut class = None;
+    let mut superclass = None;
     let mut name = None;
     let mut mark = false;
     let mut size = false;
@@ -38,12 +224,18 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
     let mut free_immediately = false;
     let mut wb_protected = false;
     let mut frozen_shareable = false;
+    let mut mutable = false;
+    let mut deconstruct = false;
+    let mut attr_readers = false;
     let mut unsafe_generics = false;
 
     attrs.parse_nested_meta(|meta| {
         if meta.path.is_ident("class") {
             class = Some(meta.value()?.parse::<LitStr>()?.value());
             Ok(())
+        } else if meta.path.is_ident("superclass") {
+            superclass = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
         } else if meta.path.is_ident("name") {
             name = Some(meta.value()?.parse::<LitStr>()?.value());
             Ok(())
@@ -65,6 +257,15 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
         } else if meta.path.is_ident("frozen_shareable") {
             frozen_shareable = true;
             Ok(())
+        } else if meta.path.is_ident("mutable") {
+            mutable = true;
+            Ok(())
+        } else if meta.path.is_ident("deconstruct") {
+            deconstruct = true;
+            Ok(())
+        } else if meta.path.is_ident("attr_readers") {
+            attr_readers = true;
+            Ok(())
         } else if meta.path.is_ident("unsafe_generics") {
             unsafe_generics = true;
             Ok(())
@@ -159,22 +360,9 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
     }) = input.data
     {
         for field in named {
-            let attrs = match util::get_magnus_attribute(&field.attrs)? {
-                Some(v) => v,
-                None => continue,
-            };
-            let mut read = false;
-            attrs.parse_nested_meta(|meta| {
-                if meta.path.is_ident("opaque_attr_reader") {
-                    read = true;
-                    Ok(())
-                } else {
-                    Err(meta.error("unsupported attribute"))
-                }
-            })?;
             let ident = field.ident.as_ref().unwrap();
             let ty = &field.ty;
-            if read {
+            if field_attrs(field)?.opaque_attr_reader {
                 accessors.push(quote! {
                     #[inline]
                     fn #ident(&self) -> <#ty as magnus::value::OpaqueVal>::Val {
@@ -196,6 +384,100 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
         quote! {}
     };
 
+    let deconstruct_impl = if deconstruct {
+        let named = match input.data {
+            Data::Struct(DataStruct {
+                fields: Fields::Named(FieldsNamed { ref named, .. }),
+                ..
+            }) => named,
+            _ => {
+                return Err(Error::new(
+                    attrs.span(),
+                    "`deconstruct` requires a struct with named fields",
+                ))
+            }
+        };
+        let mut names = Vec::new();
+        let mut members = Vec::new();
+        for field in named {
+            if field_attrs(field)?.skip_deconstruct {
+                continue;
+            }
+            let member = field.ident.as_ref().unwrap();
+            names.push(LitStr::new(&member.unraw().to_string(), member.span()));
+            members.push(member);
+        }
+        let indices = 0..members.len();
+        quote! {
+            impl #generics magnus::typed_data::Deconstruct for #ident #generics {
+                const FIELDS: &'static [&'static str] = &[#(#names),*];
+
+                fn field(&self, ruby: &magnus::Ruby, index: usize) -> magnus::Value {
+                    match index {
+                        #(#indices => magnus::IntoValue::into_value_with(
+                            ::core::clone::Clone::clone(&self.#members),
+                            ruby,
+                        ),)*
+                        _ => magnus::IntoValue::into_value_with((), ruby),
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let attr_readers_impl = if attr_readers {
+        let named = match input.data {
+            Data::Struct(DataStruct {
+                fields: Fields::Named(FieldsNamed { ref named, .. }),
+                ..
+            }) => named,
+            _ => {
+                return Err(Error::new(
+                    attrs.span(),
+                    "`attr_readers` requires a struct with named fields",
+                ))
+            }
+        };
+        let mut names = Vec::new();
+        let mut members = Vec::new();
+        for field in named {
+            if field_attrs(field)?.skip_attr_reader {
+                continue;
+            }
+            let member = field.ident.as_ref().unwrap();
+            names.push(LitStr::new(&member.unraw().to_string(), member.span()));
+            members.push(member);
+        }
+        let indices = 0..members.len();
+        quote! {
+            impl #generics magnus::typed_data::AttrReaders for #ident #generics {
+                const ATTRS: &'static [&'static str] = &[#(#names),*];
+
+                fn attr(&self, ruby: &magnus::Ruby, index: usize) -> magnus::Value {
+                    match index {
+                        #(#indices => magnus::IntoValue::into_value_with(
+                            ::core::clone::Clone::clone(&self.#members),
+                            ruby,
+                        ),)*
+                        _ => magnus::IntoValue::into_value_with((), ruby),
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let (mark_fields_impl, mark_fields, compact_fields) = mark_fields_impl(&input)?;
+    if mutable && (mark || compact || mark_fields) {
+        return Err(Error::new(
+            attrs.span(),
+            "`mutable` can not be combined with `mark` or `compact`, as marking reads the data while a method may hold a mutable reference to it",
+        ));
+    }
+
     let mut builder = Vec::new();
     builder.push(quote! { magnus::typed_data::DataTypeBuilder::<#ident>::new(#name) });
     if mark {
@@ -207,6 +489,12 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
     if compact {
         builder.push(quote! { .compact() });
     }
+    if mark_fields {
+        builder.push(quote! { .mark_fields() });
+    }
+    if compact_fields {
+        builder.push(quote! { .compact_fields() });
+    }
     if free_immediately {
         builder.push(quote! { .free_immediately() });
     }
@@ -218,6 +506,16 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
     }
     builder.push(quote! { .build() });
     let builder = builder.into_iter().collect::<TokenStream>();
+    let mutable = if mutable {
+        quote! { const MUTABLE: bool = true; }
+    } else {
+        quote! {}
+    };
+    let superclass = match superclass {
+        Some(v) => quote! { const SUPERCLASS: Option<&'static str> = Some(#v); },
+        None => quote! {},
+    };
+
     let tokens = quote! {
         #accessor_impl
 
@@ -238,6 +536,27 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
             }
 
             #class_for
+
+            #mutable
+
+            const RBS_TYPE: &'static str = #class;
+        }
+
+        #mark_fields_impl
+
+        #deconstruct_impl
+
+        #attr_readers_impl
+
+        impl #generics magnus::hierarchy::DeclareClass for #ident #generics {
+            const PATH: &'static str = #class;
+            #superclass
+        }
+
+        impl #generics magnus::rbs::RbsType for #ident #generics {
+            fn rbs_type() -> String {
+                String::from(#class)
+            }
         }
     };
     Ok(tokens)
@@ -19,3 +19,23 @@ pub fn get_magnus_attribute(attrs: &[Attribute]) -> Result<Option<&Attribute>, E
     }
     Ok(Some(attrs[0]))
 }
+
+pub fn to_snake_case(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 4);
+    let mut chars = s.chars().peekable();
+    let mut prev_lower_or_digit = false;
+    while let Some(c) = chars.next() {
+        if c.is_uppercase() {
+            let next_lower = chars.peek().map(|n| n.is_lowercase()).unwrap_or(false);
+            if !out.is_empty() && !out.ends_with('_') && (prev_lower_or_digit || next_lower) {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+            prev_lower_or_digit = false;
+        } else {
+            out.push(c);
+            prev_lower_or_digit = c.is_lowercase() || c.is_ascii_digit();
+        }
+    }
+    out
+}
@@ -1068,6 +1068,10 @@ where
 ///
 /// `I` must implement `Iterator<Item = T>`, where `T` implements [`IntoValue`].
 ///
+/// The Enumerator can be given a size, returned by its `size` method, by
+/// creating it with
+/// [`enumeratorize_with_size`](ReprValue::enumeratorize_with_size).
+///
 /// # Examples
 ///
 /// ```
@@ -1099,10 +1103,43 @@ where
 /// }
 /// # Ruby::init(example).unwrap()
 /// ```
+///
+/// With a sized Enumerator:
+///
+/// ```
+/// use magnus::{
+///     EnumeratorSize, Error, Ruby, Value, block::Yield, method, prelude::*, rb_assert,
+/// };
+///
+/// fn count_to(ruby: &Ruby, rb_self: Value, n: usize) -> Yield<impl Iterator<Item = usize> + use<>> {
+///     if ruby.block_given() {
+///         Yield::Iter(1..=n)
+///     } else {
+///         Yield::Enumerator(rb_self.enumeratorize_with_size(
+///             "count_to",
+///             (n,),
+///             EnumeratorSize::Fixed(n),
+///         ))
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("count_to", method!(count_to, 1));
+///
+///     rb_assert!(ruby, "count_to(5).size == 5");
+///     rb_assert!(ruby, "count_to(5).each_slice(2).size == 3");
+///     rb_assert!(ruby, "count_to(5).to_a == [1, 2, 3, 4, 5]");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
 pub enum Yield<I> {
     /// Yields `I::Item` to given block.
     Iter(I),
-    /// Returns `Enumerator` from the method.
+    /// Returns `Enumerator` from the method, see
+    /// [`ReprValue::enumeratorize`] and
+    /// [`ReprValue::enumeratorize_with_size`].
     Enumerator(Enumerator),
 }
 
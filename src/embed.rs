@@ -94,6 +94,7 @@ pub unsafe fn setup() -> Cleanup {
                 if ruby_setup() != 0 {
                     panic!("Failed to setup Ruby");
                 };
+                crate::error::init_panic_backtrace();
                 Cleanup(Ruby::get_unchecked())
             }
             Err(true) => panic!("Ruby already initialized"),
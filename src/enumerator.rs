@@ -4,7 +4,8 @@ use rb_sys::VALUE;
 
 use crate::{
     Ruby,
-    error::Error,
+    block::Proc,
+    error::{Error, raise},
     into_value::IntoValue,
     object::Object,
     try_convert::TryConvert,
@@ -122,3 +123,76 @@ impl TryConvert for Enumerator {
         })
     }
 }
+
+/// The size of an [`Enumerator`] created with
+/// [`enumeratorize_with_size`](ReprValue::enumeratorize_with_size), as
+/// returned by Ruby's `Enumerator#size`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{EnumeratorSize, Error, Ruby, prelude::*, rb_assert, value::Opaque};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let ary = ruby.ary_from_vec(vec![1, 2, 3]);
+///
+///     let enumerator = ary.enumeratorize_with_size("each", (), EnumeratorSize::Fixed(3));
+///     rb_assert!(ruby, "enumerator.size == 3", enumerator);
+///
+///     let enumerator = ary.enumeratorize_with_size("cycle", (), EnumeratorSize::Infinite);
+///     rb_assert!(ruby, "enumerator.size == Float::INFINITY", enumerator);
+///
+///     let opaque_ary = Opaque::from(ary);
+///     let size = ruby.proc_from_fn(move |ruby, _args, _block| ruby.get_inner(opaque_ary).len());
+///     let enumerator = ary.enumeratorize_with_size("each", (), EnumeratorSize::Lazy(size));
+///     ary.push(4)?;
+///     rb_assert!(ruby, "enumerator.size == 4", enumerator);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub enum EnumeratorSize {
+    /// A fixed size.
+    Fixed(usize),
+    /// An unbounded size, reported as `Float::INFINITY`.
+    Infinite,
+    /// A size calculated by calling the `Proc` each time it is requested,
+    /// e.g. a Rust closure from [`Ruby::proc_from_fn`].
+    ///
+    /// The `Proc` should return an Integer, `Float::INFINITY`, or `nil` if
+    /// the size is unknown.
+    Lazy(Proc),
+}
+
+impl IntoValue for EnumeratorSize {
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        match self {
+            Self::Fixed(size) => size.into_value_with(handle),
+            Self::Infinite => handle.float_from_f64(f64::INFINITY).as_value(),
+            Self::Lazy(func) => func.as_value(),
+        }
+    }
+}
+
+/// Name of the hidden instance variable holding an [`EnumeratorSize`] on an
+/// `Enumerator`. Lacking a leading `@` it is not visible from Ruby.
+pub(crate) const SIZE_IVAR: &str = "__magnus_enumerator_size__";
+
+/// Size function for `rb_enumeratorize_with_size`, returning the value of
+/// [`SIZE_IVAR`], or the result of calling it if it is a `Proc`.
+pub(crate) unsafe extern "C" fn enumerator_size(_recv: VALUE, _args: VALUE, eobj: VALUE) -> VALUE {
+    let enumerator = unsafe { Enumerator::from_rb_value_unchecked(eobj) };
+    let size =
+        enumerator
+            .ivar_get::<_, Value>(SIZE_IVAR)
+            .and_then(|size| match Proc::from_value(size) {
+                Some(func) => func.call(()),
+                None => Ok(size),
+            });
+    match size {
+        Ok(size) => size.as_rb_value(),
+        Err(e) => raise(e),
+    }
+}
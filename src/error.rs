@@ -4,23 +4,40 @@
 
 use std::{
     any::Any,
+    backtrace::Backtrace,
     borrow::Cow,
+    cell::RefCell,
     ffi::{CString, c_int},
-    fmt,
+    fmt, io,
     mem::transmute,
+    num::{ParseFloatError, ParseIntError, TryFromIntError},
+    panic::{self, AssertUnwindSafe},
+    str::Utf8Error,
+    string::FromUtf8Error,
+    sync::{
+        Once,
+        atomic::{AtomicU8, Ordering},
+    },
 };
 
 use rb_sys::{
-    VALUE, rb_bug, rb_ensure, rb_errinfo, rb_exc_fatal, rb_exc_raise, rb_iter_break_value,
-    rb_jump_tag, rb_protect, rb_set_errinfo, rb_warning, ruby_special_consts,
+    VALUE, rb_bug, rb_catch_obj, rb_category_warn, rb_ensure, rb_errinfo, rb_errno, rb_exc_fatal,
+    rb_exc_raise, rb_gv_get, rb_iter_break_value, rb_jump_tag, rb_make_backtrace, rb_protect,
+    rb_rescue2, rb_set_errinfo, rb_syserr_new_str, rb_throw_obj, rb_warn, rb_warning,
+    rb_warning_category_t, ruby_special_consts,
 };
 
 use crate::{
     ExceptionClass, Ruby,
-    class::Class,
+    class::{Class, RClass},
     exception::Exception,
-    into_value::IntoValue,
-    module::Module,
+    into_value::{IntoValue, KwArgs},
+    module::{Attr, Module, RModule},
+    object::Object,
+    r_array::RArray,
+    r_typed_data::RTypedData,
+    try_convert::TryConvert,
+    typed_data::{TypedData, borrow},
     value::{ReprValue, Value, private::ReprValue as _},
 };
 
@@ -89,11 +106,665 @@ impl Ruby {
         .unwrap_err()
     }
 
+    /// Create a new error that will `throw` `value` to the enclosing `catch`
+    /// for `tag` when returned to Ruby.
+    ///
+    /// If there is no `catch` for `tag` an `UncaughtThrowError` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, function, rb_assert};
+    ///
+    /// fn find_even(ruby: &Ruby, values: Vec<i64>) -> Result<(), Error> {
+    ///     for i in values {
+    ///         if i % 2 == 0 {
+    ///             return Err(ruby.throw(ruby.to_symbol("found"), i));
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("find_even", function!(find_even, 1));
+    ///
+    ///     rb_assert!(ruby, "catch(:found) { find_even([1, 3, 4, 5]) } == 4");
+    ///     rb_assert!(ruby, "(find_even([2]) rescue $!).is_a?(UncaughtThrowError)");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn throw<T, U>(&self, tag: T, value: U) -> Error
+    where
+        T: IntoValue,
+        U: IntoValue,
+    {
+        let tag = self.into_value(tag);
+        let value = self.into_value(value);
+        protect(|| {
+            unsafe { rb_throw_obj(tag.as_rb_value(), value.as_rb_value()) };
+            // we never get here, but this is needed to satisfy the type system
+            #[allow(unreachable_code)]
+            self.qnil()
+        })
+        .unwrap_err()
+    }
+
+    /// Call `func`, catching any `throw` to `tag`.
+    ///
+    /// Returns [`Caught::Returned`] with the result of `func` if it completes
+    /// normally, or [`Caught::Thrown`] with the thrown value if it (or any
+    /// Ruby code it calls) throws to `tag`. Throws to other tags, and other
+    /// errors, are returned as errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, Value, error::Caught, rb_assert};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let tag = ruby.to_symbol("done");
+    ///
+    ///     let res = ruby.catch(tag, |ruby| ruby.eval::<Value>("throw :done, 42"))?;
+    ///     let Caught::Thrown(val) = res else {
+    ///         panic!("expected throw")
+    ///     };
+    ///     rb_assert!(ruby, "val == 42", val);
+    ///
+    ///     let res = ruby.catch(tag, |ruby| ruby.eval::<i64>("1 + 2"))?;
+    ///     assert!(matches!(res, Caught::Returned(3)));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn catch<T, F, R>(&self, tag: T, func: F) -> Result<Caught<R>, Error>
+    where
+        T: IntoValue,
+        F: FnOnce(&Ruby) -> Result<R, Error>,
+    {
+        unsafe extern "C" fn call<F, R>(
+            _tag: VALUE,
+            callback_arg: VALUE,
+            _argc: c_int,
+            _argv: *const VALUE,
+            _blockarg: VALUE,
+        ) -> VALUE
+        where
+            F: FnOnce(&Ruby) -> Result<R, Error>,
+        {
+            unsafe {
+                let (func, res) = &mut *(callback_arg as *mut (Option<F>, Option<R>));
+                let func = func.take().unwrap();
+                let ruby = Ruby::get_unchecked();
+                match panic::catch_unwind(AssertUnwindSafe(|| func(&ruby))) {
+                    Ok(Ok(val)) => {
+                        *res = Some(val);
+                        ruby.qnil().as_rb_value()
+                    }
+                    Ok(Err(e)) => raise(e),
+                    Err(e) => raise(Error::from_panic(e)),
+                }
+            }
+        }
+
+        let tag = self.into_value(tag);
+        let mut data = (Some(func), None::<R>);
+        let thrown = protect(|| unsafe {
+            Value::new(rb_catch_obj(
+                tag.as_rb_value(),
+                Some(call::<F, R>),
+                &mut data as *mut (Option<F>, Option<R>) as VALUE,
+            ))
+        })?;
+        Ok(match data.1 {
+            Some(val) => Caught::Returned(val),
+            None => Caught::Thrown(thrown),
+        })
+    }
+
+    /// Call `func`, then call `cleanup`, even if `func` returns an error or
+    /// panics.
+    ///
+    /// This is the equivalent of Ruby's `begin`/`ensure`. `func` is run with
+    /// `rb_ensure`, so `cleanup` will also run if Ruby unwinds from a raw
+    /// call made with [`rb_sys`](https://docs.rs/rb-sys). Errors such as
+    /// `throw`, `break`, or other non-local exits are returned after
+    /// `cleanup` has run, and resumed when returned to Ruby.
+    ///
+    /// If `cleanup` returns an error, that error is returned in place of the
+    /// result of `func`, as when an `ensure` block raises an exception. If
+    /// either closure panics the panic is resumed after `cleanup` has run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, Value, prelude::*};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let log = ruby.ary_new();
+    ///
+    ///     let res = ruby.ensure(
+    ///         |ruby| ruby.eval::<Value>("raise 'bang'"),
+    ///         |_ruby| log.push("cleaned up"),
+    ///     );
+    ///
+    ///     assert!(res.unwrap_err().is_kind_of(ruby.exception_runtime_error()));
+    ///     assert_eq!(log.to_vec::<String>()?, vec!["cleaned up"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn ensure<F, C, T>(&self, func: F, cleanup: C) -> Result<T, Error>
+    where
+        F: FnOnce(&Ruby) -> Result<T, Error>,
+        C: FnOnce(&Ruby) -> Result<(), Error>,
+    {
+        let mut res = None;
+        let mut cleanup_res = None;
+        ensure(
+            || {
+                res = Some(panic::catch_unwind(AssertUnwindSafe(|| func(self))));
+                self.qnil()
+            },
+            || cleanup_res = Some(panic::catch_unwind(AssertUnwindSafe(|| cleanup(self)))),
+        );
+        match (res, cleanup_res) {
+            (_, Some(Err(e))) | (Some(Err(e)), _) => panic::resume_unwind(e),
+            (_, Some(Ok(Err(e)))) => Err(e),
+            (Some(Ok(res)), Some(Ok(Ok(())))) => res,
+            _ => unreachable!("rb_ensure returned without calling both functions"),
+        }
+    }
+
+    /// Call `func`, and if it returns an exception that is an instance of one
+    /// of `classes`, call `handler` with that exception.
+    ///
+    /// This is the equivalent of Ruby's `begin`/`rescue`. If `classes` is
+    /// empty, `StandardError` is rescued, as with a bare `rescue`. `func` is
+    /// run with `rb_rescue2`, so exceptions raised by a raw call made with
+    /// [`rb_sys`](https://docs.rs/rb-sys) are also rescued, and `$!` is set
+    /// to the exception while `handler` runs. Other errors, including
+    /// non-local exits such as `throw` and `break`, are returned unchanged.
+    ///
+    /// If either closure panics the panic is resumed once Ruby has finished
+    /// unwinding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, prelude::*};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let res: i64 = ruby.rescue(
+    ///         &[ruby.exception_zero_div_error()],
+    ///         |ruby| ruby.eval("1 / 0"),
+    ///         |_ruby, _e| Ok(0),
+    ///     )?;
+    ///     assert_eq!(res, 0);
+    ///
+    ///     let res: Result<i64, Error> = ruby.rescue(
+    ///         &[ruby.exception_zero_div_error()],
+    ///         |ruby| ruby.eval("Integer('one')"),
+    ///         |_ruby, _e| Ok(0),
+    ///     );
+    ///     assert!(res.unwrap_err().is_kind_of(ruby.exception_arg_error()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn rescue<F, H, T>(
+        &self,
+        classes: &[ExceptionClass],
+        func: F,
+        handler: H,
+    ) -> Result<T, Error>
+    where
+        F: FnOnce(&Ruby) -> Result<T, Error>,
+        H: FnOnce(&Ruby, Exception) -> Result<T, Error>,
+    {
+        struct Data<'a, F, H, T> {
+            classes: &'a [ExceptionClass],
+            func: Option<F>,
+            handler: Option<H>,
+            res: Option<T>,
+            panic: Option<Box<dyn Any + Send + 'static>>,
+        }
+
+        unsafe extern "C" fn call_func<F, H, T>(arg: VALUE) -> VALUE
+        where
+            F: FnOnce(&Ruby) -> Result<T, Error>,
+        {
+            unsafe {
+                let data = &mut *(arg as *mut Data<F, H, T>);
+                let func = data.func.take().unwrap();
+                let ruby = Ruby::get_unchecked();
+                match panic::catch_unwind(AssertUnwindSafe(|| func(&ruby))) {
+                    Ok(Ok(val)) => data.res = Some(val),
+                    Ok(Err(e)) => raise(e),
+                    Err(e) => data.panic = Some(e),
+                }
+                ruby.qnil().as_rb_value()
+            }
+        }
+
+        unsafe extern "C" fn call_handler<F, H, T>(arg: VALUE, exc: VALUE) -> VALUE
+        where
+            H: FnOnce(&Ruby, Exception) -> Result<T, Error>,
+        {
+            unsafe {
+                let data = &mut *(arg as *mut Data<F, H, T>);
+                let ruby = Ruby::get_unchecked();
+                let exc = Exception::from_rb_value_unchecked(exc);
+                let rescued = if data.classes.is_empty() {
+                    exc.is_kind_of(ruby.exception_standard_error())
+                } else {
+                    data.classes.iter().any(|&class| exc.is_kind_of(class))
+                };
+                if !rescued {
+                    rb_exc_raise(exc.as_rb_value());
+                }
+                let handler = data.handler.take().unwrap();
+                match panic::catch_unwind(AssertUnwindSafe(|| handler(&ruby, exc))) {
+                    Ok(Ok(val)) => data.res = Some(val),
+                    Ok(Err(e)) => raise(e),
+                    Err(e) => data.panic = Some(e),
+                }
+                ruby.qnil().as_rb_value()
+            }
+        }
+
+        let mut data = Data {
+            classes,
+            func: Some(func),
+            handler: Some(handler),
+            res: None,
+            panic: None,
+        };
+        // rb_rescue2 takes a variadic list of classes, so rescue everything
+        // and check against `classes` in the handler, re-raising if there's
+        // no match
+        let res = protect(|| unsafe {
+            Value::new(rb_rescue2(
+                Some(call_func::<F, H, T>),
+                &mut data as *mut Data<F, H, T> as VALUE,
+                Some(call_handler::<F, H, T>),
+                &mut data as *mut Data<F, H, T> as VALUE,
+                self.exception_exception().as_rb_value(),
+                0 as VALUE,
+            ))
+        });
+        if let Some(e) = data.panic {
+            panic::resume_unwind(e);
+        }
+        res?;
+        Ok(data
+            .res
+            .expect("rb_rescue2 returned without a result from func or handler"))
+    }
+
     /// Outputs `s` to Ruby's stderr if Ruby is configured to output warnings.
     pub fn warning(&self, s: &str) {
         let s = CString::new(s).unwrap();
         unsafe { rb_warning(s.as_ptr()) };
     }
+
+    /// Outputs `s` to Ruby's stderr as a warning, unless warnings are
+    /// disabled (`$VERBOSE` is `nil`).
+    ///
+    /// Unlike [`Ruby::warning`] this does not require Ruby to be in verbose
+    /// mode. The warning is output with `Warning.warn`, so returns an error
+    /// if that raises.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.warn("something looks wrong")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn warn(&self, s: &str) -> Result<(), Error> {
+        let s = CString::new(s).unwrap();
+        protect(|| {
+            unsafe { rb_warn(c"%s".as_ptr(), s.as_ptr()) };
+            self.qnil()
+        })?;
+        Ok(())
+    }
+
+    /// Outputs `s` to Ruby's stderr as a warning in the `deprecated`
+    /// category.
+    ///
+    /// The warning is only output if enabled with `Warning[:deprecated] =
+    /// true` (or the `-W:deprecated` command line option). See also
+    /// [`Ruby::warning_category_enabled`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, error::WarningCategory};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     if ruby.warning_category_enabled(WarningCategory::Deprecated)? {
+    ///         ruby.warn_deprecated(&format!("{} is deprecated, use {}", "old", "new"))?;
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn warn_deprecated(&self, s: &str) -> Result<(), Error> {
+        self.category_warn(WarningCategory::Deprecated, s)
+    }
+
+    /// Outputs `s` to Ruby's stderr as a warning in the `experimental`
+    /// category.
+    ///
+    /// The warning is output unless disabled with `Warning[:experimental] =
+    /// false` (or the `-W:no-experimental` command line option). See also
+    /// [`Ruby::warning_category_enabled`].
+    pub fn warn_experimental(&self, s: &str) -> Result<(), Error> {
+        self.category_warn(WarningCategory::Experimental, s)
+    }
+
+    /// Outputs `s` to Ruby's stderr as a warning in the `performance`
+    /// category.
+    ///
+    /// The warning is only output if enabled with `Warning[:performance] =
+    /// true` (or the `-W:performance` command line option). See also
+    /// [`Ruby::warning_category_enabled`].
+    #[cfg(any(ruby_gte_3_3, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_3)))]
+    pub fn warn_performance(&self, s: &str) -> Result<(), Error> {
+        self.category_warn(WarningCategory::Performance, s)
+    }
+
+    fn category_warn(&self, category: WarningCategory, s: &str) -> Result<(), Error> {
+        let s = CString::new(s).unwrap();
+        protect(|| {
+            unsafe { rb_category_warn(category.as_rb(), c"%s".as_ptr(), s.as_ptr()) };
+            self.qnil()
+        })?;
+        Ok(())
+    }
+
+    /// Outputs `s` to Ruby's stderr as a warning, prefixed with the location
+    /// of a Ruby caller, as with Ruby's `warn(s, uplevel: n)`.
+    ///
+    /// An `uplevel` of `0` is the location of the Ruby code that called the
+    /// current method. If `category` is given the warning is only output if
+    /// that category is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, Value, error::WarningCategory, function};
+    ///
+    /// fn old_method(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.warn_uplevel(
+    ///         "old_method is deprecated",
+    ///         0,
+    ///         Some(WarningCategory::Deprecated),
+    ///     )
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("old_method", function!(old_method, 0));
+    ///     // outputs "eval:1: warning: old_method is deprecated" if
+    ///     // deprecation warnings are enabled
+    ///     let _: Value = ruby.eval("old_method")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn warn_uplevel(
+        &self,
+        s: &str,
+        uplevel: usize,
+        category: Option<WarningCategory>,
+    ) -> Result<(), Error> {
+        let kw = self.hash_new();
+        kw.aset(self.to_symbol("uplevel"), uplevel)?;
+        if let Some(category) = category {
+            kw.aset(self.to_symbol("category"), self.to_symbol(category.name()))?;
+        }
+        self.module_kernel()
+            .funcall::<_, _, Value>("warn", (s, KwArgs(kw)))?;
+        Ok(())
+    }
+
+    /// Returns whether warnings in `category` will be output.
+    ///
+    /// This checks both `Warning[category]` and that warnings are not
+    /// disabled (`$VERBOSE` is not `nil`), so can be used to avoid building
+    /// a warning message that would be discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, Value, error::WarningCategory};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let _: Value = ruby.eval("Warning[:deprecated] = true")?;
+    ///     assert!(ruby.warning_category_enabled(WarningCategory::Deprecated)?);
+    ///
+    ///     let _: Value = ruby.eval("Warning[:deprecated] = false")?;
+    ///     assert!(!ruby.warning_category_enabled(WarningCategory::Deprecated)?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn warning_category_enabled(&self, category: WarningCategory) -> Result<bool, Error> {
+        let verbose = protect(|| unsafe { Value::new(rb_gv_get(c"$VERBOSE".as_ptr())) })?;
+        if verbose.is_nil() {
+            return Ok(false);
+        }
+        let warning: RModule = self.class_object().const_get("Warning")?;
+        warning.funcall("[]", (self.to_symbol(category.name()),))
+    }
+
+    /// Set how much detail about a Rust panic is attached to the `fatal`
+    /// exception raised when magnus catches the panic.
+    ///
+    /// With [`PanicBacktrace::Location`] or [`PanicBacktrace::Full`] a panic
+    /// hook is installed (calling any previously installed hook) to record
+    /// the panic's location and backtrace, which are then prepended to the
+    /// exception's Ruby backtrace.
+    ///
+    /// The initial setting is read from the `MAGNUS_PANIC_BACKTRACE`
+    /// environment variable when the extension is initialised, see
+    /// [`PanicBacktrace`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, error::PanicBacktrace};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.set_panic_backtrace(PanicBacktrace::Location);
+    ///     assert_eq!(ruby.panic_backtrace(), PanicBacktrace::Location);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn set_panic_backtrace(&self, setting: PanicBacktrace) {
+        setting.set();
+    }
+
+    /// Returns the current [`PanicBacktrace`] setting.
+    ///
+    /// See [`Ruby::set_panic_backtrace`].
+    pub fn panic_backtrace(&self) -> PanicBacktrace {
+        PanicBacktrace::get()
+    }
+
+    /// Create a new error, wrapping `payload` as an exception with `msg`.
+    ///
+    /// `T`'s class must be a subclass of `Exception`, such as one defined
+    /// with [`Ruby::define_error`]. The payload can be retrieved from the
+    /// error with [`Error::downcast_ref`], and its fields exposed to Ruby with
+    /// the `attr_readers` attribute of [`#[wrap]`](macro@crate::wrap), see
+    /// [`AttrReaders`](crate::typed_data::AttrReaders).
+    ///
+    /// As with other wrapped types, instances of the class can only be created
+    /// from Rust.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, function, prelude::*, rb_assert, typed_data::AttrReaders};
+    ///
+    /// #[magnus::wrap(class = "HttpError", free_immediately, attr_readers)]
+    /// struct HttpError {
+    ///     status: u16,
+    ///     body: String,
+    /// }
+    ///
+    /// fn fetch(ruby: &Ruby) -> Result<(), Error> {
+    ///     let payload = HttpError {
+    ///         status: 404,
+    ///         body: String::from("not found"),
+    ///     };
+    ///     Err(ruby.error_with_payload(payload, "request failed"))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = ruby.define_error("HttpError", ruby.exception_standard_error())?;
+    ///     HttpError::define_attr_readers(class.as_r_class())?;
+    ///     ruby.define_global_function("fetch", function!(fetch, 0));
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"
+    ///         begin
+    ///           fetch
+    ///         rescue HttpError => e
+    ///           e.message == "request failed" && e.status == 404 && e.body == "not found"
+    ///         end
+    ///         "#
+    ///     );
+    ///
+    ///     let err = fetch(ruby).unwrap_err();
+    ///     assert_eq!(err.downcast_ref::<HttpError>().unwrap().status, 404);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn error_with_payload<T, M>(&self, payload: T, msg: M) -> Error
+    where
+        T: TypedData,
+        M: Into<Cow<'static, str>>,
+    {
+        let class = T::class(self);
+        if !class.is_inherited(self.exception_exception().as_r_class()) {
+            return Error::new(
+                self.exception_type_error(),
+                format!("{} is not a subclass of Exception", class),
+            );
+        }
+        let msg: Cow<'static, str> = msg.into();
+        let e = unsafe { Exception::from_value_unchecked(self.wrap(payload).as_value()) };
+        match e.funcall::<_, _, Value>("initialize", (self.str_new(&msg),)) {
+            Ok(_) => Error::from(e),
+            Err(e) => e,
+        }
+    }
+
+    /// Create a new `Error` for the operating system error number `errno`,
+    /// such as the value of [`std::io::Error::raw_os_error`].
+    ///
+    /// The exception will be an instance of the matching `Errno::E*` class,
+    /// or `SystemCallError` if `errno` is unknown to Ruby. Its message is the
+    /// system's description of the error, followed by `msg` if given, e.g.
+    /// `"Connection refused - connect(2)"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, function, rb_assert};
+    ///
+    /// fn load_config(ruby: &Ruby) -> Result<(), Error> {
+    ///     // 2 is ENOENT
+    ///     Err(ruby.error_from_errno(2, Some("config.toml")))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("load_config", function!(load_config, 0));
+    ///
+    ///     rb_assert!(ruby, "(load_config rescue $!).is_a?(Errno::ENOENT)");
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"(load_config rescue $!).message == "No such file or directory - config.toml""#
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn error_from_errno(&self, errno: i32, msg: Option<&str>) -> Error {
+        let msg = match msg {
+            Some(msg) => self.str_new(msg).as_value(),
+            None => self.qnil().as_value(),
+        };
+        let res = protect(|| unsafe {
+            Exception::from_rb_value_unchecked(rb_syserr_new_str(errno, msg.as_rb_value()))
+        });
+        match res {
+            Ok(e) => Error::from(e),
+            Err(e) => e,
+        }
+    }
+
+    /// Create a new `Error` for the last operating system error on the
+    /// current thread, as reported by `errno`.
+    ///
+    /// This should be called immediately after the failing system call, as
+    /// any further calls may overwrite `errno`. See
+    /// [`Ruby::error_from_errno`] for the class and message of the exception.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs::File;
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn open(ruby: &Ruby, path: &str) -> Result<File, Error> {
+    ///     // stands in for an FFI call that sets `errno` on failure
+    ///     File::open(path).map_err(|_| ruby.error_sys_fail(Some(path)))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let err = open(ruby, "/does/not/exist").unwrap_err();
+    ///     // Rust's standard library only sets `errno` on Unix
+    ///     # #[cfg(unix)]
+    ///     assert!(err.is_kind_of(ruby.errno_class("ENOENT")?));
+    ///     # #[cfg(unix)]
+    ///     assert_eq!(err.errno(), Some(2));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn error_sys_fail(&self, msg: Option<&str>) -> Error {
+        // read errno before touching Ruby, which may overwrite it. This is
+        // what `rb_sys_fail_str` does, without raising. Ruby's accessor is
+        // used as on Windows `last_os_error` is `GetLastError`, not `errno`
+        let errno = unsafe { rb_errno() };
+        self.error_from_errno(errno, msg)
+    }
 }
 
 /// Shorthand for `std::result::Result<T, magnus::Error>`.
@@ -156,6 +827,225 @@ impl Error {
         Self(ErrorType::Error(class, msg.into()))
     }
 
+    /// Create a new `Error` that can be raised as a Ruby `Exception` with
+    /// `msg`, and `cause` as the exception's cause.
+    ///
+    /// Unlike [`Error::new`] the `Exception` is created immediately.
+    ///
+    /// Ruby normally sets the cause when the exception is raised, to the
+    /// exception currently being handled, if any. Passing `None` for `cause`
+    /// will suppress this, as with `raise ..., cause: nil`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby, function, prelude::*};
+    ///
+    /// fn load(ruby: &Ruby, path: String) -> Result<(), Error> {
+    ///     let cause: Exception = ruby.eval(r#"Errno::ENOENT.new("config.toml")"#)?;
+    ///     Err(Error::new_with_cause(
+    ///         ruby.exception_runtime_error(),
+    ///         format!("failed to load {}", path),
+    ///         Some(cause),
+    ///     ))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("load_config", function!(load, 1));
+    ///
+    ///     let e: Exception = ruby.eval(r#"begin; load_config("config.toml"); rescue => e; e; end"#)?;
+    ///     assert_eq!(e.message()?, "failed to load config.toml");
+    ///     assert!(e.cause()?.unwrap().is_kind_of(ruby.exception_system_call_error()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn new_with_cause<T>(class: ExceptionClass, msg: T, cause: Option<Exception>) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        let e = Self::new(class, msg).exception();
+        match e.init_cause(cause) {
+            Ok(()) => Self::from(e),
+            Err(e) => e,
+        }
+    }
+
+    /// Create a new `Error` from the Rust error `err`, preserving the chain
+    /// of errors returned by [`source`](std::error::Error::source) as a chain
+    /// of Ruby exceptions linked through their cause.
+    ///
+    /// Each exception is an instance of `class`, with the message from the
+    /// corresponding error's [`Display`](fmt::Display) implementation. See
+    /// [`Error::from_error_chain_with`] to choose the class for each error.
+    ///
+    /// Types such as `anyhow::Error` can be passed by dereferencing them,
+    /// e.g. `Error::from_error_chain(class, &*err)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{fmt, num::ParseIntError};
+    ///
+    /// use magnus::{Error, Exception, Ruby, function};
+    ///
+    /// #[derive(Debug)]
+    /// struct ConfigError(ParseIntError);
+    ///
+    /// impl fmt::Display for ConfigError {
+    ///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         write!(f, "invalid port in config")
+    ///     }
+    /// }
+    ///
+    /// impl std::error::Error for ConfigError {
+    ///     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    ///         Some(&self.0)
+    ///     }
+    /// }
+    ///
+    /// fn load(ruby: &Ruby, port: String) -> Result<u16, Error> {
+    ///     port.parse()
+    ///         .map_err(ConfigError)
+    ///         .map_err(|e| Error::from_error_chain(ruby.exception_runtime_error(), &e))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("load_config", function!(load, 1));
+    ///
+    ///     let e: Exception = ruby.eval(r#"begin; load_config("http"); rescue => e; e; end"#)?;
+    ///     assert_eq!(e.message()?, "invalid port in config");
+    ///     assert_eq!(e.cause()?.unwrap().message()?, "invalid digit found in string");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn from_error_chain(
+        class: ExceptionClass,
+        err: &(dyn std::error::Error + 'static),
+    ) -> Self {
+        Self::from_error_chain_with(err, |_| class)
+    }
+
+    /// Create a new `Error` from the Rust error `err`, preserving the chain
+    /// of errors returned by [`source`](std::error::Error::source) as a chain
+    /// of Ruby exceptions linked through their cause.
+    ///
+    /// `class` is called with each error in the chain, and returns the class
+    /// for its exception. The error's type can be checked with `is` or
+    /// `downcast_ref`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{fmt, num::ParseIntError};
+    ///
+    /// use magnus::{Error, Exception, Ruby, function, prelude::*};
+    ///
+    /// #[derive(Debug)]
+    /// struct ConfigError(ParseIntError);
+    ///
+    /// impl fmt::Display for ConfigError {
+    ///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         write!(f, "invalid port in config")
+    ///     }
+    /// }
+    ///
+    /// impl std::error::Error for ConfigError {
+    ///     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    ///         Some(&self.0)
+    ///     }
+    /// }
+    ///
+    /// fn load(ruby: &Ruby, port: String) -> Result<u16, Error> {
+    ///     port.parse().map_err(ConfigError).map_err(|e| {
+    ///         Error::from_error_chain_with(&e, |err| {
+    ///             if err.is::<ParseIntError>() {
+    ///                 ruby.exception_arg_error()
+    ///             } else {
+    ///                 ruby.exception_runtime_error()
+    ///             }
+    ///         })
+    ///     })
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("load_config", function!(load, 1));
+    ///
+    ///     let e: Exception = ruby.eval(r#"begin; load_config("http"); rescue => e; e; end"#)?;
+    ///     assert!(e.is_kind_of(ruby.exception_runtime_error()));
+    ///     assert!(e.cause()?.unwrap().is_kind_of(ruby.exception_arg_error()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn from_error_chain_with<F>(err: &(dyn std::error::Error + 'static), mut class: F) -> Self
+    where
+        F: FnMut(&(dyn std::error::Error + 'static)) -> ExceptionClass,
+    {
+        fn build(
+            err: &(dyn std::error::Error + 'static),
+            class: &mut dyn FnMut(&(dyn std::error::Error + 'static)) -> ExceptionClass,
+        ) -> Result<Exception, Error> {
+            let cause = err
+                .source()
+                .map(|source| build(source, class))
+                .transpose()?;
+            let e = class(err).new_instance((err.to_string(),))?;
+            if cause.is_some() {
+                e.init_cause(cause)?;
+            }
+            Ok(e)
+        }
+
+        match build(err, &mut class) {
+            Ok(e) => Self::from(e),
+            Err(e) => e,
+        }
+    }
+
+    /// Returns the operating system error number carried by `self`, if
+    /// `self` is a `SystemCallError` (including `Errno::E*` exceptions).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, Value};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let err = ruby.eval::<Value>("raise Errno::ENOENT").unwrap_err();
+    ///     assert_eq!(err.errno(), Some(2));
+    ///
+    ///     let err = ruby.eval::<Value>("raise 'oops'").unwrap_err();
+    ///     assert_eq!(err.errno(), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn errno(&self) -> Option<i32> {
+        match self.0 {
+            ErrorType::Jump(_) => None,
+            ErrorType::Error(class, _) => {
+                let ruby = Ruby::get_with(class);
+                if !class.is_inherited(ruby.exception_system_call_error()) {
+                    return None;
+                }
+                class.const_get("Errno").ok()
+            }
+            ErrorType::Exception(e) => {
+                let ruby = Ruby::get_with(e);
+                if !e.is_kind_of(ruby.exception_system_call_error()) {
+                    return None;
+                }
+                e.funcall("errno", ()).ok()
+            }
+        }
+    }
+
     pub(crate) fn from_tag(tag: Tag) -> Self {
         Self(ErrorType::Jump(tag))
     }
@@ -289,6 +1179,24 @@ impl Error {
         }
     }
 
+    /// Returns a reference to the Rust payload of `self`, if `self` is an
+    /// exception wrapping a `T`.
+    ///
+    /// See [`Ruby::error_with_payload`].
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: TypedData,
+    {
+        match self.0 {
+            ErrorType::Exception(e) => unsafe {
+                RTypedData::from_value(e.as_value())?
+                    .get_unconstrained()
+                    .ok()
+            },
+            _ => None,
+        }
+    }
+
     /// Create an `Error` from the error value of [`std::panic::catch_unwind`].
     ///
     /// The Ruby Exception will be `fatal`, terminating the Ruby process, but
@@ -301,10 +1209,22 @@ impl Error {
         } else {
             "panic".into()
         };
-        Self(ErrorType::Error(
-            unsafe { Ruby::get_unchecked().exception_fatal() },
-            msg,
-        ))
+        let ruby = unsafe { Ruby::get_unchecked() };
+        // always take the details so they can't be picked up by a later
+        // panic, but only use them if they're still wanted
+        let details = PanicDetails::take().filter(|_| PanicBacktrace::get() != PanicBacktrace::Off);
+        let Some(details) = details else {
+            return Self(ErrorType::Error(ruby.exception_fatal(), msg));
+        };
+        let e = Self::new(ruby.exception_fatal(), msg).exception();
+        let backtrace = ruby.ary_from_vec(details.frames());
+        let caller = unsafe { RArray::from_rb_value_unchecked(rb_make_backtrace()) };
+        // if either of these fail we still have the message, which is the
+        // more important part
+        let _ = backtrace
+            .concat(caller)
+            .and_then(|_| e.set_backtrace(backtrace));
+        Self::from(e)
     }
 }
 
@@ -325,6 +1245,51 @@ impl From<Exception> for Error {
 }
 
 /// Conversions into [`Error`].
+///
+/// As well as magnus' own error types, this is implemented for common errors
+/// from the standard library, so functions bound as Ruby methods can return
+/// them directly:
+///
+/// | Rust                                   | Ruby                                                |
+/// |----------------------------------------|-----------------------------------------------------|
+/// | [`std::io::Error`]                     | `Errno::E*` for OS errors and common kinds¹         |
+/// | [`ParseIntError`], [`ParseFloatError`] | `ArgumentError`                                     |
+/// | [`TryFromIntError`]                    | `RangeError`                                        |
+/// | [`Utf8Error`], [`FromUtf8Error`]       | `EncodingError`                                     |
+///
+/// ¹ On Unix an [`io::Error`] with an OS error code becomes the matching
+/// `Errno::E*` exception. Otherwise the [`io::ErrorKind`] is mapped, e.g.
+/// `NotFound` to `Errno::ENOENT` and `UnexpectedEof` to `EOFError`, keeping
+/// the error's message. Other kinds become an `IOError`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, Ruby, function, rb_assert};
+///
+/// fn read_config(path: String) -> Result<String, std::io::Error> {
+///     std::fs::read_to_string(path)
+/// }
+///
+/// fn parse_port(s: String) -> Result<u16, std::num::ParseIntError> {
+///     s.parse()
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("read_config", function!(read_config, 1));
+///     ruby.define_global_function("parse_port", function!(parse_port, 1));
+///
+///     rb_assert!(
+///         ruby,
+///         r#"(read_config("/does/not/exist") rescue $!).is_a?(Errno::ENOENT)"#
+///     );
+///     rb_assert!(ruby, r#"parse_port("8080") == 8080"#);
+///     rb_assert!(ruby, r#"(parse_port("http") rescue $!).is_a?(ArgumentError)"#);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
 pub trait IntoError {
     /// Convert `self` into [`Error`].
     fn into_error(self, ruby: &Ruby) -> Error;
@@ -337,6 +1302,171 @@ impl IntoError for Error {
     }
 }
 
+/// How much detail about a Rust panic to attach to the `fatal` exception
+/// raised when magnus catches the panic.
+///
+/// See [`Ruby::set_panic_backtrace`].
+///
+/// The initial setting is read from the `MAGNUS_PANIC_BACKTRACE` environment
+/// variable, which can be set to `location`, or `1`/`full`. Any other value
+/// is treated as [`Off`](PanicBacktrace::Off).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PanicBacktrace {
+    /// Only the panic message is attached, as the exception's message.
+    #[default]
+    Off,
+    /// The file and line where the panic occurred are added to the top of the
+    /// exception's backtrace.
+    Location,
+    /// The Rust backtrace from the panic up to where it was caught is added
+    /// to the top of the exception's backtrace.
+    ///
+    /// Capturing a backtrace is slow, and depends on debug info being
+    /// available to resolve symbols.
+    Full,
+}
+
+static PANIC_BACKTRACE: AtomicU8 = AtomicU8::new(PanicBacktrace::Off as u8);
+
+impl PanicBacktrace {
+    fn get() -> Self {
+        match PANIC_BACKTRACE.load(Ordering::Relaxed) {
+            1 => Self::Location,
+            2 => Self::Full,
+            _ => Self::Off,
+        }
+    }
+
+    fn set(self) {
+        if self != Self::Off {
+            install_panic_hook();
+        }
+        PANIC_BACKTRACE.store(self as u8, Ordering::Relaxed);
+        // details recorded under the old setting are stale
+        drop(PanicDetails::take());
+    }
+
+    fn from_env() -> Self {
+        match std::env::var("MAGNUS_PANIC_BACKTRACE").as_deref() {
+            Ok("location") => Self::Location,
+            Ok("1" | "full") => Self::Full,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// Apply the `MAGNUS_PANIC_BACKTRACE` environment variable, if set.
+pub(crate) fn init_panic_backtrace() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let setting = PanicBacktrace::from_env();
+        if setting != PanicBacktrace::Off {
+            setting.set();
+        }
+    });
+}
+
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let prev = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let setting = PanicBacktrace::get();
+            if setting != PanicBacktrace::Off {
+                let details = PanicDetails {
+                    location: info
+                        .location()
+                        .map(|l| format!("{}:{}:in 'panic'", l.file(), l.line())),
+                    backtrace: (setting == PanicBacktrace::Full).then(Backtrace::force_capture),
+                };
+                let _ = LAST_PANIC.try_with(|last| last.replace(Some(details)));
+            }
+            prev(info)
+        }));
+    });
+}
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<PanicDetails>> = const { RefCell::new(None) };
+}
+
+/// Details of a panic, recorded by the panic hook, to be picked up by
+/// [`Error::from_panic`].
+struct PanicDetails {
+    location: Option<String>,
+    backtrace: Option<Backtrace>,
+}
+
+impl PanicDetails {
+    fn take() -> Option<Self> {
+        LAST_PANIC.try_with(|last| last.take()).ok().flatten()
+    }
+
+    /// The recorded frames, formatted like Ruby backtrace lines, innermost
+    /// first.
+    fn frames(self) -> Vec<String> {
+        let Some(backtrace) = self.backtrace else {
+            return self.location.into_iter().collect();
+        };
+        let mut frames = Vec::<(String, Option<String>)>::new();
+        for line in backtrace.to_string().lines().map(str::trim) {
+            if let Some(location) = line.strip_prefix("at ") {
+                if let Some((_, loc)) = frames.last_mut() {
+                    // drop the column, Ruby backtraces are just file:line
+                    let location = match location.rsplit_once(':') {
+                        Some((file_line, col)) if col.parse::<u32>().is_ok() => file_line,
+                        _ => location,
+                    };
+                    *loc = Some(location.to_owned());
+                }
+            } else if !line.is_empty() {
+                let symbol = match line.split_once(": ") {
+                    Some((n, symbol)) if n.parse::<usize>().is_ok() => symbol,
+                    _ => line,
+                };
+                frames.push((symbol.to_owned(), None));
+            }
+        }
+
+        // skip frames outside the `catch_unwind` that caught the panic
+        let end = frames
+            .iter()
+            .position(|(symbol, _)| {
+                symbol.starts_with("std::panicking::catch_unwind")
+                    || symbol.starts_with("std::panicking::try")
+                    || symbol.starts_with("std::panic::catch_unwind")
+                    || symbol.ends_with("__rust_try")
+            })
+            .unwrap_or(frames.len());
+        // and the frames capturing the backtrace and handling the panic
+        let start = frames[..end]
+            .iter()
+            .rposition(|(symbol, _)| {
+                symbol.starts_with("std::panicking::")
+                    || symbol.starts_with("core::panicking::")
+                    || symbol.starts_with("std::sys::backtrace::")
+                    || symbol.contains("rust_begin_unwind")
+            })
+            .map_or(0, |i| i + 1);
+
+        let frames = &frames[start..end];
+        // without debug info the frames may not tell us where the panic was,
+        // so fall back to the location from the panic itself
+        let location = match frames.first() {
+            Some((_, Some(_))) => None,
+            _ => self.location,
+        };
+        location
+            .into_iter()
+            .chain(frames.iter().map(|(symbol, location)| match location {
+                Some(location) => format!("{}:in '{}'", location, symbol),
+                None => symbol.clone(),
+            }))
+            .collect()
+    }
+}
+
 /// A wrapper to make a [`Error`] [`Send`] + [`Sync`].
 ///
 /// [`Error`] is not [`Send`] or [`Sync`] as it provides a way to call some of
@@ -397,6 +1527,241 @@ impl IntoError for OpaqueError {
     }
 }
 
+impl IntoError for io::Error {
+    fn into_error(self, ruby: &Ruby) -> Error {
+        // on Windows the raw OS error is a Windows error code, not an errno
+        #[cfg(unix)]
+        if let Some(errno) = self.raw_os_error() {
+            return ruby.error_from_errno(errno, None);
+        }
+        let errno = match self.kind() {
+            io::ErrorKind::UnexpectedEof => {
+                return Error::new(ruby.exception_eof_error(), self.to_string());
+            }
+            io::ErrorKind::NotFound => "ENOENT",
+            io::ErrorKind::PermissionDenied => "EACCES",
+            io::ErrorKind::AlreadyExists => "EEXIST",
+            io::ErrorKind::WouldBlock => "EAGAIN",
+            io::ErrorKind::InvalidInput => "EINVAL",
+            io::ErrorKind::TimedOut => "ETIMEDOUT",
+            io::ErrorKind::Interrupted => "EINTR",
+            io::ErrorKind::BrokenPipe => "EPIPE",
+            io::ErrorKind::ConnectionRefused => "ECONNREFUSED",
+            io::ErrorKind::ConnectionReset => "ECONNRESET",
+            io::ErrorKind::ConnectionAborted => "ECONNABORTED",
+            io::ErrorKind::NotConnected => "ENOTCONN",
+            io::ErrorKind::AddrInUse => "EADDRINUSE",
+            io::ErrorKind::AddrNotAvailable => "EADDRNOTAVAIL",
+            _ => return Error::new(ruby.exception_io_error(), self.to_string()),
+        };
+        match ruby.errno_class(errno) {
+            Ok(class) => Error::new(class, self.to_string()),
+            Err(_) => Error::new(ruby.exception_io_error(), self.to_string()),
+        }
+    }
+}
+
+impl IntoError for ParseIntError {
+    #[inline]
+    fn into_error(self, ruby: &Ruby) -> Error {
+        Error::new(ruby.exception_arg_error(), self.to_string())
+    }
+}
+
+impl IntoError for ParseFloatError {
+    #[inline]
+    fn into_error(self, ruby: &Ruby) -> Error {
+        Error::new(ruby.exception_arg_error(), self.to_string())
+    }
+}
+
+impl IntoError for TryFromIntError {
+    #[inline]
+    fn into_error(self, ruby: &Ruby) -> Error {
+        Error::new(ruby.exception_range_error(), self.to_string())
+    }
+}
+
+impl IntoError for Utf8Error {
+    #[inline]
+    fn into_error(self, ruby: &Ruby) -> Error {
+        Error::new(ruby.exception_encoding_error(), self.to_string())
+    }
+}
+
+impl IntoError for FromUtf8Error {
+    #[inline]
+    fn into_error(self, ruby: &Ruby) -> Error {
+        self.utf8_error().into_error(ruby)
+    }
+}
+
+/// Trait for Rust error enums mapped to a hierarchy of Ruby exception
+/// classes.
+///
+/// This is implemented by [`#[derive(RubyErrors)]`](macro@crate::RubyErrors),
+/// which also implements [`IntoError`] with [`RubyErrors::into_exception`].
+///
+/// There is a base class at the path [`CLASS`](RubyErrors::CLASS), and a
+/// subclass of it for each variant, in the same namespace. The exception's
+/// message is the error's [`Display`](fmt::Display) output, and each
+/// variant's named fields are available from reader methods.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt;
+///
+/// use magnus::{Error, Ruby, error::RubyErrors, function, rb_assert};
+///
+/// #[derive(Debug, magnus::RubyErrors)]
+/// #[magnus(class = "Fetch::Error")]
+/// enum FetchError {
+///     Timeout { seconds: u64 },
+///     #[magnus(name = "HTTPError")]
+///     Http { status: u16, body: String },
+///     Closed,
+/// }
+///
+/// impl fmt::Display for FetchError {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         match self {
+///             Self::Timeout { seconds } => write!(f, "timed out after {}s", seconds),
+///             Self::Http { status, .. } => write!(f, "server returned {}", status),
+///             Self::Closed => write!(f, "connection closed"),
+///         }
+///     }
+/// }
+///
+/// fn fetch(_url: String) -> Result<String, FetchError> {
+///     Err(FetchError::Timeout { seconds: 30 })
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     FetchError::define_errors(ruby)?;
+///     ruby.define_global_function("fetch", function!(fetch, 1));
+///
+///     rb_assert!(ruby, "Fetch::Error < StandardError");
+///     rb_assert!(ruby, "Fetch::TimeoutError < Fetch::Error");
+///     rb_assert!(ruby, "Fetch::HTTPError < Fetch::Error");
+///     rb_assert!(ruby, "Fetch::ClosedError < Fetch::Error");
+///     rb_assert!(
+///         ruby,
+///         r#"
+///         begin
+///           fetch("https://example.com")
+///         rescue Fetch::TimeoutError => e
+///           e.seconds == 30 && e.message == "timed out after 30s"
+///         end
+///         "#
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub trait RubyErrors: fmt::Display + Sized {
+    /// The full path of the base class, e.g. `"MyGem::Error"`.
+    const CLASS: &'static str;
+
+    /// The full path of the base class's superclass, or `None` for
+    /// `StandardError`.
+    const SUPERCLASS: Option<&'static str> = None;
+
+    /// The class name and reader names for each variant, in order.
+    const VARIANTS: &'static [(&'static str, &'static [&'static str])];
+
+    /// Returns the base class.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the class has not been defined, see
+    /// [`define_errors`](RubyErrors::define_errors).
+    fn base_class(ruby: &Ruby) -> ExceptionClass;
+
+    /// Returns the class for `self`'s variant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the class has not been defined, see
+    /// [`define_errors`](RubyErrors::define_errors).
+    fn class(&self, ruby: &Ruby) -> ExceptionClass;
+
+    /// Consumes `self`, returning the reader names and values of its
+    /// variant's fields.
+    fn into_attrs(self, ruby: &Ruby) -> Vec<(&'static str, Value)>;
+
+    /// Define the base class, and a subclass of it for each variant with
+    /// its reader methods.
+    ///
+    /// Modules in the base class's path are defined if they do not already
+    /// exist. The superclass must already be defined.
+    ///
+    /// This should be called from your [`init`](macro@crate::init)
+    /// function.
+    fn define_errors(ruby: &Ruby) -> Result<(), Error> {
+        let superclass = match Self::SUPERCLASS {
+            Some(path) => Self::class_at_path(ruby, path)?,
+            None => ruby.exception_standard_error(),
+        };
+        let path = Self::CLASS.trim_start_matches("::");
+        let (namespace, name) = match path.rsplit_once("::") {
+            Some((namespace, name)) => (Some(namespace), name),
+            None => (None, path),
+        };
+        let mut module: Option<RModule> = None;
+        for segment in namespace.into_iter().flat_map(|n| n.split("::")) {
+            module = Some(match module {
+                Some(m) => m.define_module(segment)?,
+                None => ruby.define_module(segment)?,
+            });
+        }
+        let define = |name: &str, superclass: ExceptionClass| match module {
+            Some(m) => m.define_error(name, superclass),
+            None => ruby.define_error(name, superclass),
+        };
+        let base = define(name, superclass)?;
+        for (name, attrs) in Self::VARIANTS {
+            let class = define(name, base)?;
+            for attr in *attrs {
+                class.define_attr(*attr, Attr::Read)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the exception class at `path`, e.g. `"MyGem::Error"`, looked
+    /// up from `Object`.
+    #[doc(hidden)]
+    fn class_at_path(ruby: &Ruby, path: &str) -> Result<ExceptionClass, Error> {
+        let path = path.trim_start_matches("::");
+        let (namespace, name) = match path.rsplit_once("::") {
+            Some((namespace, name)) => (Some(namespace), name),
+            None => (None, path),
+        };
+        let mut scope = ruby.class_object().as_value();
+        for segment in namespace.into_iter().flat_map(|n| n.split("::")) {
+            scope = match RClass::from_value(scope) {
+                Some(class) => class.const_get(segment)?,
+                None => RModule::try_convert(scope)?.const_get(segment)?,
+            };
+        }
+        match RClass::from_value(scope) {
+            Some(class) => class.const_get(name),
+            None => RModule::try_convert(scope)?.const_get(name),
+        }
+    }
+
+    /// Consumes `self`, returning an instance of its variant's class.
+    fn into_exception(self, ruby: &Ruby) -> Result<Exception, Error> {
+        let e = self.class(ruby).new_instance((self.to_string(),))?;
+        for (name, val) in self.into_attrs(ruby) {
+            e.ivar_set(format!("@{}", name).as_str(), val)?;
+        }
+        Ok(e)
+    }
+}
+
 /// The state of a call to Ruby exiting early, interrupting the normal flow
 /// of code.
 #[derive(Debug, Clone, Copy)]
@@ -443,6 +1808,53 @@ impl fmt::Display for Tag {
     }
 }
 
+/// Categories of Ruby warnings, which can be enabled and disabled with
+/// `Warning[category] = bool`.
+///
+/// See [`Ruby::warn_deprecated`], [`Ruby::warn_experimental`],
+/// [`Ruby::warn_uplevel`], and [`Ruby::warning_category_enabled`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WarningCategory {
+    /// Use of deprecated features. Disabled by default.
+    Deprecated,
+    /// Use of experimental features. Enabled by default.
+    Experimental,
+    /// Use of features with known performance issues. Disabled by default.
+    #[cfg(any(ruby_gte_3_3, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_3)))]
+    Performance,
+}
+
+impl WarningCategory {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Deprecated => "deprecated",
+            Self::Experimental => "experimental",
+            #[cfg(any(ruby_gte_3_3, docsrs))]
+            Self::Performance => "performance",
+        }
+    }
+
+    fn as_rb(self) -> rb_warning_category_t {
+        match self {
+            Self::Deprecated => rb_warning_category_t::RB_WARN_CATEGORY_DEPRECATED,
+            Self::Experimental => rb_warning_category_t::RB_WARN_CATEGORY_EXPERIMENTAL,
+            #[cfg(any(ruby_gte_3_3, docsrs))]
+            Self::Performance => rb_warning_category_t::RB_WARN_CATEGORY_PERFORMANCE,
+        }
+    }
+}
+
+/// The result of [`Ruby::catch`].
+#[derive(Clone, Copy, Debug)]
+pub enum Caught<T> {
+    /// The function completed normally, returning this value.
+    Returned(T),
+    /// A value was thrown to the tag.
+    Thrown(Value),
+}
+
 /// Calls the given closure, rescuing Ruby Exceptions and returning them as
 /// an [`Error`].
 ///
@@ -481,11 +1893,14 @@ where
     // of the api and safe to do.
     // In this case we use arg2 to pass a pointer the Rust closure we actually
     // want to call, and arg1 is just a simple adapter to call arg2.
+    // Ruby may switch Fibers, or unwind past method calls, before returning
+    let depth = borrow::depth();
     let result = unsafe {
         let mut some_func = Some(func);
         let closure = &mut some_func as *mut Option<F> as VALUE;
         rb_protect(Some(call::<F, T>), closure, &mut state as *mut c_int)
     };
+    borrow::restore_depth(depth);
 
     match state {
         // Tag::None
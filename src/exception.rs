@@ -12,20 +12,20 @@ use rb_sys::{
     rb_eNotImpError, rb_eRangeError, rb_eRegexpError, rb_eRuntimeError, rb_eScriptError,
     rb_eSecurityError, rb_eSignal, rb_eStandardError, rb_eStopIteration, rb_eSyntaxError,
     rb_eSysStackError, rb_eSystemCallError, rb_eSystemExit, rb_eThreadError, rb_eTypeError,
-    rb_eZeroDivError,
+    rb_eZeroDivError, rb_ivar_set,
 };
 
 use crate::{
     Ruby,
     class::{Class, RClass},
-    error::Error,
+    error::{Error, protect},
     into_value::{ArgList, IntoValue},
     module::Module,
     object::Object,
     r_array::RArray,
     try_convert::TryConvert,
     value::{
-        NonZeroValue, ReprValue, Value,
+        IntoId, NonZeroValue, ReprValue, Value,
         private::{self, ReprValue as _},
     },
 };
@@ -84,6 +84,301 @@ impl Exception {
     pub fn exception_class(self) -> ExceptionClass {
         unsafe { ExceptionClass::from_rb_value_unchecked(self.class().as_rb_value()) }
     }
+
+    /// Returns the exception's message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(r#"StandardError.new("example")"#)?;
+    ///     assert_eq!(e.message()?, "example");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn message(self) -> Result<String, Error> {
+        self.funcall("message", ())
+    }
+
+    /// Returns the exception's message, with the class name, and any
+    /// additional detail added by the exception class or extensions such as
+    /// `did_you_mean`.
+    ///
+    /// If `highlight` is `true` the class name and message will be formatted
+    /// with terminal escape sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(r#"StandardError.new("example")"#)?;
+    ///     assert_eq!(e.detailed_message(false)?, "example (StandardError)");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(any(ruby_gte_3_2, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_2)))]
+    pub fn detailed_message(self, highlight: bool) -> Result<String, Error> {
+        let ruby = Ruby::get_with(self);
+        self.funcall(
+            "detailed_message",
+            (crate::kwargs!(&ruby, "highlight" => highlight),),
+        )
+    }
+
+    /// Returns the exception formatted as Ruby would print it when
+    /// uncaught, with its message, backtrace, and causes.
+    ///
+    /// If `highlight` is `true` the output will be formatted with terminal
+    /// escape sequences. `order` sets whether the backtrace is printed with
+    /// the innermost frame first or last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{
+    ///     Error, Exception, Ruby,
+    ///     exception::BacktraceOrder,
+    /// };
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(r#"StandardError.new("example")"#)?;
+    ///     let msg = e.full_message(false, BacktraceOrder::Top)?;
+    ///     assert!(msg.contains("example (StandardError)"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn full_message(self, highlight: bool, order: BacktraceOrder) -> Result<String, Error> {
+        let ruby = Ruby::get_with(self);
+        let order = match order {
+            BacktraceOrder::Top => ruby.to_symbol("top"),
+            BacktraceOrder::Bottom => ruby.to_symbol("bottom"),
+        };
+        self.funcall(
+            "full_message",
+            (crate::kwargs!(&ruby, "highlight" => highlight, "order" => order),),
+        )
+    }
+
+    /// Returns the exception's backtrace, or `None` if the backtrace has not
+    /// been set, i.e. the exception has not been raised.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(r#"StandardError.new("example")"#)?;
+    ///     assert_eq!(e.backtrace()?, None);
+    ///
+    ///     let e: Exception = ruby.eval(r#"begin; raise "example"; rescue => e; e; end"#)?;
+    ///     assert!(!e.backtrace()?.unwrap().is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn backtrace(self) -> Result<Option<Vec<String>>, Error> {
+        self.funcall("backtrace", ())
+    }
+
+    /// Returns the exception's backtrace as [`BacktraceLocation`]s, or
+    /// `None` if the backtrace has not been set, or was set with
+    /// [`set_backtrace`](Exception::set_backtrace).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(
+    ///         r#"
+    ///         def example
+    ///           raise "example"
+    ///         end
+    ///         begin; example; rescue => e; e; end
+    ///         "#,
+    ///     )?;
+    ///     let locations = e.backtrace_locations()?.unwrap();
+    ///     assert_eq!(locations[0].base_label()?, "example");
+    ///     assert_eq!(locations[0].lineno()?, 3);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn backtrace_locations(self) -> Result<Option<Vec<BacktraceLocation>>, Error> {
+        let locations: Option<RArray> = self.funcall("backtrace_locations", ())?;
+        locations
+            .map(|ary| {
+                ary.to_vec::<Value>()
+                    .map(|vec| vec.into_iter().map(BacktraceLocation).collect())
+            })
+            .transpose()
+    }
+
+    /// Sets the exception's backtrace.
+    ///
+    /// `backtrace` should convert to an Array of Strings, a single String, or
+    /// `nil`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(r#"StandardError.new("example")"#)?;
+    ///     e.set_backtrace(vec!["lib/example.rb:1:in 'run'"])?;
+    ///     assert_eq!(
+    ///         e.backtrace()?,
+    ///         Some(vec![String::from("lib/example.rb:1:in 'run'")])
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn set_backtrace<T>(self, backtrace: T) -> Result<(), Error>
+    where
+        T: IntoValue,
+    {
+        self.funcall::<_, _, Value>("set_backtrace", (backtrace,))
+            .map(|_| ())
+    }
+
+    /// Returns the exception that was being handled when `self` was raised,
+    /// or `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(
+    ///         r#"
+    ///         begin
+    ///           begin
+    ///             raise "inner"
+    ///           rescue
+    ///             raise ArgumentError, "outer"
+    ///           end
+    ///         rescue => e
+    ///           e
+    ///         end
+    ///         "#,
+    ///     )?;
+    ///     assert_eq!(e.message()?, "outer");
+    ///     assert_eq!(e.cause()?.unwrap().message()?, "inner");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn cause(self) -> Result<Option<Exception>, Error> {
+        self.funcall("cause", ())
+    }
+
+    /// Sets the cause reported by [`cause`](Exception::cause).
+    ///
+    /// Ruby only sets the cause when an exception is raised, and then only if
+    /// one has not already been set. Setting `None` will stop Ruby from
+    /// setting the cause to the exception currently being handled.
+    ///
+    /// Ruby has no API to set the cause without raising, so this relies on
+    /// CRuby storing it in the hidden (no `@` prefix) instance variable
+    /// `cause`, which `Exception#cause` reads and `raise` checks before
+    /// setting its own. This has been the case since causes were added in
+    /// Ruby 2.1.
+    pub(crate) fn init_cause(self, cause: Option<Exception>) -> Result<(), Error> {
+        let ruby = Ruby::get_with(self);
+        let cause = cause.map_or_else(|| ruby.qnil().as_value(), |e| e.as_value());
+        let id = "cause".into_id_with(&ruby);
+        // raises if the exception is frozen
+        protect(|| {
+            unsafe { rb_ivar_set(self.as_rb_value(), id.as_rb_id(), cause.as_rb_value()) };
+            ruby.qnil()
+        })?;
+        Ok(())
+    }
+}
+
+/// The order of the backtrace in [`Exception::full_message`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BacktraceOrder {
+    /// The innermost frame first, the default since Ruby 3.0.
+    Top,
+    /// The innermost frame last.
+    Bottom,
+}
+
+/// A location from an [`Exception`]'s backtrace, wrapping an instance of
+/// Ruby's `Thread::Backtrace::Location`.
+///
+/// See [`Exception::backtrace_locations`].
+#[derive(Clone, Copy)]
+pub struct BacktraceLocation(Value);
+
+impl BacktraceLocation {
+    /// Returns the file name of the location.
+    pub fn path(self) -> Result<String, Error> {
+        self.0.funcall("path", ())
+    }
+
+    /// Returns the full file path of the location, or `None` if it is not
+    /// available, e.g. for code evaluated from a string.
+    pub fn absolute_path(self) -> Result<Option<String>, Error> {
+        self.0.funcall("absolute_path", ())
+    }
+
+    /// Returns the line number of the location.
+    pub fn lineno(self) -> Result<usize, Error> {
+        self.0.funcall("lineno", ())
+    }
+
+    /// Returns the label of the location, usually the method name, with
+    /// additional decoration such as the class name or `block in`.
+    pub fn label(self) -> Result<String, Error> {
+        self.0.funcall("label", ())
+    }
+
+    /// Returns the base label of the location, usually the method name
+    /// without any decoration.
+    pub fn base_label(self) -> Result<String, Error> {
+        self.0.funcall("base_label", ())
+    }
+}
+
+impl fmt::Display for BacktraceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.0.to_s_infallible() })
+    }
+}
+
+impl fmt::Debug for BacktraceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.inspect())
+    }
+}
+
+impl IntoValue for BacktraceLocation {
+    #[inline]
+    fn into_value_with(self, _: &Ruby) -> Value {
+        self.0
+    }
 }
 
 impl fmt::Display for Exception {
@@ -97,7 +392,7 @@ impl fmt::Debug for Exception {
         if f.alternate() {
             unsafe {
                 writeln!(f, "{}: {}", self.classname(), self)?;
-                if let Ok(Some(backtrace)) = self.funcall::<_, _, Option<RArray>>("backtrace", ()) {
+                if let Ok(Some(backtrace)) = self.backtrace() {
                     for line in backtrace {
                         writeln!(f, "{}", line)?;
                     }
@@ -178,6 +473,43 @@ impl ExceptionClass {
     pub(crate) unsafe fn from_rb_value_unchecked(val: VALUE) -> Self {
         unsafe { Self(NonZeroValue::new_unchecked(Value::new(val))) }
     }
+
+    /// Create a new instance of `self`, passing `args` to `initialize`, with
+    /// `cause` as the exception's cause.
+    ///
+    /// Ruby normally sets the cause when the exception is raised, to the
+    /// exception currently being handled, if any. Passing `None` for `cause`
+    /// will suppress this, as with `raise ..., cause: nil`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let cause: Exception = ruby.eval(r#"IOError.new("disk on fire")"#)?;
+    ///     let e = ruby
+    ///         .exception_runtime_error()
+    ///         .new_instance_with_cause(("save failed",), Some(cause))?;
+    ///     assert_eq!(e.message()?, "save failed");
+    ///     assert_eq!(e.cause()?.unwrap().message()?, "disk on fire");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn new_instance_with_cause<T>(
+        self,
+        args: T,
+        cause: Option<Exception>,
+    ) -> Result<Exception, Error>
+    where
+        T: ArgList,
+    {
+        let e = self.new_instance(args)?;
+        e.init_cause(cause)?;
+        Ok(e)
+    }
 }
 
 impl fmt::Display for ExceptionClass {
@@ -954,6 +1286,31 @@ impl Ruby {
         unsafe { ExceptionClass::from_rb_value_unchecked(rb_eSystemCallError) }
     }
 
+    /// Return the `Errno::E*` class named `name`, e.g. `"ECONNREFUSED"`.
+    ///
+    /// Returns an error if there is no such class, as not all error numbers
+    /// are defined on all platforms.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, rb_assert};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "klass == Errno::ECONNREFUSED",
+    ///         klass = ruby.errno_class("ECONNREFUSED")?
+    ///     );
+    ///     assert!(ruby.errno_class("ENOTANERROR").is_err());
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn errno_class(&self, name: &str) -> Result<ExceptionClass, Error> {
+        self.module_errno().const_get(name)
+    }
+
     /// Return Ruby's `SystemExit` class.
     ///
     /// # Examples
@@ -2,7 +2,12 @@
 //!
 //! See also [`Ruby`](Ruby#gc) for more GC related methods.
 
-use std::{marker::PhantomData, ops::Range};
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashMap, VecDeque},
+    marker::PhantomData,
+    ops::Range,
+};
 
 use rb_sys::{
     VALUE, rb_gc_adjust_memory_usage, rb_gc_count, rb_gc_disable, rb_gc_enable, rb_gc_location,
@@ -179,6 +184,146 @@ impl Compactor {
     }
 }
 
+/// Trait for Rust types containing Ruby values that can mark those values.
+///
+/// This is used by the code generated for the `#[magnus(mark)]` and
+/// `#[magnus(compact)]` field attributes of the
+/// [`TypedData`](macro@crate::TypedData) derive, but can also be called
+/// directly when implementing
+/// [`DataTypeFunctions::mark`](`crate::typed_data::DataTypeFunctions::mark`).
+///
+/// Where [`Mark`] is implemented for single values that can be passed to
+/// [`Marker`], `Markable` is implemented for containers of those values, such
+/// as `Vec<Opaque<Value>>` or `HashMap<String, Opaque<Value>>`. Map types
+/// only mark their values.
+///
+/// Values are only ever marked as movable when they can be updated during
+/// compaction, which requires interior mutability, e.g. `Cell<Opaque<T>>`.
+/// Any other values are marked as not movable.
+///
+/// `Markable` is deliberately not implemented for `RefCell<T>`. The garbage
+/// collector can run while code holds a `RefMut` to the contents, e.g. part
+/// way through `Vec::extend`, so there is no sound way to read them from
+/// [`mark`](Markable::mark).
+pub trait Markable {
+    /// Mark all Ruby values in `self`.
+    fn mark(&self, marker: &Marker);
+
+    /// Mark all Ruby values in `self`, allowing values that can be updated by
+    /// [`compact`](Markable::compact) to be moved.
+    ///
+    /// The default implementation delegates to [`mark`](Markable::mark).
+    fn mark_movable(&self, marker: &Marker) {
+        self.mark(marker)
+    }
+
+    /// Update all movable Ruby values in `self` with their new location.
+    ///
+    /// The default implementation does nothing.
+    fn compact(&self, #[allow(unused_variables)] compactor: &Compactor) {}
+}
+
+impl<T> Markable for crate::value::Opaque<T>
+where
+    T: ReprValue,
+{
+    fn mark(&self, marker: &Marker) {
+        marker.mark(*self);
+    }
+}
+
+impl<T> Markable for Cell<crate::value::Opaque<T>>
+where
+    T: ReprValue,
+{
+    fn mark(&self, marker: &Marker) {
+        marker.mark(self.get());
+    }
+
+    fn mark_movable(&self, marker: &Marker) {
+        marker.mark_movable(self.get());
+    }
+
+    fn compact(&self, compactor: &Compactor) {
+        self.set(compactor.location(self.get()));
+    }
+}
+
+impl<T> Markable for Option<T>
+where
+    T: Markable,
+{
+    fn mark(&self, marker: &Marker) {
+        if let Some(v) = self {
+            v.mark(marker);
+        }
+    }
+
+    fn mark_movable(&self, marker: &Marker) {
+        if let Some(v) = self {
+            v.mark_movable(marker);
+        }
+    }
+
+    fn compact(&self, compactor: &Compactor) {
+        if let Some(v) = self {
+            v.compact(compactor);
+        }
+    }
+}
+
+impl<T> Markable for Box<T>
+where
+    T: Markable + ?Sized,
+{
+    fn mark(&self, marker: &Marker) {
+        (**self).mark(marker);
+    }
+
+    fn mark_movable(&self, marker: &Marker) {
+        (**self).mark_movable(marker);
+    }
+
+    fn compact(&self, compactor: &Compactor) {
+        (**self).compact(compactor);
+    }
+}
+
+macro_rules! impl_markable_for_iter {
+    ($($ty:ty, $iter:ident, [$($params:tt)*]);* $(;)?) => {
+        $(
+            impl<$($params)*> Markable for $ty {
+                fn mark(&self, marker: &Marker) {
+                    for v in self.$iter() {
+                        v.mark(marker);
+                    }
+                }
+
+                fn mark_movable(&self, marker: &Marker) {
+                    for v in self.$iter() {
+                        v.mark_movable(marker);
+                    }
+                }
+
+                fn compact(&self, compactor: &Compactor) {
+                    for v in self.$iter() {
+                        v.compact(compactor);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_markable_for_iter! {
+    [T], iter, [T: Markable];
+    [T; N], iter, [T: Markable, const N: usize];
+    Vec<T>, iter, [T: Markable];
+    VecDeque<T>, iter, [T: Markable];
+    HashMap<K, V, S>, values, [K, V: Markable, S];
+    BTreeMap<K, V>, values, [K, V: Markable];
+}
+
 /// Registers `value` to never be garbage collected.
 ///
 /// This is essentially a deliberate memory leak.
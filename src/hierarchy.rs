@@ -0,0 +1,349 @@
+//! Declaring Ruby classes, modules, and constants, to be defined in
+//! dependency order.
+//!
+//! A [`Hierarchy`] collects declarations in any order, and
+//! [`define`](Hierarchy::define) defines namespaces before the classes and
+//! modules nested in them, and superclasses before their subclasses.
+//! Constants and method sets are defined once all classes and modules exist.
+//!
+//! This is what the `modules`, `classes`, and `constants` attributes of the
+//! [`init`](macro@crate::init) macro expand to.
+
+use std::fmt;
+
+use crate::{
+    Ruby,
+    class::{Class, RClass},
+    error::Error,
+    into_value::IntoValueFromNative,
+    module::{Module, RModule},
+    try_convert::TryConvert,
+    typed_data::TypedData,
+    value::{ReprValue, Value},
+};
+
+/// Trait for [`TypedData`] types with a known class path, so their class can
+/// be declared in a [`Hierarchy`].
+///
+/// This is implemented by [`#[wrap]`](macro@crate::wrap) and
+/// [`#[derive(TypedData)]`](macro@crate::TypedData), with the path from the
+/// `class = "..."` attribute and superclass from the optional
+/// `superclass = "..."` attribute.
+pub trait DeclareClass: TypedData {
+    /// The full path of the class, e.g. `"Foo::Bar"`.
+    const PATH: &'static str;
+
+    /// The full path of the class's superclass, or `None` for `Object`.
+    const SUPERCLASS: Option<&'static str> = None;
+}
+
+#[derive(Debug)]
+enum Kind {
+    Module,
+    Class {
+        superclass: Option<String>,
+        wrapped: bool,
+    },
+}
+
+#[derive(Debug)]
+struct Declaration {
+    path: String,
+    kind: Kind,
+}
+
+impl Declaration {
+    fn dependencies(&self) -> impl Iterator<Item = &str> {
+        let superclass = match &self.kind {
+            Kind::Class {
+                superclass: Some(s),
+                ..
+            } => Some(s.as_str()),
+            _ => None,
+        };
+        split_path(&self.path).0.into_iter().chain(superclass)
+    }
+
+    fn define(&self, ruby: &Ruby) -> Result<(), Error> {
+        let (namespace, name) = split_path(&self.path);
+        let namespace = Namespace::get(ruby, namespace)?;
+        match &self.kind {
+            Kind::Module => {
+                namespace.define_module(name)?;
+            }
+            Kind::Class {
+                superclass,
+                wrapped,
+            } => {
+                let superclass = match superclass {
+                    Some(path) => {
+                        let val = lookup(ruby, path)?;
+                        RClass::from_value(val).ok_or_else(|| {
+                            Error::new(
+                                ruby.exception_type_error(),
+                                format!("{} is not a class (superclass of {})", path, self.path),
+                            )
+                        })?
+                    }
+                    None => ruby.class_object(),
+                };
+                let class = namespace.define_class(name, superclass)?;
+                if *wrapped {
+                    class.undef_default_alloc_func();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+type Deferred = Box<dyn FnOnce(&Ruby) -> Result<(), Error>>;
+
+/// A set of class, module, and constant declarations.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, RClass, Ruby, function, hierarchy::Hierarchy, prelude::*, rb_assert};
+///
+/// #[magnus::wrap(class = "Shapes::Shape")]
+/// struct Shape;
+///
+/// #[magnus::wrap(class = "Shapes::Circle", superclass = "Shapes::Shape")]
+/// struct Circle;
+///
+/// fn is_round() -> bool {
+///     true
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let mut hierarchy = Hierarchy::new();
+///     // declared in any order
+///     hierarchy
+///         .wrapped::<Circle>()
+///         .wrapped::<Shape>()
+///         .module("Shapes")
+///         .constant("Shapes::SIDES", 0)
+///         .methods("Shapes::Circle", |_ruby: &Ruby, class: RClass| {
+///             class.define_singleton_method("round?", function!(is_round, 0))
+///         });
+///     hierarchy.define(ruby)?;
+///
+///     rb_assert!(ruby, "Shapes::Circle.superclass == Shapes::Shape");
+///     rb_assert!(ruby, "Shapes::SIDES == 0");
+///     rb_assert!(ruby, "Shapes::Circle.round?");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Default)]
+pub struct Hierarchy {
+    declarations: Vec<Declaration>,
+    constants: Vec<Deferred>,
+    methods: Vec<Deferred>,
+}
+
+impl Hierarchy {
+    /// Create a new, empty, `Hierarchy`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare the module at `path`, e.g. `"Foo::Bar"`.
+    pub fn module(&mut self, path: &str) -> &mut Self {
+        self.declare(path, Kind::Module)
+    }
+
+    /// Declare the class at `path`, e.g. `"Foo::Bar"`, with the superclass
+    /// `Object`.
+    pub fn class(&mut self, path: &str) -> &mut Self {
+        self.declare(
+            path,
+            Kind::Class {
+                superclass: None,
+                wrapped: false,
+            },
+        )
+    }
+
+    /// Declare the class at `path`, e.g. `"Foo::Bar"`, with the superclass
+    /// at `superclass`.
+    ///
+    /// `superclass` is defined first if it is declared in this `Hierarchy`,
+    /// otherwise it must already exist.
+    pub fn subclass(&mut self, path: &str, superclass: &str) -> &mut Self {
+        self.declare(
+            path,
+            Kind::Class {
+                superclass: Some(superclass.to_owned()),
+                wrapped: false,
+            },
+        )
+    }
+
+    /// Declare the class for the wrapped type `T`.
+    ///
+    /// The class's default allocator function will be undefined, as
+    /// instances can only be created from Rust.
+    pub fn wrapped<T>(&mut self) -> &mut Self
+    where
+        T: DeclareClass,
+    {
+        self.declare(
+            T::PATH,
+            Kind::Class {
+                superclass: T::SUPERCLASS.map(ToOwned::to_owned),
+                wrapped: true,
+            },
+        )
+    }
+
+    /// Declare the constant at `path`, e.g. `"Foo::VERSION"`, with `value`.
+    pub fn constant<T>(&mut self, path: &str, value: T) -> &mut Self
+    where
+        T: IntoValueFromNative + 'static,
+    {
+        let path = path.to_owned();
+        self.constants.push(Box::new(move |ruby| {
+            let (namespace, name) = split_path(&path);
+            Namespace::get(ruby, namespace)?.const_set(name, value.into_value_with(ruby))
+        }));
+        self
+    }
+
+    /// Declare a set of methods (or other definitions) for the class or
+    /// module at `path`.
+    ///
+    /// `func` is called with the class or module once all declared classes,
+    /// modules, and constants have been defined. `M` can be [`RClass`],
+    /// [`RModule`], or any other type that implements [`TryConvert`].
+    pub fn methods<M, F>(&mut self, path: &str, func: F) -> &mut Self
+    where
+        M: TryConvert,
+        F: FnOnce(&Ruby, M) -> Result<(), Error> + 'static,
+    {
+        let path = path.to_owned();
+        self.methods.push(Box::new(move |ruby| {
+            let module = M::try_convert(lookup(ruby, &path)?)?;
+            func(ruby, module)
+        }));
+        self
+    }
+
+    fn declare(&mut self, path: &str, kind: Kind) -> &mut Self {
+        let path = path.trim_start_matches("::");
+        self.declarations.retain(|d| d.path != path);
+        self.declarations.push(Declaration {
+            path: path.to_owned(),
+            kind,
+        });
+        self
+    }
+
+    /// Define all declared classes, modules, and constants, then call all
+    /// method set functions.
+    ///
+    /// Returns an error if a namespace or superclass is neither declared nor
+    /// already defined, or if the declarations contain a cycle.
+    pub fn define(self, ruby: &Ruby) -> Result<(), Error> {
+        let mut pending = self.declarations;
+        while !pending.is_empty() {
+            let paths = pending.iter().map(|d| d.path.clone()).collect::<Vec<_>>();
+            let (ready, blocked): (Vec<_>, Vec<_>) = pending
+                .into_iter()
+                .partition(|d| d.dependencies().all(|dep| !paths.iter().any(|p| p == dep)));
+            if ready.is_empty() {
+                return Err(Error::new(
+                    ruby.exception_runtime_error(),
+                    format!(
+                        "cyclic namespace or superclass declarations: {}",
+                        blocked
+                            .iter()
+                            .map(|d| d.path.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                ));
+            }
+            for decl in ready {
+                decl.define(ruby)?;
+            }
+            pending = blocked;
+        }
+        for constant in self.constants {
+            constant(ruby)?;
+        }
+        for methods in self.methods {
+            methods(ruby)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Hierarchy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Hierarchy")
+            .field("declarations", &self.declarations)
+            .field("constants", &self.constants.len())
+            .field("methods", &self.methods.len())
+            .finish()
+    }
+}
+
+/// Splits `"Foo::Bar::Baz"` into `(Some("Foo::Bar"), "Baz")`.
+fn split_path(path: &str) -> (Option<&str>, &str) {
+    match path.rsplit_once("::") {
+        Some((namespace, name)) => (Some(namespace), name),
+        None => (None, path),
+    }
+}
+
+fn lookup(ruby: &Ruby, path: &str) -> Result<Value, Error> {
+    ruby.class_object().funcall("const_get", (path,))
+}
+
+enum Namespace {
+    Class(RClass),
+    Module(RModule),
+}
+
+impl Namespace {
+    fn get(ruby: &Ruby, path: Option<&str>) -> Result<Self, Error> {
+        let val = match path {
+            Some(path) => lookup(ruby, path)?,
+            None => return Ok(Self::Class(ruby.class_object())),
+        };
+        if let Some(class) = RClass::from_value(val) {
+            Ok(Self::Class(class))
+        } else if let Some(module) = RModule::from_value(val) {
+            Ok(Self::Module(module))
+        } else {
+            Err(Error::new(
+                ruby.exception_type_error(),
+                format!("{} is not a class/module", val.inspect()),
+            ))
+        }
+    }
+
+    fn define_module(self, name: &str) -> Result<RModule, Error> {
+        match self {
+            Self::Class(class) => class.define_module(name),
+            Self::Module(module) => module.define_module(name),
+        }
+    }
+
+    fn define_class(self, name: &str, superclass: RClass) -> Result<RClass, Error> {
+        match self {
+            Self::Class(class) => class.define_class(name, superclass),
+            Self::Module(module) => module.define_class(name, superclass),
+        }
+    }
+
+    fn const_set(self, name: &str, value: Value) -> Result<(), Error> {
+        match self {
+            Self::Class(class) => class.const_set(name, value),
+            Self::Module(module) => module.const_set(name, value),
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Wrapping Rust iterators as Ruby `Enumerable` objects.
+//!
+//! A [`RustIterator`] holds a Rust iterator, converting each item to a Ruby
+//! value only as it is reached. This makes it suitable for one-shot
+//! iterators, such as streaming database cursors, where
+//! [`Yield`](crate::block::Yield) would need to re-invoke the method to
+//! restart iteration.
+
+use std::cell::{Cell, RefCell};
+
+use crate::{
+    Ruby,
+    class::{Class, RClass},
+    error::Error,
+    gc,
+    into_value::IntoValue,
+    module::Module,
+    rbs::RbsType,
+    typed_data::{DataType, DataTypeBuilder, DataTypeFunctions, Obj, TypedData},
+    value::{Lazy, Opaque, ReprValue, Value},
+};
+
+trait DynIter: Send {
+    fn next_value(&mut self, ruby: &Ruby) -> Option<Value>;
+
+    fn size_hint(&self) -> (usize, Option<usize>);
+
+    fn rewind(&mut self) -> bool;
+}
+
+struct OneShot<I>(I);
+
+impl<I> DynIter for OneShot<I>
+where
+    I: Iterator + Send,
+    I::Item: IntoValue,
+{
+    fn next_value(&mut self, ruby: &Ruby) -> Option<Value> {
+        self.0.next().map(|item| item.into_value_with(ruby))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+
+    fn rewind(&mut self) -> bool {
+        false
+    }
+}
+
+struct Rewindable<I> {
+    start: I,
+    iter: I,
+}
+
+impl<I> DynIter for Rewindable<I>
+where
+    I: Iterator + Clone + Send,
+    I::Item: IntoValue,
+{
+    fn next_value(&mut self, ruby: &Ruby) -> Option<Value> {
+        self.iter.next().map(|item| item.into_value_with(ruby))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn rewind(&mut self) -> bool {
+        self.iter = self.start.clone();
+        true
+    }
+}
+
+/// A Rust iterator wrapped as a Ruby object.
+///
+/// The Ruby object includes `Enumerable`, and has the methods:
+///
+/// * `each`: yields each remaining item to the block, or returns an
+///   `Enumerator` if no block is given.
+/// * `next`: returns the next item, raising `StopIteration` at the end.
+/// * `peek`: returns the next item without advancing.
+/// * `rewind`: restarts iteration, for iterators created with
+///   [`RustIterator::rewindable`]. Raises `RuntimeError` otherwise, or if
+///   called while the iterator is producing an item.
+/// * `size`: the number of remaining items if known from the iterator's
+///   [`size_hint`](Iterator::size_hint), otherwise `nil`.
+///
+/// All other `Enumerable` methods, such as `map` and `lazy`, are implemented
+/// in terms of `each`.
+///
+/// The Ruby class is `Magnus::Iterator`. The iterator is shared by all of
+/// these methods, so items consumed by one
+/// will not be seen by another. Items are converted to Ruby values as they
+/// are reached.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, Ruby, function, iterator::RustIterator, rb_assert};
+///
+/// fn squares(limit: u64) -> RustIterator {
+///     RustIterator::new((1..=limit).map(|i| i * i))
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("squares", function!(squares, 1));
+///
+///     rb_assert!(ruby, "squares(4).to_a == [1, 4, 9, 16]");
+///     rb_assert!(ruby, "squares(4).size == 4");
+///     rb_assert!(ruby, "squares(4).lazy.select(&:even?).first(1) == [4]");
+///
+///     let squares = ruby.eval::<magnus::Value>("squares(3)")?;
+///     rb_assert!(ruby, "squares.next == 1", squares);
+///     rb_assert!(ruby, "squares.peek == 4", squares);
+///     rb_assert!(ruby, "squares.map { |i| i + 1 } == [5, 10]", squares);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub struct RustIterator {
+    iter: RefCell<Box<dyn DynIter>>,
+    peeked: Cell<Option<Opaque<Value>>>,
+}
+
+impl RustIterator {
+    /// Wrap the one-shot iterator `iter`.
+    ///
+    /// Calling `rewind` on the returned object will raise a `RuntimeError`.
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: Iterator + Send + 'static,
+        I::Item: IntoValue,
+    {
+        Self::from_dyn(Box::new(OneShot(iter)))
+    }
+
+    /// Wrap the iterator `iter`, keeping a clone of it so iteration can be
+    /// restarted with `rewind`.
+    pub fn rewindable<I>(iter: I) -> Self
+    where
+        I: Iterator + Clone + Send + 'static,
+        I::Item: IntoValue,
+    {
+        Self::from_dyn(Box::new(Rewindable {
+            start: iter.clone(),
+            iter,
+        }))
+    }
+
+    fn from_dyn(iter: Box<dyn DynIter>) -> Self {
+        Self {
+            iter: RefCell::new(iter),
+            peeked: Cell::new(None),
+        }
+    }
+
+    fn next_item(&self, ruby: &Ruby) -> Result<Option<Value>, Error> {
+        if let Some(val) = self.peeked.take() {
+            return Ok(Some(ruby.get_inner(val)));
+        }
+        let mut iter = self.iter.try_borrow_mut().map_err(|_| {
+            Error::new(
+                ruby.exception_runtime_error(),
+                "iterator accessed while producing an item",
+            )
+        })?;
+        Ok(iter.next_value(ruby))
+    }
+
+    fn each(ruby: &Ruby, rb_self: Obj<Self>) -> Result<Value, Error> {
+        if !ruby.block_given() {
+            return Ok(rb_self.enumeratorize("each", ()).as_value());
+        }
+        while let Some(val) = rb_self.next_item(ruby)? {
+            ruby.yield_value::<_, Value>(val)?;
+        }
+        Ok(rb_self.as_value())
+    }
+
+    fn next(ruby: &Ruby, rb_self: &Self) -> Result<Value, Error> {
+        rb_self.next_item(ruby)?.ok_or_else(|| stop_iteration(ruby))
+    }
+
+    fn peek(ruby: &Ruby, rb_self: &Self) -> Result<Value, Error> {
+        let val = rb_self
+            .next_item(ruby)?
+            .ok_or_else(|| stop_iteration(ruby))?;
+        rb_self.peeked.set(Some(Opaque::from(val)));
+        Ok(val)
+    }
+
+    fn rewind(ruby: &Ruby, rb_self: Obj<Self>) -> Result<Obj<Self>, Error> {
+        let rewound = rb_self
+            .iter
+            .try_borrow_mut()
+            .map_err(|_| Error::new(ruby.exception_runtime_error(), "iterator is in use"))?
+            .rewind();
+        if !rewound {
+            return Err(Error::new(
+                ruby.exception_runtime_error(),
+                "can't rewind a one-shot iterator",
+            ));
+        }
+        rb_self.peeked.set(None);
+        Ok(rb_self)
+    }
+
+    fn size(rb_self: &Self) -> Option<usize> {
+        let (lower, upper) = rb_self.iter.try_borrow().ok()?.size_hint();
+        let peeked = usize::from(rb_self.peeked.get().is_some());
+        (upper == Some(lower)).then(|| lower + peeked)
+    }
+}
+
+fn stop_iteration(ruby: &Ruby) -> Error {
+    Error::new(ruby.exception_stop_iteration(), "iteration reached an end")
+}
+
+impl DataTypeFunctions for RustIterator {
+    fn mark(&self, marker: &gc::Marker) {
+        if let Some(val) = self.peeked.get() {
+            marker.mark(val);
+        }
+    }
+}
+
+unsafe impl TypedData for RustIterator {
+    fn class(ruby: &Ruby) -> RClass {
+        static CLASS: Lazy<RClass> = Lazy::new(|ruby| {
+            let class = ruby
+                .define_module("Magnus")
+                .unwrap()
+                .define_class("Iterator", ruby.class_object())
+                .unwrap();
+            class.undef_default_alloc_func();
+            class.include_module(ruby.module_enumerable()).unwrap();
+            class
+                .define_method("each", crate::method!(RustIterator::each, 0))
+                .unwrap();
+            class
+                .define_method("next", crate::method!(RustIterator::next, 0))
+                .unwrap();
+            class
+                .define_method("peek", crate::method!(RustIterator::peek, 0))
+                .unwrap();
+            class
+                .define_method("rewind", crate::method!(RustIterator::rewind, 0))
+                .unwrap();
+            class
+                .define_method("size", crate::method!(RustIterator::size, 0))
+                .unwrap();
+            class
+        });
+        ruby.get_inner(&CLASS)
+    }
+
+    fn data_type() -> &'static DataType {
+        static DATA_TYPE: DataType = DataTypeBuilder::<RustIterator>::new(c"magnus rust iterator")
+            .mark()
+            .free_immediately()
+            .build();
+        &DATA_TYPE
+    }
+
+    const RBS_TYPE: &'static str = "Enumerable[untyped]";
+}
+
+impl RbsType for RustIterator {
+    fn rbs_type() -> String {
+        Self::RBS_TYPE.to_owned()
+    }
+}
@@ -347,10 +347,11 @@
 //!
 //! * `rb_call_super`: See [`Ruby::call_super`].
 //! * `rb_call_super_kw`: [`Ruby::call_super`].
-// * `rb_catch`:
-// * `rb_catch_obj`:
+//! * `rb_catch`: See [`Ruby::catch`].
+//! * `rb_catch_obj`: [`Ruby::catch`].
 // * `rb_category_compile_warn`:
-// * `rb_category_warn`:
+//! * `rb_category_warn`: [`Ruby::warn_deprecated`], [`Ruby::warn_experimental`],
+//!   [`Ruby::warn_performance`].
 // * `rb_category_warning`:
 // * `rb_char_to_option_kcode`:
 //! * `rb_check_arity`: [`Ruby::check_arity`].
@@ -641,10 +642,11 @@
 // * `rb_enc_vsprintf`:
 //!
 //! ## `rb_en`-`rb_ez`
-// * `rb_ensure`:
+//! * `rb_ensure`: [`Ruby::ensure`].
 //! * `rb_enumeratorize`: See [`Value::enumeratorize`].
-//! * `rb_enumeratorize_with_size`: See [`Value::enumeratorize`].
-//! * `rb_enumeratorize_with_size_kw`: [`Value::enumeratorize`].
+//! * `rb_enumeratorize_with_size`: See [`Value::enumeratorize_with_size`].
+//! * `rb_enumeratorize_with_size_kw`: [`Value::enumeratorize`],
+//!   [`Value::enumeratorize_with_size`].
 // * `rb_enum_values_pack`:
 // * `rb_env_clear`:
 // * `rb_eof_error`:
@@ -1310,8 +1312,8 @@
 // * `rb_remove_method_id`:
 //! * `rb_require`: [`Ruby::require`].
 //! * `rb_require_string`: [`Ruby::require`].
-// * `rb_rescue`:
-// * `rb_rescue2`:
+//! * `rb_rescue`: See [`Ruby::rescue`].
+//! * `rb_rescue2`: See [`Ruby::rescue`].
 // * `RB_RESERVED_FD_P`:
 // * `rb_reserved_fd_p`:
 // * `rb_reset_random_seed`:
@@ -1492,11 +1494,11 @@
 // * `rb_sym_to_s`:
 // * `rb_syserr_fail`:
 // * `rb_syserr_fail_str`:
-// * `rb_syserr_new`:
-// * `rb_syserr_new_str`:
+//! * `rb_syserr_new`: See [`Ruby::error_from_errno`].
+//! * `rb_syserr_new_str`: [`Ruby::error_from_errno`].
 // * `rb_syswait`:
-// * `rb_sys_fail`:
-// * `rb_sys_fail_str`:
+//! * `rb_sys_fail`: See [`Ruby::error_sys_fail`].
+//! * `rb_sys_fail_str`: See [`Ruby::error_sys_fail`].
 // * `rb_sys_warning`:
 //!
 //! ## `rb_t`
@@ -1533,8 +1535,8 @@
 //! * `rb_thread_wait_for`: [`Ruby::thread_sleep`].
 //! * `rb_thread_wakeup`: [`Thread::wakeup`].
 //! * `rb_thread_wakeup_alive`: [`Thread::wakeup_alive`].
-// * `rb_throw`:
-// * `rb_throw_obj`:
+//! * `rb_throw`: See [`Ruby::throw`].
+//! * `rb_throw_obj`: [`Ruby::throw`].
 // * `rb_timespec_now`:
 // * `rb_time_interval`:
 //! * `rb_time_nano_new`: [`Ruby::time_nano_new`].
@@ -1618,7 +1620,7 @@
 // * `rb_w32_fd_copy`:
 // * `rb_w32_fd_dup`:
 //! * `rb_waitpid`: [`Ruby::waitpid`].
-// * `rb_warn`:
+//! * `rb_warn`: [`Ruby::warn`].
 //! * `rb_warning`: [`Ruby::warning`].
 // * `rb_write_error`:
 // * `rb_write_error2`:
@@ -1802,16 +1804,19 @@ pub mod exception;
 pub mod fiber;
 mod float;
 pub mod gc;
+pub mod hierarchy;
 mod integer;
 mod into_value;
 #[cfg(feature = "io")]
 #[cfg_attr(docsrs, doc(cfg(feature = "io")))]
 pub mod io;
+pub mod iterator;
 pub mod method;
 pub mod module;
 mod mutex;
 pub mod numeric;
 mod object;
+pub mod operators;
 pub mod process;
 /// Traits that commonly should be in scope.
 pub mod prelude {
@@ -1838,6 +1843,7 @@ mod range;
 #[cfg(feature = "rb-sys")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rb-sys")))]
 pub mod rb_sys;
+pub mod rbs;
 pub mod scan_args;
 pub mod symbol;
 mod thread;
@@ -1856,14 +1862,14 @@ use ::rb_sys::{
     rb_define_class, rb_define_global_const, rb_define_global_function, rb_define_module,
     rb_define_variable, rb_errinfo, rb_eval_string_protect, rb_require_string, rb_set_errinfo,
 };
-pub use magnus_macros::{DataTypeFunctions, TypedData, init, wrap};
+pub use magnus_macros::{DataTypeFunctions, RubyErrors, SymbolEnum, TypedData, init, wrap};
 
 #[cfg(ruby_use_flonum)]
 pub use crate::value::Flonum;
 pub use crate::{
     api::Ruby,
     class::{Class, RClass},
-    enumerator::Enumerator,
+    enumerator::{Enumerator, EnumeratorSize},
     error::Error,
     exception::{Exception, ExceptionClass},
     fiber::Fiber,
@@ -2247,6 +2253,11 @@ impl Ruby {
 
     /// Define a method in the root scope.
     ///
+    /// # Panics
+    ///
+    /// Panics if `func` was wrapped with an arity above 15 and defining the
+    /// method raises an exception.
+    ///
     /// # Examples
     ///
     /// ```
@@ -2268,6 +2279,12 @@ impl Ruby {
     where
         M: Method,
     {
+        if let Some(arity) = M::declared_arity() {
+            let kernel = self.module_kernel().as_value();
+            module::define_bound_method(self, kernel, name, arity, func, Some("module_function"))
+                .unwrap();
+            return;
+        }
         let name = CString::new(name).unwrap();
         unsafe {
             rb_define_global_function(
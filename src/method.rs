@@ -21,7 +21,10 @@ use crate::{
     error::{Error, IntoError, raise},
     into_value::{ArgList, IntoValue},
     r_array::RArray,
+    rbs::{MethodSignature, RbsType},
+    scan_args::ScanArgsSplat,
     try_convert::TryConvert,
+    typed_data::borrow,
     value::{ReprValue, Value},
 };
 
@@ -32,6 +35,71 @@ mod private {
         fn arity() -> i8;
         #[allow(clippy::wrong_self_convention)]
         fn as_ptr(self) -> *mut c_void;
+
+        /// The required and maximum number of arguments, for functions
+        /// defined as taking any number of arguments, so that `Method#arity`
+        /// can be reported correctly.
+        fn declared_arity() -> Option<(u8, u8)> {
+            None
+        }
+
+        /// The signatures of the wrapped function, more than one for an
+        /// overloaded method.
+        #[cfg(feature = "rbs")]
+        fn signatures(&self) -> Vec<MethodSignature> {
+            Vec::new()
+        }
+    }
+
+    #[cfg(feature = "high-arity")]
+    unsafe impl<const N: u8> Method for Wide<N> {
+        fn arity() -> i8 {
+            -1
+        }
+
+        fn as_ptr(self) -> *mut c_void {
+            self.0 as *mut c_void
+        }
+
+        fn declared_arity() -> Option<(u8, u8)> {
+            Some((N, N))
+        }
+    }
+
+    unsafe impl<const REQUIRED: u8, const MAX: u8> Method for Optional<REQUIRED, MAX> {
+        fn arity() -> i8 {
+            -1
+        }
+
+        fn as_ptr(self) -> *mut c_void {
+            self.0 as *mut c_void
+        }
+
+        fn declared_arity() -> Option<(u8, u8)> {
+            Some((REQUIRED, MAX))
+        }
+    }
+
+    #[cfg(feature = "rbs")]
+    unsafe impl<F> Method for Signed<F>
+    where
+        F: Method,
+    {
+        fn arity() -> i8 {
+            F::arity()
+        }
+
+        fn as_ptr(self) -> *mut c_void {
+            self.func.as_ptr()
+        }
+
+        fn declared_arity() -> Option<(u8, u8)> {
+            F::declared_arity()
+        }
+
+        fn signatures(&self) -> Vec<MethodSignature> {
+            (self.sigs)()
+        }
     }
 
     unsafe impl Method for unsafe extern "C" fn(Value, RArray) -> Value {
@@ -184,6 +252,39 @@ mod private {
         }
     }
 
+    pub trait BlockArg: Sized {
+        const REQUIRED: bool;
+
+        fn from_block(ruby: &Ruby) -> Result<Self, Error>;
+    }
+
+    impl BlockArg for Proc {
+        const REQUIRED: bool = true;
+
+        fn from_block(ruby: &Ruby) -> Result<Self, Error> {
+            if ruby.block_given() {
+                ruby.block_proc()
+            } else {
+                Err(Error::new(
+                    ruby.exception_local_jump_error(),
+                    "no block given (yield)",
+                ))
+            }
+        }
+    }
+
+    impl BlockArg for Option<Proc> {
+        const REQUIRED: bool = false;
+
+        fn from_block(ruby: &Ruby) -> Result<Self, Error> {
+            if ruby.block_given() {
+                ruby.block_proc().map(Some)
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
     pub trait InitReturn {
         fn into_init_return(self) -> Result<(), Error>;
     }
@@ -254,6 +355,49 @@ pub trait Method: private::Method {}
 
 impl<T> Method for T where T: private::Method {}
 
+/// A function pointer paired with its [RBS](crate::rbs) signature.
+///
+/// Returned by the [`method`](crate::method!) and
+/// [`function`](crate::function!) macros when the `rbs` feature is enabled.
+#[cfg(feature = "rbs")]
+#[doc(hidden)]
+pub struct Signed<F> {
+    func: F,
+    sigs: fn() -> Vec<MethodSignature>,
+}
+
+#[cfg(feature = "rbs")]
+impl<F> Signed<F> {
+    #[doc(hidden)]
+    pub fn new(func: F, sigs: fn() -> Vec<MethodSignature>) -> Self {
+        Self { func, sigs }
+    }
+}
+
+/// A function with more parameters than Ruby's C API supports, wrapped as
+/// taking any number of arguments.
+///
+/// Returned by the [`method`](crate::method!) and
+/// [`function`](crate::function!) macros for arities 16 to 31. Methods are
+/// defined with a block taking `N` parameters, so that `Method#arity` is
+/// correct.
+#[cfg(feature = "high-arity")]
+#[doc(hidden)]
+pub struct Wide<const N: u8>(pub unsafe extern "C" fn(c_int, *const Value, Value) -> Value);
+
+/// A function with trailing optional arguments, wrapped as taking any number
+/// of arguments.
+///
+/// Returned by the [`method`](crate::method!) and
+/// [`function`](crate::function!) macros for a range of arities. Methods are
+/// defined with a block taking `REQUIRED` parameters followed by optional
+/// ones up to `MAX`, so that `Method#arity` is `-(REQUIRED + 1)` (or
+/// `REQUIRED` when there are no optional arguments).
+#[doc(hidden)]
+pub struct Optional<const REQUIRED: u8, const MAX: u8>(
+    pub unsafe extern "C" fn(c_int, *const Value, Value) -> Value,
+);
+
 /// Trait marking types that can be returned to Ruby.
 ///
 /// Implemented for the following types:
@@ -282,6 +426,21 @@ pub trait ReturnValue: private::ReturnValue {}
 
 impl<T> ReturnValue for T where T: private::ReturnValue {}
 
+/// Trait marking types that can be taken as a trailing block parameter by
+/// functions wrapped with the [`method`](crate::method!) and
+/// [`function`](crate::function!) macros.
+///
+/// Implemented for the following types:
+///
+/// * [`Proc`]
+/// * `Option<Proc>`
+///
+/// When the parameter is `Proc` and the method is called without a block a
+/// `LocalJumpError` is raised.
+pub trait BlockArg: private::BlockArg {}
+
+impl<T> BlockArg for T where T: private::BlockArg {}
+
 /// Trait marking types that can be returned to Ruby from a library
 /// [`init`](magnus_macros::init) function.
 ///
@@ -329,7 +488,11 @@ where
 {
     #[inline]
     unsafe fn call_handle_error(self) {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| (self)().into_init_return())) {
+        crate::error::init_panic_backtrace();
+        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let _borrows = borrow::Scope::new();
+            (self)().into_init_return()
+        })) {
             Ok(v) => v,
             Err(e) => Err(Error::from_panic(e)),
         };
@@ -359,8 +522,10 @@ where
 {
     #[inline]
     unsafe fn call_handle_error(self) {
+        crate::error::init_panic_backtrace();
         unsafe {
             let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
                 (self)(&Ruby::get_unchecked()).into_init_return()
             })) {
                 Ok(v) => v,
@@ -409,6 +574,7 @@ where
     unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, blockarg: Value) -> Value {
         unsafe {
             let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
                 self.call_convert_value(argc, argv, blockarg)
             })) {
                 Ok(v) => v,
@@ -447,8 +613,10 @@ where
     #[inline]
     unsafe fn call_handle_error(self) -> Value {
         unsafe {
-            let res = match std::panic::catch_unwind(AssertUnwindSafe(|| self.call_convert_value()))
-            {
+            let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
+                self.call_convert_value()
+            })) {
                 Ok(v) => v,
                 Err(e) => Err(Error::from_panic(e)),
             };
@@ -483,8 +651,10 @@ where
     #[inline]
     unsafe fn call_handle_error(self) -> Value {
         unsafe {
-            let res = match std::panic::catch_unwind(AssertUnwindSafe(|| self.call_convert_value()))
-            {
+            let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
+                self.call_convert_value()
+            })) {
                 Ok(v) => v,
                 Err(e) => Err(Error::from_panic(e)),
             };
@@ -527,6 +697,7 @@ where
     #[inline]
     unsafe fn call_handle_error(self, rb_self: Value, args: RArray) -> Value {
         let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let _borrows = borrow::Scope::new();
             self.call_convert_value(rb_self, args)
         })) {
             Ok(v) => v,
@@ -537,6 +708,14 @@ where
             Err(e) => raise(e),
         }
     }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
 }
 
 impl<Func, RbSelf, Args, Res> MethodRbAry<RbSelf, Args, Res> for Func
@@ -574,6 +753,7 @@ where
     #[inline]
     unsafe fn call_handle_error(self, rb_self: Value, args: RArray) -> Value {
         let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let _borrows = borrow::Scope::new();
             self.call_convert_value(rb_self, args)
         })) {
             Ok(v) => v,
@@ -584,6 +764,14 @@ where
             Err(e) => raise(e),
         }
     }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
 }
 
 impl<Func, RbSelf, Args, Res> RubyMethodRbAry<RbSelf, Args, Res> for Func
@@ -623,6 +811,7 @@ where
     unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, rb_self: Value) -> Value {
         unsafe {
             let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
                 self.call_convert_value(argc, argv, rb_self)
             })) {
                 Ok(v) => v,
@@ -634,6 +823,14 @@ where
             }
         }
     }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
 }
 
 impl<Func, RbSelf, Res> MethodCAry<RbSelf, Res> for Func
@@ -678,6 +875,7 @@ where
     unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, rb_self: Value) -> Value {
         unsafe {
             let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
                 self.call_convert_value(argc, argv, rb_self)
             })) {
                 Ok(v) => v,
@@ -689,6 +887,14 @@ where
             }
         }
     }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
 }
 
 impl<Func, RbSelf, Res> RubyMethodCAry<RbSelf, Res> for Func
@@ -699,8 +905,340 @@ where
 {
 }
 
+/// Trait for the tuple of required arguments taken by functions wrapped with
+/// an arity of `*`.
+///
+/// Implemented for tuples of up to 31 types implementing [`TryConvert`].
+#[doc(hidden)]
+pub trait SplatRequired: Sized {
+    /// The number of required arguments.
+    const LEN: usize;
+
+    /// Convert exactly [`LEN`](SplatRequired::LEN) values to `Self`.
+    fn from_slice(vals: &[Value]) -> Result<Self, Error>;
+}
+
+macro_rules! impl_splat_required {
+    ($n:literal) => {
+        seq!(N in 0..$n {
+            impl<#(T~N,)*> SplatRequired for (#(T~N,)*)
+            where
+                #(T~N: TryConvert,)*
+            {
+                const LEN: usize = $n;
+
+                #[allow(unused_variables)]
+                fn from_slice(vals: &[Value]) -> Result<Self, Error> {
+                    Ok((#(TryConvert::try_convert(vals[N])?,)*))
+                }
+            }
+        });
+    };
+}
+
+seq!(N in 0..=31 {
+    impl_splat_required!(N);
+});
+
+/// Splits the arguments to a function wrapped with an arity of `*` into the
+/// required arguments and the remaining arguments, or returns an
+/// `ArgumentError` if there are too few (or too many, when `Splat` is `()`).
+unsafe fn splat_args<Req, Splat>(argc: c_int, argv: *const Value) -> Result<(Req, Splat), Error>
+where
+    Req: SplatRequired,
+    Splat: ScanArgsSplat,
+{
+    let ruby = unsafe { Ruby::get_unchecked() };
+    let args = unsafe { slice::from_raw_parts(argv, argc as usize) };
+    if args.len() < Req::LEN || (!Splat::REQ && args.len() > Req::LEN) {
+        let plus = if Splat::REQ { "+" } else { "" };
+        return Err(Error::new(
+            ruby.exception_arg_error(),
+            format!(
+                "wrong number of arguments (given {}, expected {}{})",
+                args.len(),
+                Req::LEN,
+                plus
+            ),
+        ));
+    }
+    let (required, rest) = args.split_at(Req::LEN);
+    let rest = Splat::REQ.then(|| ruby.ary_new_from_values(rest).as_value());
+    Ok((Req::from_slice(required)?, Splat::from_opt(rest)?))
+}
+
+/// Helper trait for wrapping a function as a Ruby method taking self, a
+/// tuple of required arguments, and the remaining arguments, with type
+/// conversions and error handling.
+///
+/// See the [`method`](crate::method!) macro.
+#[doc(hidden)]
+pub trait MethodSplat<RbSelf, Req, Splat, Res>
+where
+    Self: Sized + Fn(RbSelf, Req, Splat) -> Res,
+    RbSelf: TryConvert,
+    Req: SplatRequired,
+    Splat: ScanArgsSplat,
+    Res: ReturnValue,
+{
+    #[inline]
+    unsafe fn call_convert_value(
+        self,
+        argc: c_int,
+        argv: *const Value,
+        rb_self: Value,
+    ) -> Result<Value, Error> {
+        unsafe {
+            let (required, splat) = splat_args::<Req, Splat>(argc, argv)?;
+            (self)(TryConvert::try_convert(rb_self)?, required, splat).into_return_value()
+        }
+    }
+
+    #[inline]
+    unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, rb_self: Value) -> Value {
+        unsafe {
+            let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
+                self.call_convert_value(argc, argv, rb_self)
+            })) {
+                Ok(v) => v,
+                Err(e) => Err(Error::from_panic(e)),
+            };
+            match res {
+                Ok(v) => v,
+                Err(e) => raise(e),
+            }
+        }
+    }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
+}
+
+impl<Func, RbSelf, Req, Splat, Res> MethodSplat<RbSelf, Req, Splat, Res> for Func
+where
+    Func: Fn(RbSelf, Req, Splat) -> Res,
+    RbSelf: TryConvert,
+    Req: SplatRequired,
+    Splat: ScanArgsSplat,
+    Res: ReturnValue,
+{
+}
+
+/// Helper trait for wrapping a function as a Ruby method taking
+/// [`&Ruby`](Ruby), self, a tuple of required arguments, and the remaining
+/// arguments, with type conversions and error handling.
+///
+/// See the [`method`](crate::method!) macro.
+#[doc(hidden)]
+pub trait RubyMethodSplat<RbSelf, Req, Splat, Res>
+where
+    Self: Sized + Fn(&Ruby, RbSelf, Req, Splat) -> Res,
+    RbSelf: TryConvert,
+    Req: SplatRequired,
+    Splat: ScanArgsSplat,
+    Res: ReturnValue,
+{
+    #[inline]
+    unsafe fn call_convert_value(
+        self,
+        argc: c_int,
+        argv: *const Value,
+        rb_self: Value,
+    ) -> Result<Value, Error> {
+        unsafe {
+            let (required, splat) = splat_args::<Req, Splat>(argc, argv)?;
+            (self)(
+                &Ruby::get_with(rb_self),
+                TryConvert::try_convert(rb_self)?,
+                required,
+                splat,
+            )
+            .into_return_value()
+        }
+    }
+
+    #[inline]
+    unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, rb_self: Value) -> Value {
+        unsafe {
+            let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
+                self.call_convert_value(argc, argv, rb_self)
+            })) {
+                Ok(v) => v,
+                Err(e) => Err(Error::from_panic(e)),
+            };
+            match res {
+                Ok(v) => v,
+                Err(e) => raise(e),
+            }
+        }
+    }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
+}
+
+impl<Func, RbSelf, Req, Splat, Res> RubyMethodSplat<RbSelf, Req, Splat, Res> for Func
+where
+    Func: Fn(&Ruby, RbSelf, Req, Splat) -> Res,
+    RbSelf: TryConvert,
+    Req: SplatRequired,
+    Splat: ScanArgsSplat,
+    Res: ReturnValue,
+{
+}
+
+/// Returns `N` arguments from `argv`, padded with `nil` after the first
+/// `required`, or an `ArgumentError` if `argc` is out of range.
+unsafe fn optional_args<const N: usize>(
+    required: usize,
+    argc: c_int,
+    argv: *const Value,
+) -> Result<[Value; N], Error> {
+    let ruby = unsafe { Ruby::get_unchecked() };
+    let args = unsafe { slice::from_raw_parts(argv, argc as usize) };
+    if args.len() < required || args.len() > N {
+        let expected = if required == N {
+            required.to_string()
+        } else {
+            format!("{}..{}", required, N)
+        };
+        return Err(Error::new(
+            ruby.exception_arg_error(),
+            format!(
+                "wrong number of arguments (given {}, expected {})",
+                args.len(),
+                expected
+            ),
+        ));
+    }
+    let mut values = [ruby.qnil().as_value(); N];
+    values[..args.len()].copy_from_slice(args);
+    Ok(values)
+}
+
+/// Converts the argument at `index` for a function with optional arguments,
+/// where `given` arguments were passed.
+///
+/// Arguments past `given` were not passed and are `nil`, if one of those can't
+/// be converted (it's not an `Option`) that is an `ArgumentError`, rather than
+/// the `TypeError` from converting `nil`.
+fn optional_try_convert<T>(val: Value, index: usize, given: usize) -> Result<T, Error>
+where
+    T: TryConvert,
+{
+    if index < given {
+        return T::try_convert(val);
+    }
+    T::try_convert(val).map_err(|_| {
+        Error::new(
+            Ruby::get_with(val).exception_arg_error(),
+            format!(
+                "wrong number of arguments (given {}, expected {}+)",
+                given,
+                index + 1
+            ),
+        )
+    })
+}
+
+/// Converts an argument for an overloaded function.
+///
+/// A `TypeError` means the overload does not match, and is returned as
+/// `Err(None)` so the next overload can be tried. Any other error is returned
+/// as `Err(Some(e))`.
+pub(crate) fn overload_try_convert<T>(val: Value) -> Result<T, Option<Error>>
+where
+    T: TryConvert,
+{
+    T::try_convert(val)
+        .map_err(|e| (!e.is_kind_of(Ruby::get_with(val).exception_type_error())).then_some(e))
+}
+
+/// One of the functions wrapped by the
+/// [`overloaded_method`](crate::overloaded_method!) and
+/// [`overloaded_function`](crate::overloaded_function!) macros.
+#[doc(hidden)]
+pub trait Overload {
+    /// Calls the function if the arguments match its parameters, otherwise
+    /// returns `None`.
+    unsafe fn try_call(&self, rb_self: Value, args: &[Value]) -> Option<Result<Value, Error>>;
+
+    /// Returns the parameter list of the function, for error messages.
+    fn signature(&self) -> String;
+}
+
+#[doc(hidden)]
+pub struct OverloadFn<C, S>(pub C, pub S);
+
+impl<C, S> Overload for OverloadFn<C, S>
+where
+    C: Fn(Value, &[Value]) -> Option<Result<Value, Error>>,
+    S: Fn() -> String,
+{
+    unsafe fn try_call(&self, rb_self: Value, args: &[Value]) -> Option<Result<Value, Error>> {
+        (self.0)(rb_self, args)
+    }
+
+    fn signature(&self) -> String {
+        (self.1)()
+    }
+}
+
+#[doc(hidden)]
+pub unsafe fn overload_handle_error(
+    overloads: &[&dyn Overload],
+    argc: c_int,
+    argv: *const Value,
+    rb_self: Value,
+) -> Value {
+    let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let args = unsafe { slice::from_raw_parts(argv, argc as usize) };
+        for overload in overloads {
+            // each attempt gets its own scope, so borrows taken while
+            // converting the arguments for an overload that doesn't match are
+            // released before the next is tried
+            let _borrows = borrow::Scope::new();
+            if let Some(res) = unsafe { overload.try_call(rb_self, args) } {
+                return res;
+            }
+        }
+        let given = args
+            .iter()
+            .map(|v| unsafe { v.classname() }.into_owned())
+            .collect::<Vec<_>>();
+        let expected = overloads.iter().map(|o| o.signature()).collect::<Vec<_>>();
+        Err(Error::new(
+            Ruby::get_with(rb_self).exception_type_error(),
+            format!(
+                "no overload matches ({}), expected one of {}",
+                given.join(", "),
+                expected.join(", ")
+            ),
+        ))
+    })) {
+        Ok(v) => v,
+        Err(e) => Err(Error::from_panic(e)),
+    };
+    match res {
+        Ok(v) => v,
+        Err(e) => raise(e),
+    }
+}
+
 macro_rules! method_n {
-    ($name:ident, $ruby_name:ident, $n:literal) => {
+    ($name:ident, $ruby_name:ident, $block_name:ident, $ruby_block_name:ident, $n:literal) => {
         seq!(N in 0..$n {
             /// Helper trait for wrapping a function as a Ruby method taking
             /// self and N arguments, with type conversions and error handling.
@@ -722,10 +1260,19 @@ macro_rules! method_n {
                     ).into_return_value()
                 }
 
+                #[inline]
+                fn call_convert_optional(self, given: usize, rb_self: Value, #(arg~N: Value,)*) -> Result<Value, Error> {
+                    (self)(
+                        TryConvert::try_convert(rb_self)?,
+                        #(optional_try_convert(arg~N, N, given)?,)*
+                    ).into_return_value()
+                }
+
                 #[inline]
                 unsafe fn call_handle_error(self, rb_self: Value, #(arg~N: Value,)*) -> Value {
                     let res =
                         match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
                             self.call_convert_value(rb_self, #(arg~N,)*)
                         })) {
                             Ok(v) => v,
@@ -736,7 +1283,67 @@ macro_rules! method_n {
                         Err(e) => raise(e),
                     }
                 }
-            }
+
+                #[inline]
+                unsafe fn call_handle_error_optional(
+                    self,
+                    required: usize,
+                    argc: c_int,
+                    argv: *const Value,
+                    rb_self: Value,
+                ) -> Value {
+                    let res =
+                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
+                            let [#(arg~N,)*] = unsafe { optional_args::<$n>(required, argc, argv)? };
+                            self.call_convert_optional(argc as usize, rb_self, #(arg~N,)*)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+
+                /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+                fn rbs_signature(&self) -> MethodSignature
+                where
+                    #(T~N: RbsType,)*
+                    Res: RbsType,
+                {
+                    MethodSignature::new(vec![#(T~N::rbs_type(),)*], Res::rbs_type())
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                unsafe fn try_call_convert_value(
+                    self,
+                    rb_self: Value,
+                    args: &[Value],
+                ) -> Option<Result<Value, Error>> {
+                    let &[#(arg~N,)*] = <&[Value; $n]>::try_from(args).ok()?;
+                    let this = match overload_try_convert::<RbSelf>(rb_self) {
+                        Ok(v) => v,
+                        Err(e) => return e.map(Err),
+                    };
+                    #(let arg~N = match overload_try_convert::<T~N>(arg~N) {
+                        Ok(v) => v,
+                        Err(e) => return e.map(Err),
+                    };)*
+                    Some((self)(this, #(arg~N,)*).into_return_value())
+                }
+
+                #[doc(hidden)]
+                fn overload_signature(&self) -> String
+                where
+                    #(T~N: RbsType,)*
+                {
+                    let params: [String; $n] = [#(T~N::rbs_type(),)*];
+                    format!("({})", params.join(", "))
+                }
+            }
 
             impl<Func, RbSelf, #(T~N,)* Res> $name<RbSelf, #(T~N,)* Res> for Func
             where
@@ -768,10 +1375,20 @@ macro_rules! method_n {
                     ).into_return_value()
                 }
 
+                #[inline]
+                fn call_convert_optional(self, given: usize, rb_self: Value, #(arg~N: Value,)*) -> Result<Value, Error> {
+                    (self)(
+                        &Ruby::get_with(rb_self),
+                        TryConvert::try_convert(rb_self)?,
+                        #(optional_try_convert(arg~N, N, given)?,)*
+                    ).into_return_value()
+                }
+
                 #[inline]
                 unsafe fn call_handle_error(self, rb_self: Value, #(arg~N: Value,)*) -> Value {
                     let res =
                         match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
                             self.call_convert_value(rb_self, #(arg~N,)*)
                         })) {
                             Ok(v) => v,
@@ -782,6 +1399,66 @@ macro_rules! method_n {
                         Err(e) => raise(e),
                     }
                 }
+
+                #[inline]
+                unsafe fn call_handle_error_optional(
+                    self,
+                    required: usize,
+                    argc: c_int,
+                    argv: *const Value,
+                    rb_self: Value,
+                ) -> Value {
+                    let res =
+                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
+                            let [#(arg~N,)*] = unsafe { optional_args::<$n>(required, argc, argv)? };
+                            self.call_convert_optional(argc as usize, rb_self, #(arg~N,)*)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+
+                /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+                fn rbs_signature(&self) -> MethodSignature
+                where
+                    #(T~N: RbsType,)*
+                    Res: RbsType,
+                {
+                    MethodSignature::new(vec![#(T~N::rbs_type(),)*], Res::rbs_type())
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                unsafe fn try_call_convert_value(
+                    self,
+                    rb_self: Value,
+                    args: &[Value],
+                ) -> Option<Result<Value, Error>> {
+                    let &[#(arg~N,)*] = <&[Value; $n]>::try_from(args).ok()?;
+                    let this = match overload_try_convert::<RbSelf>(rb_self) {
+                        Ok(v) => v,
+                        Err(e) => return e.map(Err),
+                    };
+                    #(let arg~N = match overload_try_convert::<T~N>(arg~N) {
+                        Ok(v) => v,
+                        Err(e) => return e.map(Err),
+                    };)*
+                    Some((self)(&Ruby::get_with(rb_self), this, #(arg~N,)*).into_return_value())
+                }
+
+                #[doc(hidden)]
+                fn overload_signature(&self) -> String
+                where
+                    #(T~N: RbsType,)*
+                {
+                    let params: [String; $n] = [#(T~N::rbs_type(),)*];
+                    format!("({})", params.join(", "))
+                }
             }
 
             impl<Func, RbSelf, #(T~N,)* Res> $ruby_name<RbSelf, #(T~N,)* Res> for Func
@@ -791,12 +1468,137 @@ macro_rules! method_n {
                 #(T~N: TryConvert,)*
                 Res: ReturnValue,
             {}
+
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// self, N arguments, and a block, with type conversions and error
+            /// handling.
+            ///
+            /// See the [`method`](crate::method!) macro.
+            #[doc(hidden)]
+            pub trait $block_name<RbSelf, #(T~N,)* B, Res>
+            where
+                Self: Sized + Fn(RbSelf, #(T~N,)* B) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                B: BlockArg,
+                Res: ReturnValue,
+            {
+                #[inline]
+                fn call_convert_value(self, rb_self: Value, #(arg~N: Value,)*) -> Result<Value, Error> {
+                    (self)(
+                        TryConvert::try_convert(rb_self)?,
+                        #(TryConvert::try_convert(arg~N)?,)*
+                        B::from_block(&Ruby::get_with(rb_self))?,
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(self, rb_self: Value, #(arg~N: Value,)*) -> Value {
+                    let res =
+                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
+                            self.call_convert_value(rb_self, #(arg~N,)*)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+
+                /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+                fn rbs_signature(&self) -> MethodSignature
+                where
+                    #(T~N: RbsType,)*
+                    Res: RbsType,
+                {
+                    MethodSignature::new(vec![#(T~N::rbs_type(),)*], Res::rbs_type())
+                        .with_block(B::REQUIRED)
+                }
+            }
+
+            impl<Func, RbSelf, #(T~N,)* B, Res> $block_name<RbSelf, #(T~N,)* B, Res> for Func
+            where
+                Func: Fn(RbSelf, #(T~N,)* B) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                B: BlockArg,
+                Res: ReturnValue,
+            {}
+
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// [`&Ruby`](Ruby), self, N arguments, and a block, with type
+            /// conversions and error handling.
+            ///
+            /// See the [`method`](crate::method!) macro.
+            #[doc(hidden)]
+            pub trait $ruby_block_name<RbSelf, #(T~N,)* B, Res>
+            where
+                Self: Sized + Fn(&Ruby, RbSelf, #(T~N,)* B) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                B: BlockArg,
+                Res: ReturnValue,
+            {
+                #[inline]
+                fn call_convert_value(self, rb_self: Value, #(arg~N: Value,)*) -> Result<Value, Error> {
+                    let ruby = Ruby::get_with(rb_self);
+                    (self)(
+                        &ruby,
+                        TryConvert::try_convert(rb_self)?,
+                        #(TryConvert::try_convert(arg~N)?,)*
+                        B::from_block(&ruby)?,
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(self, rb_self: Value, #(arg~N: Value,)*) -> Value {
+                    let res =
+                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
+                            self.call_convert_value(rb_self, #(arg~N,)*)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+
+                /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+                fn rbs_signature(&self) -> MethodSignature
+                where
+                    #(T~N: RbsType,)*
+                    Res: RbsType,
+                {
+                    MethodSignature::new(vec![#(T~N::rbs_type(),)*], Res::rbs_type())
+                        .with_block(B::REQUIRED)
+                }
+            }
+
+            impl<Func, RbSelf, #(T~N,)* B, Res> $ruby_block_name<RbSelf, #(T~N,)* B, Res> for Func
+            where
+                Func: Fn(&Ruby, RbSelf, #(T~N,)* B) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                B: BlockArg,
+                Res: ReturnValue,
+            {}
         });
     }
 }
 
 seq!(N in 0..=15 {
-    method_n!(Method~N, RubyMethod~N, N);
+    method_n!(Method~N, RubyMethod~N, MethodBlock~N, RubyMethodBlock~N, N);
+});
+
+#[cfg(feature = "high-arity")]
+seq!(N in 16..=31 {
+    method_n!(Method~N, RubyMethod~N, MethodBlock~N, RubyMethodBlock~N, N);
 });
 
 /// Wrap a Rust function item with Ruby type conversion and error handling.
@@ -806,6 +1608,10 @@ seq!(N in 0..=15 {
 /// define Ruby methods such as
 /// [`define_method`](crate::module::Module::define_method).
 ///
+/// With the `rbs` feature enabled the returned value also carries the
+/// [RBS](crate::rbs) signature of the function, so that it is recorded when
+/// the method is defined. See [`Signatures::bound`](crate::rbs::Signatures::bound).
+///
 /// Ruby code implicitly always has a `self` parameter available. In the
 /// extension API this is passed explicitly. As a result there is always an
 /// extra `self` argument before the arguments explicitly passed in Ruby, and the
@@ -826,7 +1632,8 @@ seq!(N in 0..=15 {
 /// |     1 | `fn(rb_self: T, arg1: U) -> Result<R, Error>`             |
 /// |     2 | `fn(rb_self: T, arg1: U, arg2: V) -> Result<R, Error>`    |
 /// |   ... | ...                                                       |
-/// |    16 | ...                                                       |
+/// |    31 | ...                                                       |
+/// |   `*` | `fn(rb_self: T, (U, V), rest: W) -> Result<R, Error>`     |
 ///
 /// Where `T`, `U`, `V` and so on are any types that implement `TryConvert`,
 /// and `R` implements [`IntoValue`]. It is also possible to return just `R`
@@ -834,6 +1641,39 @@ seq!(N in 0..=15 {
 /// return value (i.e. return `()`) for a function that returns `nil` to Ruby.
 /// See [`ReturnValue`] for more details on what can be returned.
 ///
+/// A range `required..=max` for `arity` wraps a function taking `max`
+/// arguments (plus `self`), where the arguments after the first `required`
+/// are optional. Omitted arguments are passed as `nil`, so should be an
+/// `Option<T>`, e.g. `method!(f, 1..=3)` for
+/// `fn(rb_self: T, arg1: U, arg2: Option<V>, arg3: Option<W>)`. Calling the
+/// method with too few or too many arguments raises an `ArgumentError`, such
+/// as `wrong number of arguments (given 4, expected 1..3)`, as does omitting
+/// an argument that can't be converted from `nil`. The method is defined via
+/// a Ruby block with optional parameters, so `Method#arity` is
+/// `-(required + 1)`.
+///
+/// Ruby's C API only supports fixed arities up to 15. Arities 16 to 31, and
+/// ranges with a `max` above 15, require the `high-arity` feature. Arities 16
+/// to 31 are wrapped as a function taking any number of arguments, and
+/// defined via a Ruby block taking `arity` parameters, so that `Method#arity`
+/// is correct. This adds the overhead of a block call to each call of the
+/// method, as it does for ranges.
+///
+/// An `arity` of `*` wraps a function taking a tuple of required arguments,
+/// followed by the remaining arguments, as they would be returned by
+/// [`scan_args`](crate::scan_args::scan_args). The tuple can have up to 31
+/// elements, and the remaining arguments can be an [`RArray`], a `Vec<T>`, or
+/// `()` for no further arguments, e.g. `method!(f, *)` for
+/// `fn(rb_self: T, args: (U, V), rest: RArray)`.
+///
+/// The function may take one more parameter than `arity`, as long as that
+/// final parameter is a [`Proc`] or `Option<Proc>` (see [`BlockArg`]). It is
+/// filled from the block passed to the method, rather than from the method's
+/// arguments. When the parameter is `Proc` and the method is called without a
+/// block a `LocalJumpError` is raised, as with `yield`. Block parameters can't
+/// be combined with a `required..=max` range `arity`, `*`, or arities above
+/// 15.
+///
 /// See the [`function`](crate::function!) macro for cases where there is no
 /// need to handle the `self` argument.
 ///
@@ -855,8 +1695,182 @@ seq!(N in 0..=15 {
 /// # let cleanup = unsafe { magnus::embed::init() };
 /// # init(&cleanup).unwrap();
 /// ```
+///
+/// Optional arguments.
+///
+/// ```
+/// use magnus::{Error, Ruby, method, prelude::*, rb_assert};
+///
+/// fn rb_center(rb_self: String, width: usize, pad: Option<char>) -> String {
+///     let pad = pad.unwrap_or(' ');
+///     let fill = width.saturating_sub(rb_self.chars().count());
+///     let left = std::iter::repeat_n(pad, fill / 2).collect::<String>();
+///     let right = std::iter::repeat_n(pad, fill - fill / 2).collect::<String>();
+///     format!("{}{}{}", left, rb_self, right)
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.class_string()
+///         .define_method("rs_center", method!(rb_center, 1..=2))?;
+///
+///     rb_assert!(ruby, r#""ab".rs_center(6) == "  ab  ""#);
+///     rb_assert!(ruby, r#""ab".rs_center(5, "*") == "*ab**""#);
+///     rb_assert!(
+///         ruby,
+///         r#"("ab".rs_center rescue $!).message == "wrong number of arguments (given 0, expected 1..2)""#
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+///
+/// Block parameter.
+///
+/// ```
+/// use magnus::{Error, Ruby, block::Proc, method, prelude::*, rb_assert};
+///
+/// fn rb_map_chars(rb_self: String, block: Proc) -> Result<Vec<String>, Error> {
+///     rb_self.chars().map(|c| block.call((c,))).collect()
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.class_string()
+///         .define_method("map_chars", method!(rb_map_chars, 0))?;
+///
+///     rb_assert!(ruby, r#""ab".map_chars(&:upcase) == ["A", "B"]"#);
+///     rb_assert!(ruby, r#"("ab".map_chars rescue $!).class == LocalJumpError"#);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
 #[macro_export]
 macro_rules! method {
+    ($name:expr_2021, $($arity:tt)+) => {
+        $crate::__method_signed!($name, $($arity)+)
+    };
+}
+
+#[cfg(feature = "rbs")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_signed {
+    ($name:expr_2021, $($arity:tt)+) => {
+        $crate::method::Signed::new($crate::__method_ptr!($name, $($arity)+), || {
+            vec![$crate::method_signature!($name, $($arity)+)]
+        })
+    };
+}
+
+#[cfg(not(feature = "rbs"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_signed {
+    ($name:expr_2021, $($arity:tt)+) => {
+        $crate::__method_ptr!($name, $($arity)+)
+    };
+}
+
+#[cfg(feature = "high-arity")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_high_arity {
+    ($arity:tt) => {};
+}
+
+#[cfg(not(feature = "high-arity"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_high_arity {
+    (16) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (17) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (18) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (19) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (20) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (21) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (22) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (23) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (24) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (25) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (26) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (27) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (28) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (29) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (30) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    (31) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+    ($arity:tt) => {};
+}
+
+#[cfg(feature = "high-arity")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_wide {
+    ($name:expr_2021, $arity:tt) => {
+        $crate::method::Wide::<$arity>($crate::__method_ptr!(@optional $name, $arity, $arity))
+    };
+}
+
+#[cfg(not(feature = "high-arity"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_wide {
+    ($name:expr_2021, $arity:tt) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_ptr {
+    (@optional $name:expr_2021, $required:tt, $max:tt) => {{
+        unsafe extern "C" fn anon(
+            argc: std::ffi::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            $crate::__check_high_arity!($max);
+            const { assert!($required <= $max, "required arguments exceed maximum") };
+            $crate::__method_optional!($name, $required, $max, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::ffi::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
     ($name:expr_2021, -2) => {{
         unsafe extern "C" fn anon(rb_self: $crate::Value, args: $crate::RArray) -> $crate::Value {
             use $crate::method::{MethodRbAry, RubyMethodRbAry};
@@ -881,14 +1895,14 @@ macro_rules! method {
     }};
     ($name:expr_2021, 0) => {{
         unsafe extern "C" fn anon(rb_self: $crate::Value) -> $crate::Value {
-            use $crate::method::{Method0, RubyMethod0};
+            use $crate::method::{Method0, MethodBlock0, RubyMethod0, RubyMethodBlock0};
             unsafe { $name.call_handle_error(rb_self) }
         }
         anon as unsafe extern "C" fn($crate::Value) -> $crate::Value
     }};
     ($name:expr_2021, 1) => {{
         unsafe extern "C" fn anon(rb_self: $crate::Value, a: $crate::Value) -> $crate::Value {
-            use $crate::method::{Method1, RubyMethod1};
+            use $crate::method::{Method1, MethodBlock1, RubyMethod1, RubyMethodBlock1};
             unsafe { $name.call_handle_error(rb_self, a) }
         }
         anon as unsafe extern "C" fn($crate::Value, $crate::Value) -> $crate::Value
@@ -899,7 +1913,7 @@ macro_rules! method {
             a: $crate::Value,
             b: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method2, RubyMethod2};
+            use $crate::method::{Method2, MethodBlock2, RubyMethod2, RubyMethodBlock2};
             unsafe { $name.call_handle_error(rb_self, a, b) }
         }
         anon as unsafe extern "C" fn($crate::Value, $crate::Value, $crate::Value) -> $crate::Value
@@ -911,7 +1925,7 @@ macro_rules! method {
             b: $crate::Value,
             c: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method3, RubyMethod3};
+            use $crate::method::{Method3, MethodBlock3, RubyMethod3, RubyMethodBlock3};
             unsafe { $name.call_handle_error(rb_self, a, b, c) }
         }
         anon as unsafe extern "C" fn(
@@ -929,7 +1943,7 @@ macro_rules! method {
             c: $crate::Value,
             d: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method4, RubyMethod4};
+            use $crate::method::{Method4, MethodBlock4, RubyMethod4, RubyMethodBlock4};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d) }
         }
         anon as unsafe extern "C" fn(
@@ -949,7 +1963,7 @@ macro_rules! method {
             d: $crate::Value,
             e: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method5, RubyMethod5};
+            use $crate::method::{Method5, MethodBlock5, RubyMethod5, RubyMethodBlock5};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e) }
         }
         anon as unsafe extern "C" fn(
@@ -971,7 +1985,7 @@ macro_rules! method {
             e: $crate::Value,
             f: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method6, RubyMethod6};
+            use $crate::method::{Method6, MethodBlock6, RubyMethod6, RubyMethodBlock6};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f) }
         }
         anon as unsafe extern "C" fn(
@@ -995,7 +2009,7 @@ macro_rules! method {
             f: $crate::Value,
             g: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method7, RubyMethod7};
+            use $crate::method::{Method7, MethodBlock7, RubyMethod7, RubyMethodBlock7};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f, g) }
         }
         anon as unsafe extern "C" fn(
@@ -1021,7 +2035,7 @@ macro_rules! method {
             g: $crate::Value,
             h: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method8, RubyMethod8};
+            use $crate::method::{Method8, MethodBlock8, RubyMethod8, RubyMethodBlock8};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f, g, h) }
         }
         anon as unsafe extern "C" fn(
@@ -1049,7 +2063,7 @@ macro_rules! method {
             h: $crate::Value,
             i: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method9, RubyMethod9};
+            use $crate::method::{Method9, MethodBlock9, RubyMethod9, RubyMethodBlock9};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f, g, h, i) }
         }
         anon as unsafe extern "C" fn(
@@ -1079,7 +2093,7 @@ macro_rules! method {
             i: $crate::Value,
             j: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method10, RubyMethod10};
+            use $crate::method::{Method10, MethodBlock10, RubyMethod10, RubyMethodBlock10};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f, g, h, i, j) }
         }
         anon as unsafe extern "C" fn(
@@ -1111,7 +2125,7 @@ macro_rules! method {
             j: $crate::Value,
             k: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method11, RubyMethod11};
+            use $crate::method::{Method11, MethodBlock11, RubyMethod11, RubyMethodBlock11};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f, g, h, i, j, k) }
         }
         anon as unsafe extern "C" fn(
@@ -1145,7 +2159,7 @@ macro_rules! method {
             k: $crate::Value,
             l: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method12, RubyMethod12};
+            use $crate::method::{Method12, MethodBlock12, RubyMethod12, RubyMethodBlock12};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f, g, h, i, j, k, l) }
         }
         anon as unsafe extern "C" fn(
@@ -1181,7 +2195,7 @@ macro_rules! method {
             l: $crate::Value,
             m: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method13, RubyMethod13};
+            use $crate::method::{Method13, MethodBlock13, RubyMethod13, RubyMethodBlock13};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f, g, h, i, j, k, l, m) }
         }
         anon as unsafe extern "C" fn(
@@ -1219,7 +2233,7 @@ macro_rules! method {
             m: $crate::Value,
             n: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method14, RubyMethod14};
+            use $crate::method::{Method14, MethodBlock14, RubyMethod14, RubyMethodBlock14};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f, g, h, i, j, k, l, m, n) }
         }
         anon as unsafe extern "C" fn(
@@ -1259,7 +2273,7 @@ macro_rules! method {
             n: $crate::Value,
             o: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Method15, RubyMethod15};
+            use $crate::method::{Method15, MethodBlock15, RubyMethod15, RubyMethodBlock15};
             unsafe { $name.call_handle_error(rb_self, a, b, c, d, e, f, g, h, i, j, k, l, m, n, o) }
         }
         anon as unsafe extern "C" fn(
@@ -1281,8 +2295,74 @@ macro_rules! method {
             $crate::Value,
         ) -> $crate::Value
     }};
+    ($name:expr_2021, 16) => {
+        $crate::__method_wide!($name, 16)
+    };
+    ($name:expr_2021, 17) => {
+        $crate::__method_wide!($name, 17)
+    };
+    ($name:expr_2021, 18) => {
+        $crate::__method_wide!($name, 18)
+    };
+    ($name:expr_2021, 19) => {
+        $crate::__method_wide!($name, 19)
+    };
+    ($name:expr_2021, 20) => {
+        $crate::__method_wide!($name, 20)
+    };
+    ($name:expr_2021, 21) => {
+        $crate::__method_wide!($name, 21)
+    };
+    ($name:expr_2021, 22) => {
+        $crate::__method_wide!($name, 22)
+    };
+    ($name:expr_2021, 23) => {
+        $crate::__method_wide!($name, 23)
+    };
+    ($name:expr_2021, 24) => {
+        $crate::__method_wide!($name, 24)
+    };
+    ($name:expr_2021, 25) => {
+        $crate::__method_wide!($name, 25)
+    };
+    ($name:expr_2021, 26) => {
+        $crate::__method_wide!($name, 26)
+    };
+    ($name:expr_2021, 27) => {
+        $crate::__method_wide!($name, 27)
+    };
+    ($name:expr_2021, 28) => {
+        $crate::__method_wide!($name, 28)
+    };
+    ($name:expr_2021, 29) => {
+        $crate::__method_wide!($name, 29)
+    };
+    ($name:expr_2021, 30) => {
+        $crate::__method_wide!($name, 30)
+    };
+    ($name:expr_2021, 31) => {
+        $crate::__method_wide!($name, 31)
+    };
+    ($name:expr_2021, *) => {{
+        unsafe extern "C" fn anon(
+            argc: std::ffi::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat, RubyMethodSplat};
+            unsafe { $name.call_handle_error(argc, argv, rb_self) }
+        }
+        anon as unsafe extern "C" fn(
+            std::ffi::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr_2021, $required:tt..=$max:tt) => {
+        $crate::method::Optional::<$required, $max>($crate::__method_ptr!(@optional $name, $required, $max))
+    };
     ($name:expr_2021, $arity:expr_2021) => {
-        compile_error!("arity must be an integer literal between -2..=15")
+        compile_error!("arity must be `*` or an integer literal between -2..=31")
     };
 }
 
@@ -1304,9 +2384,11 @@ where
 
     #[inline]
     unsafe fn call_handle_error(self, args: RArray) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| self.call_convert_value(args)))
-        {
-            Ok(v) => v,
+        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let _borrows = borrow::Scope::new();
+            self.call_convert_value(args)
+        })) {
+            Ok(v) => v,
             Err(e) => Err(Error::from_panic(e)),
         };
         match res {
@@ -1314,6 +2396,14 @@ where
             Err(e) => raise(e),
         }
     }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
 }
 
 impl<Func, Args, Res> FunctionRbAry<Args, Res> for Func
@@ -1347,8 +2437,10 @@ where
 
     #[inline]
     unsafe fn call_handle_error(self, args: RArray) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| self.call_convert_value(args)))
-        {
+        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let _borrows = borrow::Scope::new();
+            self.call_convert_value(args)
+        })) {
             Ok(v) => v,
             Err(e) => Err(Error::from_panic(e)),
         };
@@ -1357,6 +2449,14 @@ where
             Err(e) => raise(e),
         }
     }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
 }
 
 impl<Func, Args, Res> RubyFunctionRbAry<Args, Res> for Func
@@ -1389,6 +2489,7 @@ where
     unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
         unsafe {
             let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
                 self.call_convert_value(argc, argv)
             })) {
                 Ok(v) => v,
@@ -1400,6 +2501,14 @@ where
             }
         }
     }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
 }
 
 impl<Func, Res> FunctionCAry<Res> for Func
@@ -1432,6 +2541,7 @@ where
     unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
         unsafe {
             let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
                 self.call_convert_value(argc, argv)
             })) {
                 Ok(v) => v,
@@ -1443,6 +2553,14 @@ where
             }
         }
     }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
 }
 
 impl<Func, Res> RubyFunctionCAry<Res> for Func
@@ -1452,8 +2570,120 @@ where
 {
 }
 
+/// Helper trait for wrapping a function as a Ruby method ignoring self and
+/// taking a tuple of required arguments and the remaining arguments, with
+/// type conversions and error handling.
+///
+/// See the [`function`](crate::function!) macro.
+#[doc(hidden)]
+pub trait FunctionSplat<Req, Splat, Res>
+where
+    Self: Sized + Fn(Req, Splat) -> Res,
+    Req: SplatRequired,
+    Splat: ScanArgsSplat,
+    Res: ReturnValue,
+{
+    #[inline]
+    unsafe fn call_convert_value(self, argc: c_int, argv: *const Value) -> Result<Value, Error> {
+        unsafe {
+            let (required, splat) = splat_args::<Req, Splat>(argc, argv)?;
+            (self)(required, splat).into_return_value()
+        }
+    }
+
+    #[inline]
+    unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
+        unsafe {
+            let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
+                self.call_convert_value(argc, argv)
+            })) {
+                Ok(v) => v,
+                Err(e) => Err(Error::from_panic(e)),
+            };
+            match res {
+                Ok(v) => v,
+                Err(e) => raise(e),
+            }
+        }
+    }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
+}
+
+impl<Func, Req, Splat, Res> FunctionSplat<Req, Splat, Res> for Func
+where
+    Func: Fn(Req, Splat) -> Res,
+    Req: SplatRequired,
+    Splat: ScanArgsSplat,
+    Res: ReturnValue,
+{
+}
+
+/// Helper trait for wrapping a function as a Ruby method taking
+/// [`&Ruby`](Ruby), ignoring self, and taking a tuple of required arguments
+/// and the remaining arguments, with type conversions and error handling.
+///
+/// See the [`function`](crate::function!) macro.
+#[doc(hidden)]
+pub trait RubyFunctionSplat<Req, Splat, Res>
+where
+    Self: Sized + Fn(&Ruby, Req, Splat) -> Res,
+    Req: SplatRequired,
+    Splat: ScanArgsSplat,
+    Res: ReturnValue,
+{
+    #[inline]
+    unsafe fn call_convert_value(self, argc: c_int, argv: *const Value) -> Result<Value, Error> {
+        unsafe {
+            let (required, splat) = splat_args::<Req, Splat>(argc, argv)?;
+            (self)(&Ruby::get_unchecked(), required, splat).into_return_value()
+        }
+    }
+
+    #[inline]
+    unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
+        unsafe {
+            let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
+                self.call_convert_value(argc, argv)
+            })) {
+                Ok(v) => v,
+                Err(e) => Err(Error::from_panic(e)),
+            };
+            match res {
+                Ok(v) => v,
+                Err(e) => raise(e),
+            }
+        }
+    }
+
+    /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+    fn rbs_signature(&self) -> MethodSignature
+    where
+        Res: RbsType,
+    {
+        MethodSignature::variadic(Res::rbs_type())
+    }
+}
+
+impl<Func, Req, Splat, Res> RubyFunctionSplat<Req, Splat, Res> for Func
+where
+    Func: Fn(&Ruby, Req, Splat) -> Res,
+    Req: SplatRequired,
+    Splat: ScanArgsSplat,
+    Res: ReturnValue,
+{
+}
+
 macro_rules! function_n {
-    ($name:ident, $ruby_name:ident, $n:literal) => {
+    ($name:ident, $ruby_name:ident, $block_name:ident, $ruby_block_name:ident, $n:literal) => {
         seq!(N in 0..$n {
             /// Helper trait for wrapping a function as a Ruby method ignoring
             /// self and taking N arguments, with type conversions and error
@@ -1474,10 +2704,18 @@ macro_rules! function_n {
                     ).into_return_value()
                 }
 
+                #[inline]
+                fn call_convert_optional(self, given: usize, #(arg~N: Value,)*) -> Result<Value, Error> {
+                    (self)(
+                        #(optional_try_convert(arg~N, N, given)?,)*
+                    ).into_return_value()
+                }
+
                 #[inline]
                 unsafe fn call_handle_error(self, #(arg~N: Value,)*) -> Value {
                     let res =
                         match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
                             self.call_convert_value(#(arg~N,)*)
                         })) {
                             Ok(v) => v,
@@ -1488,6 +2726,57 @@ macro_rules! function_n {
                         Err(e) => raise(e),
                     }
                 }
+
+                #[inline]
+                unsafe fn call_handle_error_optional(
+                    self,
+                    required: usize,
+                    argc: c_int,
+                    argv: *const Value,
+                ) -> Value {
+                    let res =
+                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
+                            let [#(arg~N,)*] = unsafe { optional_args::<$n>(required, argc, argv)? };
+                            self.call_convert_optional(argc as usize, #(arg~N,)*)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+
+                /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+                fn rbs_signature(&self) -> MethodSignature
+                where
+                    #(T~N: RbsType,)*
+                    Res: RbsType,
+                {
+                    MethodSignature::new(vec![#(T~N::rbs_type(),)*], Res::rbs_type())
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                unsafe fn try_call_convert_value(self, args: &[Value]) -> Option<Result<Value, Error>> {
+                    let &[#(arg~N,)*] = <&[Value; $n]>::try_from(args).ok()?;
+                    #(let arg~N = match overload_try_convert::<T~N>(arg~N) {
+                        Ok(v) => v,
+                        Err(e) => return e.map(Err),
+                    };)*
+                    Some((self)(#(arg~N,)*).into_return_value())
+                }
+
+                #[doc(hidden)]
+                fn overload_signature(&self) -> String
+                where
+                    #(T~N: RbsType,)*
+                {
+                    let params: [String; $n] = [#(T~N::rbs_type(),)*];
+                    format!("({})", params.join(", "))
+                }
             }
 
             impl<Func, #(T~N,)* Res> $name<#(T~N,)* Res> for Func
@@ -1517,10 +2806,19 @@ macro_rules! function_n {
                     ).into_return_value()
                 }}
 
+                #[inline]
+                unsafe fn call_convert_optional(self, given: usize, #(arg~N: Value,)*) -> Result<Value, Error> { unsafe {
+                    (self)(
+                        &Ruby::get_unchecked(),
+                        #(optional_try_convert(arg~N, N, given)?,)*
+                    ).into_return_value()
+                }}
+
                 #[inline]
                 unsafe fn call_handle_error(self, #(arg~N: Value,)*) -> Value { unsafe {
                     let res =
                         match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
                             self.call_convert_value(#(arg~N,)*)
                         })) {
                             Ok(v) => v,
@@ -1531,6 +2829,58 @@ macro_rules! function_n {
                         Err(e) => raise(e),
                     }
                 }}
+
+                #[inline]
+                unsafe fn call_handle_error_optional(
+                    self,
+                    required: usize,
+                    argc: c_int,
+                    argv: *const Value,
+                ) -> Value {
+                    let res =
+                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
+                            let [#(arg~N,)*] = unsafe { optional_args::<$n>(required, argc, argv)? };
+                            unsafe { self.call_convert_optional(argc as usize, #(arg~N,)*) }
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+
+                /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+                fn rbs_signature(&self) -> MethodSignature
+                where
+                    #(T~N: RbsType,)*
+                    Res: RbsType,
+                {
+                    MethodSignature::new(vec![#(T~N::rbs_type(),)*], Res::rbs_type())
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                unsafe fn try_call_convert_value(self, args: &[Value]) -> Option<Result<Value, Error>> {
+                    let &[#(arg~N,)*] = <&[Value; $n]>::try_from(args).ok()?;
+                    #(let arg~N = match overload_try_convert::<T~N>(arg~N) {
+                        Ok(v) => v,
+                        Err(e) => return e.map(Err),
+                    };)*
+                    let ruby = unsafe { Ruby::get_unchecked() };
+                    Some((self)(&ruby, #(arg~N,)*).into_return_value())
+                }
+
+                #[doc(hidden)]
+                fn overload_signature(&self) -> String
+                where
+                    #(T~N: RbsType,)*
+                {
+                    let params: [String; $n] = [#(T~N::rbs_type(),)*];
+                    format!("({})", params.join(", "))
+                }
             }
 
             impl<Func, #(T~N,)* Res> $ruby_name<#(T~N,)* Res> for Func
@@ -1539,12 +2889,131 @@ macro_rules! function_n {
                 #(T~N: TryConvert,)*
                 Res: ReturnValue,
             {}
+
+            /// Helper trait for wrapping a function as a Ruby method ignoring
+            /// self and taking N arguments and a block, with type conversions
+            /// and error handling.
+            ///
+            /// See the [`function`](crate::function!) macro.
+            #[doc(hidden)]
+            pub trait $block_name<#(T~N,)* B, Res>
+            where
+                Self: Sized + Fn(#(T~N,)* B) -> Res,
+                #(T~N: TryConvert,)*
+                B: BlockArg,
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(self, #(arg~N: Value,)*) -> Result<Value, Error> { unsafe {
+                    (self)(
+                        #(TryConvert::try_convert(arg~N)?,)*
+                        B::from_block(&Ruby::get_unchecked())?,
+                    ).into_return_value()
+                }}
+
+                #[inline]
+                unsafe fn call_handle_error(self, #(arg~N: Value,)*) -> Value {
+                    let res =
+                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
+                            unsafe { self.call_convert_value(#(arg~N,)*) }
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+
+                /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+                fn rbs_signature(&self) -> MethodSignature
+                where
+                    #(T~N: RbsType,)*
+                    Res: RbsType,
+                {
+                    MethodSignature::new(vec![#(T~N::rbs_type(),)*], Res::rbs_type())
+                        .with_block(B::REQUIRED)
+                }
+            }
+
+            impl<Func, #(T~N,)* B, Res> $block_name<#(T~N,)* B, Res> for Func
+            where
+                Func: Fn(#(T~N,)* B) -> Res,
+                #(T~N: TryConvert,)*
+                B: BlockArg,
+                Res: ReturnValue,
+            {}
+
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// [`&Ruby`](Ruby), ignoring self, and taking N arguments and a
+            /// block, with type conversions and error handling.
+            ///
+            /// See the [`function`](crate::function!) macro.
+            #[doc(hidden)]
+            pub trait $ruby_block_name<#(T~N,)* B, Res>
+            where
+                Self: Sized + Fn(&Ruby, #(T~N,)* B) -> Res,
+                #(T~N: TryConvert,)*
+                B: BlockArg,
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(self, #(arg~N: Value,)*) -> Result<Value, Error> { unsafe {
+                    let ruby = Ruby::get_unchecked();
+                    (self)(
+                        &ruby,
+                        #(TryConvert::try_convert(arg~N)?,)*
+                        B::from_block(&ruby)?,
+                    ).into_return_value()
+                }}
+
+                #[inline]
+                unsafe fn call_handle_error(self, #(arg~N: Value,)*) -> Value {
+                    let res =
+                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                            let _borrows = borrow::Scope::new();
+                            unsafe { self.call_convert_value(#(arg~N,)*) }
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+
+                /// Returns the [RBS](crate::rbs) signature of the wrapped function.
+                fn rbs_signature(&self) -> MethodSignature
+                where
+                    #(T~N: RbsType,)*
+                    Res: RbsType,
+                {
+                    MethodSignature::new(vec![#(T~N::rbs_type(),)*], Res::rbs_type())
+                        .with_block(B::REQUIRED)
+                }
+            }
+
+            impl<Func, #(T~N,)* B, Res> $ruby_block_name<#(T~N,)* B, Res> for Func
+            where
+                Func: Fn(&Ruby, #(T~N,)* B) -> Res,
+                #(T~N: TryConvert,)*
+                B: BlockArg,
+                Res: ReturnValue,
+            {}
         });
     }
 }
 
 seq!(N in 0..=15 {
-    function_n!(Function~N, RubyFunction~N, N);
+    function_n!(Function~N, RubyFunction~N, FunctionBlock~N, RubyFunctionBlock~N, N);
+});
+
+#[cfg(feature = "high-arity")]
+seq!(N in 16..=31 {
+    function_n!(Function~N, RubyFunction~N, FunctionBlock~N, RubyFunctionBlock~N, N);
 });
 
 /// Wrap a Rust function item with Ruby type conversion and error handling,
@@ -1555,6 +3024,10 @@ seq!(N in 0..=15 {
 /// define Ruby methods such as
 /// [`define_method`](crate::module::Module::define_method).
 ///
+/// With the `rbs` feature enabled the returned value also carries the
+/// [RBS](crate::rbs) signature of the function, so that it is recorded when
+/// the method is defined. See [`Signatures::bound`](crate::rbs::Signatures::bound).
+///
 /// Ruby code implicitly always has a `self` parameter available. In the
 /// extension API this is passed explicitly. The wrapper this macro generates
 /// ignores that argument, and does not pass it to the wrapped function.
@@ -1574,7 +3047,8 @@ seq!(N in 0..=15 {
 /// |     1 | `fn(arg1: T) -> Result<R, Error>`             |
 /// |     2 | `fn(arg1: T, arg2: U) -> Result<R, Error>`    |
 /// |   ... | ...                                           |
-/// |    16 | ...                                           |
+/// |    31 | ...                                           |
+/// |   `*` | `fn((T, U), rest: V) -> Result<R, Error>`     |
 ///
 /// Where `T`, `U`, and so on are any types that implement `TryConvert`,
 /// and `R` implements [`IntoValue`]. It is also possible to return just `R`
@@ -1582,6 +3056,19 @@ seq!(N in 0..=15 {
 /// return value (i.e. return `()`) for a function that returns `nil` to Ruby.
 /// See [`ReturnValue`] for more details on what can be returned.
 ///
+/// A range `required..=max` for `arity` wraps a function taking `max`
+/// arguments, where the arguments after the first `required` are optional.
+/// Omitted arguments are passed as `nil`, so should be an `Option<T>`. See
+/// the [`method`](crate::method!) macro for details.
+///
+/// Arities 16 to 31 (with the `high-arity` feature), and `*` for a tuple of
+/// required arguments followed by the remaining arguments, are also
+/// supported. See the [`method`](crate::method!) macro for details.
+///
+/// The function may also take a trailing [`Proc`] or `Option<Proc>`
+/// parameter, not counted in `arity`, that is filled from the method's block.
+/// See the [`method`](crate::method!) macro for details.
+///
 /// See the [`method`](crate::method!) macro for cases where the `self`
 /// argument is required.
 ///
@@ -1601,38 +3088,100 @@ seq!(N in 0..=15 {
 /// ```
 #[macro_export]
 macro_rules! function {
-    ($name:expr_2021, -2) => {{
-        unsafe extern "C" fn anon(rb_self: $crate::Value, args: $crate::RArray) -> $crate::Value {
-            use $crate::method::{FunctionRbAry, RubyFunctionRbAry};
-            unsafe { $name.call_handle_error(args) }
-        }
-        anon as unsafe extern "C" fn($crate::Value, $crate::RArray) -> $crate::Value
-    }};
-    ($name:expr_2021, -1) => {{
-        unsafe extern "C" fn anon(
-            argc: std::ffi::c_int,
-            argv: *const $crate::Value,
-            rb_self: $crate::Value,
-        ) -> $crate::Value {
-            use $crate::method::{FunctionCAry, RubyFunctionCAry};
-            unsafe { $name.call_handle_error(argc, argv) }
-        }
-        anon as unsafe extern "C" fn(
-            std::ffi::c_int,
-            *const $crate::Value,
-            $crate::Value,
-        ) -> $crate::Value
-    }};
+    ($name:expr_2021, $($arity:tt)+) => {
+        $crate::__function_signed!($name, $($arity)+)
+    };
+}
+
+#[cfg(feature = "rbs")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __function_signed {
+    ($name:expr_2021, $($arity:tt)+) => {
+        $crate::method::Signed::new($crate::__function_ptr!($name, $($arity)+), || {
+            vec![$crate::function_signature!($name, $($arity)+)]
+        })
+    };
+}
+
+#[cfg(not(feature = "rbs"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __function_signed {
+    ($name:expr_2021, $($arity:tt)+) => {
+        $crate::__function_ptr!($name, $($arity)+)
+    };
+}
+
+#[cfg(feature = "high-arity")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __function_wide {
+    ($name:expr_2021, $arity:tt) => {
+        $crate::method::Wide::<$arity>($crate::__function_ptr!(@optional $name, $arity, $arity))
+    };
+}
+
+#[cfg(not(feature = "high-arity"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __function_wide {
+    ($name:expr_2021, $arity:tt) => {
+        compile_error!("arities above 15 require the `high-arity` feature")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __function_ptr {
+    (@optional $name:expr_2021, $required:tt, $max:tt) => {{
+        unsafe extern "C" fn anon(
+            argc: std::ffi::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            $crate::__check_high_arity!($max);
+            const { assert!($required <= $max, "required arguments exceed maximum") };
+            $crate::__function_optional!($name, $required, $max, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::ffi::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr_2021, -2) => {{
+        unsafe extern "C" fn anon(rb_self: $crate::Value, args: $crate::RArray) -> $crate::Value {
+            use $crate::method::{FunctionRbAry, RubyFunctionRbAry};
+            unsafe { $name.call_handle_error(args) }
+        }
+        anon as unsafe extern "C" fn($crate::Value, $crate::RArray) -> $crate::Value
+    }};
+    ($name:expr_2021, -1) => {{
+        unsafe extern "C" fn anon(
+            argc: std::ffi::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionCAry, RubyFunctionCAry};
+            unsafe { $name.call_handle_error(argc, argv) }
+        }
+        anon as unsafe extern "C" fn(
+            std::ffi::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
     ($name:expr_2021, 0) => {{
         unsafe extern "C" fn anon(rb_self: $crate::Value) -> $crate::Value {
-            use $crate::method::{Function0, RubyFunction0};
+            use $crate::method::{Function0, FunctionBlock0, RubyFunction0, RubyFunctionBlock0};
             unsafe { $name.call_handle_error() }
         }
         anon as unsafe extern "C" fn($crate::Value) -> $crate::Value
     }};
     ($name:expr_2021, 1) => {{
         unsafe extern "C" fn anon(rb_self: $crate::Value, a: $crate::Value) -> $crate::Value {
-            use $crate::method::{Function1, RubyFunction1};
+            use $crate::method::{Function1, FunctionBlock1, RubyFunction1, RubyFunctionBlock1};
             unsafe { $name.call_handle_error(a) }
         }
         anon as unsafe extern "C" fn($crate::Value, $crate::Value) -> $crate::Value
@@ -1643,7 +3192,7 @@ macro_rules! function {
             a: $crate::Value,
             b: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function2, RubyFunction2};
+            use $crate::method::{Function2, FunctionBlock2, RubyFunction2, RubyFunctionBlock2};
             unsafe { $name.call_handle_error(a, b) }
         }
         anon as unsafe extern "C" fn($crate::Value, $crate::Value, $crate::Value) -> $crate::Value
@@ -1655,7 +3204,7 @@ macro_rules! function {
             b: $crate::Value,
             c: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function3, RubyFunction3};
+            use $crate::method::{Function3, FunctionBlock3, RubyFunction3, RubyFunctionBlock3};
             unsafe { $name.call_handle_error(a, b, c) }
         }
         anon as unsafe extern "C" fn(
@@ -1673,7 +3222,7 @@ macro_rules! function {
             c: $crate::Value,
             d: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function4, RubyFunction4};
+            use $crate::method::{Function4, FunctionBlock4, RubyFunction4, RubyFunctionBlock4};
             unsafe { $name.call_handle_error(a, b, c, d) }
         }
         anon as unsafe extern "C" fn(
@@ -1693,7 +3242,7 @@ macro_rules! function {
             d: $crate::Value,
             e: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function5, RubyFunction5};
+            use $crate::method::{Function5, FunctionBlock5, RubyFunction5, RubyFunctionBlock5};
             unsafe { $name.call_handle_error(a, b, c, d, e) }
         }
         anon as unsafe extern "C" fn(
@@ -1715,7 +3264,7 @@ macro_rules! function {
             e: $crate::Value,
             f: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function6, RubyFunction6};
+            use $crate::method::{Function6, FunctionBlock6, RubyFunction6, RubyFunctionBlock6};
             unsafe { $name.call_handle_error(a, b, c, d, e, f) }
         }
         anon as unsafe extern "C" fn(
@@ -1739,7 +3288,7 @@ macro_rules! function {
             f: $crate::Value,
             g: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function7, RubyFunction7};
+            use $crate::method::{Function7, FunctionBlock7, RubyFunction7, RubyFunctionBlock7};
             unsafe { $name.call_handle_error(a, b, c, d, e, f, g) }
         }
         anon as unsafe extern "C" fn(
@@ -1765,7 +3314,7 @@ macro_rules! function {
             g: $crate::Value,
             h: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function8, RubyFunction8};
+            use $crate::method::{Function8, FunctionBlock8, RubyFunction8, RubyFunctionBlock8};
             unsafe { $name.call_handle_error(a, b, c, d, e, f, g, h) }
         }
         anon as unsafe extern "C" fn(
@@ -1793,7 +3342,7 @@ macro_rules! function {
             h: $crate::Value,
             i: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function9, RubyFunction9};
+            use $crate::method::{Function9, FunctionBlock9, RubyFunction9, RubyFunctionBlock9};
             unsafe { $name.call_handle_error(a, b, c, d, e, f, g, h, i) }
         }
         anon as unsafe extern "C" fn(
@@ -1823,7 +3372,9 @@ macro_rules! function {
             i: $crate::Value,
             j: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function10, RubyFunction10};
+            use $crate::method::{
+                Function10, FunctionBlock10, RubyFunction10, RubyFunctionBlock10,
+            };
             unsafe { $name.call_handle_error(a, b, c, d, e, f, g, h, i, j) }
         }
         anon as unsafe extern "C" fn(
@@ -1855,7 +3406,9 @@ macro_rules! function {
             j: $crate::Value,
             k: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function11, RubyFunction11};
+            use $crate::method::{
+                Function11, FunctionBlock11, RubyFunction11, RubyFunctionBlock11,
+            };
             unsafe { $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k) }
         }
         anon as unsafe extern "C" fn(
@@ -1889,7 +3442,9 @@ macro_rules! function {
             k: $crate::Value,
             l: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function12, RubyFunction12};
+            use $crate::method::{
+                Function12, FunctionBlock12, RubyFunction12, RubyFunctionBlock12,
+            };
             unsafe { $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l) }
         }
         anon as unsafe extern "C" fn(
@@ -1925,7 +3480,9 @@ macro_rules! function {
             l: $crate::Value,
             m: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function13, RubyFunction13};
+            use $crate::method::{
+                Function13, FunctionBlock13, RubyFunction13, RubyFunctionBlock13,
+            };
             unsafe { $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m) }
         }
         anon as unsafe extern "C" fn(
@@ -1963,7 +3520,9 @@ macro_rules! function {
             m: $crate::Value,
             n: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function14, RubyFunction14};
+            use $crate::method::{
+                Function14, FunctionBlock14, RubyFunction14, RubyFunctionBlock14,
+            };
             unsafe { $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m, n) }
         }
         anon as unsafe extern "C" fn(
@@ -2003,7 +3562,9 @@ macro_rules! function {
             n: $crate::Value,
             o: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function15, RubyFunction15};
+            use $crate::method::{
+                Function15, FunctionBlock15, RubyFunction15, RubyFunctionBlock15,
+            };
             unsafe { $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o) }
         }
         anon as unsafe extern "C" fn(
@@ -2025,7 +3586,1107 @@ macro_rules! function {
             $crate::Value,
         ) -> $crate::Value
     }};
+    ($name:expr_2021, 16) => {
+        $crate::__function_wide!($name, 16)
+    };
+    ($name:expr_2021, 17) => {
+        $crate::__function_wide!($name, 17)
+    };
+    ($name:expr_2021, 18) => {
+        $crate::__function_wide!($name, 18)
+    };
+    ($name:expr_2021, 19) => {
+        $crate::__function_wide!($name, 19)
+    };
+    ($name:expr_2021, 20) => {
+        $crate::__function_wide!($name, 20)
+    };
+    ($name:expr_2021, 21) => {
+        $crate::__function_wide!($name, 21)
+    };
+    ($name:expr_2021, 22) => {
+        $crate::__function_wide!($name, 22)
+    };
+    ($name:expr_2021, 23) => {
+        $crate::__function_wide!($name, 23)
+    };
+    ($name:expr_2021, 24) => {
+        $crate::__function_wide!($name, 24)
+    };
+    ($name:expr_2021, 25) => {
+        $crate::__function_wide!($name, 25)
+    };
+    ($name:expr_2021, 26) => {
+        $crate::__function_wide!($name, 26)
+    };
+    ($name:expr_2021, 27) => {
+        $crate::__function_wide!($name, 27)
+    };
+    ($name:expr_2021, 28) => {
+        $crate::__function_wide!($name, 28)
+    };
+    ($name:expr_2021, 29) => {
+        $crate::__function_wide!($name, 29)
+    };
+    ($name:expr_2021, 30) => {
+        $crate::__function_wide!($name, 30)
+    };
+    ($name:expr_2021, 31) => {
+        $crate::__function_wide!($name, 31)
+    };
+    ($name:expr_2021, *) => {{
+        unsafe extern "C" fn anon(
+            argc: std::ffi::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat, RubyFunctionSplat};
+            unsafe { $name.call_handle_error(argc, argv) }
+        }
+        anon as unsafe extern "C" fn(
+            std::ffi::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr_2021, $required:tt..=$max:tt) => {
+        $crate::method::Optional::<$required, $max>($crate::__function_ptr!(@optional $name, $required, $max))
+    };
+    ($name:expr_2021, $arity:expr_2021) => {
+        compile_error!("arity must be `*` or an integer literal between -2..=31")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_optional {
+    ($name:expr_2021, $required:tt, 0, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method0, RubyMethod0};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 1, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method1, RubyMethod1};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 2, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method2, RubyMethod2};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 3, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method3, RubyMethod3};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 4, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method4, RubyMethod4};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 5, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method5, RubyMethod5};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 6, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method6, RubyMethod6};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 7, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method7, RubyMethod7};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 8, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method8, RubyMethod8};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 9, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method9, RubyMethod9};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 10, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method10, RubyMethod10};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 11, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method11, RubyMethod11};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 12, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method12, RubyMethod12};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 13, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method13, RubyMethod13};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 14, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method14, RubyMethod14};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 15, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method15, RubyMethod15};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 16, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method16, RubyMethod16};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 17, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method17, RubyMethod17};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 18, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method18, RubyMethod18};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 19, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method19, RubyMethod19};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 20, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method20, RubyMethod20};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 21, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method21, RubyMethod21};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 22, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method22, RubyMethod22};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 23, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method23, RubyMethod23};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 24, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method24, RubyMethod24};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 25, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method25, RubyMethod25};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 26, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method26, RubyMethod26};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 27, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method27, RubyMethod27};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 28, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method28, RubyMethod28};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 29, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method29, RubyMethod29};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 30, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method30, RubyMethod30};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, 31, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Method31, RubyMethod31};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv, $rb_self) }
+    }};
+    ($name:expr_2021, $required:tt, $max:tt, $argc:ident, $argv:ident, $rb_self:ident) => {
+        compile_error!("maximum arity must be an integer literal between 0..=31")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __function_optional {
+    ($name:expr_2021, $required:tt, 0, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function0, RubyFunction0};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 1, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function1, RubyFunction1};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 2, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function2, RubyFunction2};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 3, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function3, RubyFunction3};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 4, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function4, RubyFunction4};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 5, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function5, RubyFunction5};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 6, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function6, RubyFunction6};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 7, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function7, RubyFunction7};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 8, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function8, RubyFunction8};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 9, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function9, RubyFunction9};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 10, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function10, RubyFunction10};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 11, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function11, RubyFunction11};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 12, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function12, RubyFunction12};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 13, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function13, RubyFunction13};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 14, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function14, RubyFunction14};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 15, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function15, RubyFunction15};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 16, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function16, RubyFunction16};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 17, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function17, RubyFunction17};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 18, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function18, RubyFunction18};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 19, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function19, RubyFunction19};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 20, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function20, RubyFunction20};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 21, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function21, RubyFunction21};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 22, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function22, RubyFunction22};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 23, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function23, RubyFunction23};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 24, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function24, RubyFunction24};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 25, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function25, RubyFunction25};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 26, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function26, RubyFunction26};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 27, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function27, RubyFunction27};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 28, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function28, RubyFunction28};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 29, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function29, RubyFunction29};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 30, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function30, RubyFunction30};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, 31, $argc:ident, $argv:ident, $rb_self:ident) => {{
+        use $crate::method::{Function31, RubyFunction31};
+        unsafe { $name.call_handle_error_optional($required, $argc, $argv) }
+    }};
+    ($name:expr_2021, $required:tt, $max:tt, $argc:ident, $argv:ident, $rb_self:ident) => {
+        compile_error!("maximum arity must be an integer literal between 0..=31")
+    };
+}
+
+/// Wrap several Rust function items as one Ruby method, dispatching on the
+/// number and types of the arguments.
+///
+/// Takes a list of `(function, arity)` pairs, where each function and arity
+/// are as for the [`method`](crate::method!) macro (though ranges of arities
+/// and block parameters are not supported). When the method is called the
+/// functions are tried in order, and the first one with `arity` equal to the
+/// number of arguments, and for which `self` and all the arguments can be
+/// converted with [`TryConvert`], is called. Only a `TypeError` from a
+/// conversion moves on to the next function, any other error, such as a
+/// `RangeError` for an out of range integer, is raised.
+///
+/// The parameter types of each function must implement
+/// [`RbsType`](crate::rbs::RbsType). If no function matches a `TypeError` is
+/// raised, listing the [RBS](crate::rbs) parameter types of each function.
+///
+/// With the `rbs` feature enabled the signature of each function is recorded
+/// as an overload of the method, and return types must also implement
+/// [`RbsType`](crate::rbs::RbsType).
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, RArray, Ruby, overloaded_method, prelude::*, rb_assert};
+///
+/// fn push_int(rb_self: RArray, i: i64) -> Result<RArray, Error> {
+///     rb_self.push(i)?;
+///     Ok(rb_self)
+/// }
+///
+/// fn push_chars(rb_self: RArray, s: String) -> Result<RArray, Error> {
+///     for c in s.chars() {
+///         rb_self.push(c)?;
+///     }
+///     Ok(rb_self)
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.class_array().define_method(
+///         "add",
+///         overloaded_method!((push_int, 1), (push_chars, 1)),
+///     )?;
+///
+///     rb_assert!(ruby, r#"[].add(1).add("ab") == [1, "a", "b"]"#);
+///     rb_assert!(
+///         ruby,
+///         r#"([].add(nil) rescue $!).message == "no overload matches (NilClass), expected one of (Integer), (String)""#
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[macro_export]
+macro_rules! overloaded_method {
+    ($(($name:expr_2021, $arity:tt)),+ $(,)?) => {
+        $crate::__overloaded_signed!(
+            method_signature,
+            $crate::__overloaded_method_ptr!($(($name, $arity)),+),
+            $(($name, $arity)),+
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __overloaded_method_ptr {
+    ($(($name:expr_2021, $arity:tt)),+) => {{
+        unsafe extern "C" fn anon(
+            argc: std::ffi::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            let overloads: &[&dyn $crate::method::Overload] =
+                &[$(&$crate::__method_overload!($name, $arity)),+];
+            unsafe { $crate::method::overload_handle_error(overloads, argc, argv, rb_self) }
+        }
+        anon as unsafe extern "C" fn(
+            std::ffi::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+}
+
+/// Wrap several Rust function items as one Ruby method, ignoring Ruby's
+/// `self` argument, and dispatching on the number and types of the
+/// arguments.
+///
+/// Takes a list of `(function, arity)` pairs, where each function and arity
+/// are as for the [`function`](crate::function!) macro. See the
+/// [`overloaded_method`](crate::overloaded_method!) macro for details.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, Ruby, overloaded_function, rb_assert};
+///
+/// fn area_square(side: f64) -> f64 {
+///     side * side
+/// }
+///
+/// fn area_rect(width: f64, height: f64) -> f64 {
+///     width * height
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function(
+///         "area",
+///         overloaded_function!((area_square, 1), (area_rect, 2)),
+///     );
+///
+///     rb_assert!(ruby, "area(3) == 9.0");
+///     rb_assert!(ruby, "area(2, 3) == 6.0");
+///     rb_assert!(ruby, "(area rescue $!).is_a?(TypeError)");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[macro_export]
+macro_rules! overloaded_function {
+    ($(($name:expr_2021, $arity:tt)),+ $(,)?) => {
+        $crate::__overloaded_signed!(
+            function_signature,
+            $crate::__overloaded_function_ptr!($(($name, $arity)),+),
+            $(($name, $arity)),+
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __overloaded_function_ptr {
+    ($(($name:expr_2021, $arity:tt)),+) => {{
+        unsafe extern "C" fn anon(
+            argc: std::ffi::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            let overloads: &[&dyn $crate::method::Overload] =
+                &[$(&$crate::__function_overload!($name, $arity)),+];
+            unsafe { $crate::method::overload_handle_error(overloads, argc, argv, rb_self) }
+        }
+        anon as unsafe extern "C" fn(
+            std::ffi::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+}
+
+#[cfg(feature = "rbs")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __overloaded_signed {
+    ($signature:ident, $ptr:expr_2021, $(($name:expr_2021, $arity:tt)),+) => {
+        $crate::method::Signed::new($ptr, || vec![$($crate::$signature!($name, $arity)),+])
+    };
+}
+
+#[cfg(not(feature = "rbs"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __overloaded_signed {
+    ($signature:ident, $ptr:expr_2021, $(($name:expr_2021, $arity:tt)),+) => {
+        $ptr
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method_overload {
+    ($name:expr_2021, 0) => {{
+        use $crate::method::{Method0, RubyMethod0};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 1) => {{
+        use $crate::method::{Method1, RubyMethod1};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 2) => {{
+        use $crate::method::{Method2, RubyMethod2};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 3) => {{
+        use $crate::method::{Method3, RubyMethod3};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 4) => {{
+        use $crate::method::{Method4, RubyMethod4};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 5) => {{
+        use $crate::method::{Method5, RubyMethod5};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 6) => {{
+        use $crate::method::{Method6, RubyMethod6};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 7) => {{
+        use $crate::method::{Method7, RubyMethod7};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 8) => {{
+        use $crate::method::{Method8, RubyMethod8};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 9) => {{
+        use $crate::method::{Method9, RubyMethod9};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 10) => {{
+        use $crate::method::{Method10, RubyMethod10};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 11) => {{
+        use $crate::method::{Method11, RubyMethod11};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 12) => {{
+        use $crate::method::{Method12, RubyMethod12};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 13) => {{
+        use $crate::method::{Method13, RubyMethod13};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 14) => {{
+        use $crate::method::{Method14, RubyMethod14};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 15) => {{
+        use $crate::method::{Method15, RubyMethod15};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 16) => {{
+        use $crate::method::{Method16, RubyMethod16};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 17) => {{
+        use $crate::method::{Method17, RubyMethod17};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 18) => {{
+        use $crate::method::{Method18, RubyMethod18};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 19) => {{
+        use $crate::method::{Method19, RubyMethod19};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 20) => {{
+        use $crate::method::{Method20, RubyMethod20};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 21) => {{
+        use $crate::method::{Method21, RubyMethod21};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 22) => {{
+        use $crate::method::{Method22, RubyMethod22};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 23) => {{
+        use $crate::method::{Method23, RubyMethod23};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 24) => {{
+        use $crate::method::{Method24, RubyMethod24};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 25) => {{
+        use $crate::method::{Method25, RubyMethod25};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 26) => {{
+        use $crate::method::{Method26, RubyMethod26};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 27) => {{
+        use $crate::method::{Method27, RubyMethod27};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 28) => {{
+        use $crate::method::{Method28, RubyMethod28};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 29) => {{
+        use $crate::method::{Method29, RubyMethod29};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 30) => {{
+        use $crate::method::{Method30, RubyMethod30};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 31) => {{
+        use $crate::method::{Method31, RubyMethod31};
+        $crate::method::OverloadFn(
+            move |rb_self: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(rb_self, args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, $arity:expr_2021) => {
+        compile_error!("arity must be an integer literal between 0..=31")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __function_overload {
+    ($name:expr_2021, 0) => {{
+        use $crate::method::{Function0, RubyFunction0};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 1) => {{
+        use $crate::method::{Function1, RubyFunction1};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 2) => {{
+        use $crate::method::{Function2, RubyFunction2};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 3) => {{
+        use $crate::method::{Function3, RubyFunction3};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 4) => {{
+        use $crate::method::{Function4, RubyFunction4};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 5) => {{
+        use $crate::method::{Function5, RubyFunction5};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 6) => {{
+        use $crate::method::{Function6, RubyFunction6};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 7) => {{
+        use $crate::method::{Function7, RubyFunction7};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 8) => {{
+        use $crate::method::{Function8, RubyFunction8};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 9) => {{
+        use $crate::method::{Function9, RubyFunction9};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 10) => {{
+        use $crate::method::{Function10, RubyFunction10};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 11) => {{
+        use $crate::method::{Function11, RubyFunction11};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 12) => {{
+        use $crate::method::{Function12, RubyFunction12};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 13) => {{
+        use $crate::method::{Function13, RubyFunction13};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 14) => {{
+        use $crate::method::{Function14, RubyFunction14};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 15) => {{
+        use $crate::method::{Function15, RubyFunction15};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 16) => {{
+        use $crate::method::{Function16, RubyFunction16};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 17) => {{
+        use $crate::method::{Function17, RubyFunction17};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 18) => {{
+        use $crate::method::{Function18, RubyFunction18};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 19) => {{
+        use $crate::method::{Function19, RubyFunction19};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 20) => {{
+        use $crate::method::{Function20, RubyFunction20};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 21) => {{
+        use $crate::method::{Function21, RubyFunction21};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 22) => {{
+        use $crate::method::{Function22, RubyFunction22};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 23) => {{
+        use $crate::method::{Function23, RubyFunction23};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 24) => {{
+        use $crate::method::{Function24, RubyFunction24};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 25) => {{
+        use $crate::method::{Function25, RubyFunction25};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 26) => {{
+        use $crate::method::{Function26, RubyFunction26};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 27) => {{
+        use $crate::method::{Function27, RubyFunction27};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 28) => {{
+        use $crate::method::{Function28, RubyFunction28};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 29) => {{
+        use $crate::method::{Function29, RubyFunction29};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 30) => {{
+        use $crate::method::{Function30, RubyFunction30};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
+    ($name:expr_2021, 31) => {{
+        use $crate::method::{Function31, RubyFunction31};
+        $crate::method::OverloadFn(
+            move |_: $crate::Value, args: &[$crate::Value]| unsafe {
+                $name.try_call_convert_value(args)
+            },
+            move || $name.overload_signature(),
+        )
+    }};
     ($name:expr_2021, $arity:expr_2021) => {
-        compile_error!("arity must be an integer literal between -2..=15")
+        compile_error!("arity must be an integer literal between 0..=31")
     };
 }
@@ -3,9 +3,14 @@
 //! See also [`Ruby`](Ruby#core-modules) for more module related methods.
 
 use std::{
+    collections::BTreeMap,
     ffi::{CString, c_int, c_void},
     fmt,
     mem::transmute,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
 };
 
 use rb_sys::{
@@ -19,16 +24,18 @@ use rb_sys::{
 
 use crate::{
     Ruby,
+    block::Proc,
     class::{Class, RClass},
     error::{Error, protect},
     exception::ExceptionClass,
+    gc,
     into_value::IntoValue,
     method::Method,
     object::Object,
     r_array::RArray,
     try_convert::TryConvert,
     value::{
-        IntoId, NonZeroValue, ReprValue, Value,
+        IntoId, Lazy, NonZeroValue, Opaque, ReprValue, StaticSymbol, Value,
         private::{self, ReprValue as _},
     },
 };
@@ -159,20 +166,37 @@ impl RModule {
         M: Method,
     {
         debug_assert_value!(self);
+        #[cfg(feature = "rbs")]
+        let sigs = func.signatures();
         let name = CString::new(name).unwrap();
-        protect(|| {
-            unsafe {
-                rb_define_module_function(
-                    self.as_rb_value(),
-                    name.as_ptr(),
-                    transmute::<*mut c_void, Option<unsafe extern "C" fn() -> VALUE>>(
-                        func.as_ptr(),
-                    ),
-                    M::arity().into(),
-                )
-            };
-            Ruby::get_with(self).qnil()
-        })?;
+        if let Some(arity) = M::declared_arity() {
+            let ruby = Ruby::get_with(self);
+            let name = name.to_str().unwrap();
+            define_bound_method(
+                &ruby,
+                self.as_value(),
+                name,
+                arity,
+                func,
+                Some("module_function"),
+            )?;
+        } else {
+            protect(|| {
+                unsafe {
+                    rb_define_module_function(
+                        self.as_rb_value(),
+                        name.as_ptr(),
+                        transmute::<*mut c_void, Option<unsafe extern "C" fn() -> VALUE>>(
+                            func.as_ptr(),
+                        ),
+                        M::arity().into(),
+                    )
+                };
+                Ruby::get_with(self).qnil()
+            })?;
+        }
+        #[cfg(feature = "rbs")]
+        crate::rbs::record(self.as_value(), true, &name.to_string_lossy(), sigs);
         Ok(())
     }
 }
@@ -541,22 +565,128 @@ pub trait Module: Object + ReprValue + Copy {
         debug_assert_value!(self);
         let handle = Ruby::get_with(self);
         let id = name.into_id_with(&handle);
-        protect(|| {
-            unsafe {
-                rb_define_method_id(
-                    self.as_rb_value(),
-                    id.as_rb_id(),
-                    transmute::<*mut c_void, Option<unsafe extern "C" fn() -> VALUE>>(
-                        func.as_ptr(),
-                    ),
-                    M::arity().into(),
-                )
-            };
-            handle.qnil()
-        })?;
+        #[cfg(feature = "rbs")]
+        let sigs = func.signatures();
+        if let Some(arity) = M::declared_arity() {
+            define_bound_method(&handle, self.as_value(), id, arity, func, None)?;
+        } else {
+            protect(|| {
+                unsafe {
+                    rb_define_method_id(
+                        self.as_rb_value(),
+                        id.as_rb_id(),
+                        transmute::<*mut c_void, Option<unsafe extern "C" fn() -> VALUE>>(
+                            func.as_ptr(),
+                        ),
+                        M::arity().into(),
+                    )
+                };
+                handle.qnil()
+            })?;
+        }
+        #[cfg(feature = "rbs")]
+        if let Ok(name) = id.name() {
+            crate::rbs::record(self.as_value(), false, name, sigs);
+        }
         Ok(())
     }
 
+    /// Define a method in `self`'s scope, implemented by the closure `func`.
+    ///
+    /// Unlike [`define_method`](Module::define_method), `func` may capture
+    /// variables, such as an `Arc` of shared configuration. The closure is
+    /// stored in a GC managed object kept alive by the method, and is dropped
+    /// when the method is removed or redefined and the object is garbage
+    /// collected.
+    ///
+    /// Ruby values captured directly by `func` are marked, but values held
+    /// behind a pointer, such as in a `Vec`, `Box`, or `Arc`, are not, and
+    /// must be kept alive some other way, e.g. with
+    /// [`gc::register_mark_object`](crate::gc::register_mark_object).
+    ///
+    /// `func` is passed `self`, the arguments to the method, and the block
+    /// passed to the method, if any. When `arity`
+    /// is non-negative the method takes exactly that many arguments, `-1`
+    /// accepts any number of arguments. `Method#arity` reports `arity`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ArgumentError` if `arity` is less than `-1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use magnus::{Error, Ruby, Value, prelude::*, rb_assert};
+    ///
+    /// struct Field {
+    ///     name: String,
+    ///     default: i64,
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = ruby.define_class("Record", ruby.class_object())?;
+    ///
+    ///     let schema = vec![
+    ///         Arc::new(Field { name: String::from("width"), default: 10 }),
+    ///         Arc::new(Field { name: String::from("height"), default: 20 }),
+    ///     ];
+    ///     for field in schema {
+    ///         let name = field.name.clone();
+    ///         class.define_method_fn(name, 0, move |_ruby, rb_self: Value, _args, _block| {
+    ///             let ivar = format!("@{}", field.name);
+    ///             Ok(rb_self
+    ///                 .funcall::<_, _, Option<i64>>("instance_variable_get", (ivar,))?
+    ///                 .unwrap_or(field.default))
+    ///         })?;
+    ///     }
+    ///
+    ///     rb_assert!(ruby, "Record.new.width == 10");
+    ///     rb_assert!(ruby, "Record.new.height == 20");
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "(Record.new.width(1) rescue $!).message == 'wrong number of arguments (given 1, expected 0)'"
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn define_method_fn<T, F, R>(self, name: T, arity: i8, func: F) -> Result<(), Error>
+    where
+        T: IntoId,
+        F: 'static + Send + Fn(&Ruby, Value, &[Value], Option<Proc>) -> Result<R, Error>,
+        R: IntoValue,
+    {
+        debug_assert_value!(self);
+        let handle = Ruby::get_with(self);
+        if arity < -1 {
+            return Err(Error::new(
+                handle.exception_arg_error(),
+                format!("arity must be -1 or greater, got {}", arity),
+            ));
+        }
+        let proc = handle.proc_from_fn(move |ruby, args, block| {
+            let (rb_self, args) = args.split_first().unwrap();
+            func(ruby, *rb_self, args, block)
+        });
+        let arity = if arity < 0 {
+            (0, None)
+        } else {
+            (arity as u8, Some(arity as u8))
+        };
+        define_block_method(
+            &handle,
+            self.as_value(),
+            name,
+            arity,
+            proc.as_value(),
+            "call",
+            None,
+        )
+    }
+
     /// Define a private method in `self`'s scope.
     ///
     /// # Examples
@@ -603,6 +733,10 @@ pub trait Module: Object + ReprValue + Copy {
         M: Method,
     {
         debug_assert_value!(self);
+        if let Some(arity) = M::declared_arity() {
+            let ruby = Ruby::get_with(self);
+            return define_bound_method(&ruby, self.as_value(), name, arity, func, Some("private"));
+        }
         let name = CString::new(name).unwrap();
         protect(|| {
             unsafe {
@@ -671,6 +805,17 @@ pub trait Module: Object + ReprValue + Copy {
         M: Method,
     {
         debug_assert_value!(self);
+        if let Some(arity) = M::declared_arity() {
+            let ruby = Ruby::get_with(self);
+            return define_bound_method(
+                &ruby,
+                self.as_value(),
+                name,
+                arity,
+                func,
+                Some("protected"),
+            );
+        }
         let name = CString::new(name).unwrap();
         protect(|| {
             unsafe {
@@ -769,6 +914,146 @@ pub trait Module: Object + ReprValue + Copy {
     }
 }
 
+/// Define the method `name` on `owner`, implemented by `func`, which is
+/// called with `self` followed by the method's arguments, and the method's
+/// block.
+///
+/// `call` is the method used to invoke `func`, e.g. `"call"` for a `Proc` or
+/// `"bind_call"` for an `UnboundMethod`.
+///
+/// The method is defined with a block taking `required` parameters, then
+/// optional parameters up to `max`, or `*args` if `max` is `None`.
+/// `define_method` gives the block lambda semantics, so Ruby checks the
+/// number of arguments and `Method#arity` reflects the declared parameters.
+/// `visibility` names a method, such as `"private"`, called with `name` after
+/// the method is defined.
+fn define_block_method<T>(
+    ruby: &Ruby,
+    owner: Value,
+    name: T,
+    (required, max): (u8, Option<u8>),
+    func: Value,
+    call: &'static str,
+    visibility: Option<&str>,
+) -> Result<(), Error>
+where
+    T: IntoId,
+{
+    let name = StaticSymbol::from(name.into_id_with(ruby));
+    let body = block_method_wrapper(ruby, required, max, call)?.call::<_, Proc>((func,))?;
+    owner.funcall::<_, _, Value>("define_method", (name, body))?;
+    if let Some(visibility) = visibility {
+        owner.funcall::<_, _, Value>(visibility, (name,))?;
+    }
+    Ok(())
+}
+
+/// Returns a lambda that takes a callable and returns a block with
+/// `required` parameters and optional parameters up to `max` (or `*args`),
+/// which invokes the callable's `call` method with `self`, the arguments
+/// actually passed to the block, and the method's block.
+///
+/// These are built once for each shape and `call`, rather than for every
+/// method.
+fn block_method_wrapper(
+    ruby: &Ruby,
+    required: u8,
+    max: Option<u8>,
+    call: &'static str,
+) -> Result<Proc, Error> {
+    type Key = (&'static str, u8, Option<u8>);
+    static WRAPPERS: Mutex<BTreeMap<Key, Opaque<Proc>>> = Mutex::new(BTreeMap::new());
+
+    // the lock must not be held while calling Ruby, which may switch threads
+    let cached = WRAPPERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&(call, required, max))
+        .copied();
+    if let Some(wrap) = cached {
+        return Ok(ruby.get_inner(wrap));
+    }
+    let mut params = (0..required)
+        .map(|i| format!("arg{}, ", i))
+        .collect::<String>();
+    let mut args = params.clone();
+    match max {
+        // optional parameters default to `unset`, which is filtered out so
+        // `func` sees only the arguments that were passed
+        Some(max) if max > required => {
+            let optional = (required..max).map(|i| format!("arg{}", i));
+            params.extend(optional.clone().map(|p| format!("{} = unset, ", p)));
+            args.push_str(&format!(
+                "*[{}].reject {{ |a| unset.equal?(a) }}, ",
+                optional.collect::<Vec<_>>().join(", ")
+            ));
+        }
+        Some(_) => (),
+        None => {
+            params.push_str("*args, ");
+            args.push_str("*args, ");
+        }
+    }
+    let wrap = ruby.eval::<Proc>(&format!(
+        "->(func) {{ unset = Object.new; proc {{ |{}&blk| func.{}(self, {}&blk) }} }}",
+        params, call, args
+    ))?;
+    gc::register_mark_object(wrap);
+    WRAPPERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert((call, required, max), Opaque::from(wrap));
+    Ok(wrap)
+}
+
+/// Define the method `name` on `owner` for `func`, which is wrapped as
+/// taking any number of arguments, so that `Method#arity` reflects the
+/// `required` and `max` number of arguments it actually takes.
+///
+/// `func` is defined as a method taking any number of arguments on a hidden
+/// module, and called with `UnboundMethod#bind_call` from a block with
+/// matching parameters, see [`define_block_method`]. This way `func` runs as
+/// a regular C method, with the method's block.
+pub(crate) fn define_bound_method<T, M>(
+    ruby: &Ruby,
+    owner: Value,
+    name: T,
+    (required, max): (u8, u8),
+    func: M,
+    visibility: Option<&str>,
+) -> Result<(), Error>
+where
+    T: IntoId,
+    M: Method,
+{
+    static FUNCS: Lazy<RModule> = Lazy::new(|ruby| ruby.module_new());
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let funcs = ruby.get_inner(&FUNCS);
+    let id = ruby.intern(&format!("func_{}", COUNT.fetch_add(1, Ordering::Relaxed)));
+    protect(|| {
+        unsafe {
+            rb_define_method_id(
+                funcs.as_rb_value(),
+                id.as_rb_id(),
+                transmute::<*mut c_void, Option<unsafe extern "C" fn() -> VALUE>>(func.as_ptr()),
+                M::arity().into(),
+            )
+        };
+        ruby.qnil()
+    })?;
+    let func = funcs.funcall::<_, _, Value>("instance_method", (id,))?;
+    define_block_method(
+        ruby,
+        owner,
+        name,
+        (required, Some(max)),
+        func,
+        "bind_call",
+        visibility,
+    )
+}
+
 /// Argument for [`define_attr`](Module::define_attr).
 #[derive(Clone, Copy, Debug)]
 pub enum Attr {
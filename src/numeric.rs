@@ -256,7 +256,7 @@ pub struct NumericValue(NonZeroValue);
 
 impl NumericValue {
     #[inline]
-    unsafe fn from_rb_value_unchecked(val: VALUE) -> Self {
+    pub(crate) unsafe fn from_rb_value_unchecked(val: VALUE) -> Self {
         unsafe { Self(NonZeroValue::new_unchecked(Value::new(val))) }
     }
 }
@@ -14,7 +14,7 @@ use crate::{
     error::{Error, protect},
     into_value::IntoValue,
     method::Method,
-    module::RModule,
+    module::{RModule, define_bound_method},
     try_convert::TryConvert,
     value::{IntoId, ReprValue, Value, private::ReprValue as _},
 };
@@ -76,20 +76,31 @@ pub trait Object: ReprValue + Copy {
         M: Method,
     {
         debug_assert_value!(self);
+        #[cfg(feature = "rbs")]
+        let sigs = func.signatures();
         let name = CString::new(name).unwrap();
-        protect(|| {
-            unsafe {
-                rb_define_singleton_method(
-                    self.as_rb_value(),
-                    name.as_ptr(),
-                    transmute::<*mut c_void, Option<unsafe extern "C" fn() -> VALUE>>(
-                        func.as_ptr(),
-                    ),
-                    M::arity().into(),
-                )
-            };
-            Ruby::get_with(self).qnil()
-        })?;
+        if let Some(arity) = M::declared_arity() {
+            let ruby = Ruby::get_with(self);
+            let name = name.to_str().unwrap();
+            let owner = self.singleton_class()?.as_value();
+            define_bound_method(&ruby, owner, name, arity, func, None)?;
+        } else {
+            protect(|| {
+                unsafe {
+                    rb_define_singleton_method(
+                        self.as_rb_value(),
+                        name.as_ptr(),
+                        transmute::<*mut c_void, Option<unsafe extern "C" fn() -> VALUE>>(
+                            func.as_ptr(),
+                        ),
+                        M::arity().into(),
+                    )
+                };
+                Ruby::get_with(self).qnil()
+            })?;
+        }
+        #[cfg(feature = "rbs")]
+        crate::rbs::record(self.as_value(), true, &name.to_string_lossy(), sigs);
         Ok(())
     }
 
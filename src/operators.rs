@@ -0,0 +1,391 @@
+//! Defining Ruby operators from Rust's [`std::ops`] traits.
+//!
+//! An [`Operators`] collects the operators a wrapped type supports, and
+//! [`define`](Operators::define) defines them as methods on its class. When
+//! any reflected operators (such as [`rmul`](Operators::rmul)) are declared a
+//! `coerce` method is also defined, so the type works as the right hand
+//! operand of Ruby's numeric types, e.g. `2 * vector`.
+
+use std::{fmt, marker::PhantomData, ops};
+
+use crate::{
+    Ruby,
+    class::{Class, RClass},
+    error::Error,
+    gc,
+    into_value::IntoValue,
+    method::overload_try_convert,
+    module::Module,
+    numeric::{Numeric, NumericValue},
+    object::Object,
+    r_object::RObject,
+    try_convert::TryConvert,
+    typed_data::TypedData,
+    value::{Opaque, ReprValue, Value, private::ReprValue as _},
+};
+
+/// The exponentiation operator `**`.
+///
+/// Rust has no operator trait for exponentiation, so this fills that gap for
+/// [`Operators::pow`] and [`Operators::rpow`], following the shape of the
+/// [`std::ops`] traits.
+pub trait Pow<Rhs = Self> {
+    /// The resulting type after applying the `**` operator.
+    type Output;
+
+    /// Performs the `**` operation.
+    fn pow(self, rhs: Rhs) -> Self::Output;
+}
+
+type Binary = Box<dyn Fn(&Ruby, Value, Value) -> Option<Result<Value, Error>> + Send + Sync>;
+type Unary = Box<dyn Fn(&Ruby, Value) -> Result<Value, Error> + Send + Sync>;
+
+/// A set of Ruby operators for the wrapped type `T`.
+///
+/// Binary operators take `T` by value (cloning the receiver) and the right
+/// hand operand as any type implementing [`TryConvert`]. An operator may be
+/// declared for several right hand types, they are tried in the order they
+/// were declared. Wrapped types can be used as the right hand operand by
+/// reference, e.g. `add::<&Vector>()` requires `impl Add<&Vector> for
+/// Vector`.
+///
+/// If the right hand operand doesn't convert to any of the declared types
+/// Ruby's coercion protocol is followed (as with
+/// [`Numeric::coerce_bin`](crate::Numeric::coerce_bin)), calling the
+/// operand's `coerce` method and applying the operator to the result. If the
+/// operand has no `coerce` method a `TypeError` is raised.
+///
+/// Reflected operators, e.g. [`rmul`](Operators::rmul), implement the
+/// operator where `T` is the right hand operand, for when a Ruby numeric is
+/// on the left hand side.
+///
+/// # Examples
+///
+/// ```
+/// use std::ops::{Add, Mul, Neg};
+///
+/// use magnus::{Error, Ruby, function, method, operators::Operators, prelude::*, rb_assert};
+///
+/// #[derive(Clone)]
+/// #[magnus::wrap(class = "Vector")]
+/// struct Vector {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// impl Vector {
+///     fn new(x: f64, y: f64) -> Self {
+///         Self { x, y }
+///     }
+///
+///     fn to_a(&self) -> (f64, f64) {
+///         (self.x, self.y)
+///     }
+/// }
+///
+/// impl Add<&Vector> for Vector {
+///     type Output = Vector;
+///
+///     fn add(self, other: &Vector) -> Vector {
+///         Vector::new(self.x + other.x, self.y + other.y)
+///     }
+/// }
+///
+/// impl Mul<f64> for Vector {
+///     type Output = Vector;
+///
+///     fn mul(self, scalar: f64) -> Vector {
+///         Vector::new(self.x * scalar, self.y * scalar)
+///     }
+/// }
+///
+/// impl Mul<Vector> for f64 {
+///     type Output = Vector;
+///
+///     fn mul(self, vector: Vector) -> Vector {
+///         vector * self
+///     }
+/// }
+///
+/// impl Neg for Vector {
+///     type Output = Vector;
+///
+///     fn neg(self) -> Vector {
+///         Vector::new(-self.x, -self.y)
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Vector", ruby.class_object())?;
+///     class.define_singleton_method("new", function!(Vector::new, 2))?;
+///     class.define_method("to_a", method!(Vector::to_a, 0))?;
+///
+///     let mut operators = Operators::<Vector>::new();
+///     operators.add::<&Vector>().mul::<f64>().rmul::<f64>().neg();
+///     operators.define(class)?;
+///
+///     rb_assert!(ruby, "(Vector.new(1, 2) + Vector.new(3, 4)).to_a == [4.0, 6.0]");
+///     rb_assert!(ruby, "(Vector.new(1, 2) * 3).to_a == [3.0, 6.0]");
+///     rb_assert!(ruby, "(2 * Vector.new(1, 2)).to_a == [2.0, 4.0]");
+///     rb_assert!(ruby, "(-Vector.new(1, 2)).to_a == [-1.0, -2.0]");
+///     rb_assert!(
+///         ruby,
+///         r#"(Vector.new(1, 2) + "a" rescue $!).message == "String can't be coerced into Vector""#
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub struct Operators<T> {
+    binary_ops: Vec<(&'static str, Binary)>,
+    reflected_ops: Vec<(&'static str, Binary)>,
+    unary_ops: Vec<(&'static str, Unary)>,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for Operators<T> {
+    fn default() -> Self {
+        Self {
+            binary_ops: Vec::new(),
+            reflected_ops: Vec::new(),
+            unary_ops: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Operators<T>
+where
+    T: TypedData + Clone + 'static,
+{
+    /// Create a new, empty, `Operators`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare the binary operator `op` implemented by `func`, for `T op R`.
+    ///
+    /// This can be used for operators that don't have a [`std::ops`] trait,
+    /// or to adapt a method to an operator.
+    pub fn binary<R, O>(&mut self, op: &'static str, func: fn(T, R) -> O) -> &mut Self
+    where
+        R: TryConvert + 'static,
+        O: IntoValue + 'static,
+    {
+        self.binary_ops.push((
+            op,
+            Box::new(move |ruby, rb_self, other| {
+                let rhs = match overload_try_convert::<R>(other) {
+                    Ok(rhs) => rhs,
+                    Err(e) => return e.map(Err),
+                };
+                Some(
+                    <&T>::try_convert(rb_self)
+                        .map(|lhs| func(lhs.clone(), rhs).into_value_with(ruby)),
+                )
+            }),
+        ));
+        self
+    }
+
+    /// Declare the reflected binary operator `op` implemented by `func`, for
+    /// `L op T` where the left hand operand is a Ruby value that coerces
+    /// `T`.
+    pub fn reflected<L, O>(&mut self, op: &'static str, func: fn(L, T) -> O) -> &mut Self
+    where
+        L: TryConvert + 'static,
+        O: IntoValue + 'static,
+    {
+        self.reflected_ops.push((
+            op,
+            Box::new(move |ruby, other, rb_self| {
+                let lhs = match overload_try_convert::<L>(other) {
+                    Ok(lhs) => lhs,
+                    Err(e) => return e.map(Err),
+                };
+                Some(
+                    <&T>::try_convert(rb_self)
+                        .map(|rhs| func(lhs, rhs.clone()).into_value_with(ruby)),
+                )
+            }),
+        ));
+        self
+    }
+
+    /// Declare the unary operator `op` implemented by `func`.
+    pub fn unary<O>(&mut self, op: &'static str, func: fn(T) -> O) -> &mut Self
+    where
+        O: IntoValue + 'static,
+    {
+        self.unary_ops.push((
+            op,
+            Box::new(move |ruby, rb_self| {
+                <&T>::try_convert(rb_self).map(|val| func(val.clone()).into_value_with(ruby))
+            }),
+        ));
+        self
+    }
+
+    /// Declare the operator `-@` from `T`'s [`Neg`](ops::Neg) impl.
+    pub fn neg(&mut self) -> &mut Self
+    where
+        T: ops::Neg,
+        T::Output: IntoValue + 'static,
+    {
+        self.unary("-@", <T as ops::Neg>::neg)
+    }
+
+    /// Declare the operator `~` from `T`'s [`Not`](ops::Not) impl.
+    pub fn not(&mut self) -> &mut Self
+    where
+        T: ops::Not,
+        T::Output: IntoValue + 'static,
+    {
+        self.unary("~", <T as ops::Not>::not)
+    }
+
+    /// Define the declared operators as methods on `class`, the class of `T`.
+    ///
+    /// If any reflected operators were declared a `coerce` method is also
+    /// defined. This returns a proxy for the left hand operand, that applies
+    /// the reflected operators, raising a `TypeError` for any operator
+    /// without a reflected impl for the left hand operand.
+    pub fn define(self, class: RClass) -> Result<(), Error> {
+        let ruby = Ruby::get_with(class);
+        for (op, handlers) in group(self.binary_ops) {
+            class.define_method_fn(op, 1, move |ruby, rb_self, args, _block| {
+                for handler in &handlers {
+                    if let Some(res) = handler(ruby, rb_self, args[0]) {
+                        return res;
+                    }
+                }
+                // Ruby's coercion only calls `coerce` on the right hand
+                // operand, so neither operand has to actually be a Numeric
+                let (lhs, rhs) = unsafe {
+                    (
+                        NumericValue::from_rb_value_unchecked(rb_self.as_rb_value()),
+                        NumericValue::from_rb_value_unchecked(args[0].as_rb_value()),
+                    )
+                };
+                lhs.coerce_bin(rhs, op)
+            })?;
+        }
+        for (op, func) in self.unary_ops {
+            class.define_method_fn(op, 0, move |ruby, rb_self, _args, _block| {
+                func(ruby, rb_self)
+            })?;
+        }
+        if self.reflected_ops.is_empty() {
+            return Ok(());
+        }
+        let mut reflected_ops = group(self.reflected_ops);
+        for &op in BINARY_OPERATORS {
+            if !reflected_ops.iter().any(|(o, _)| *o == op) {
+                reflected_ops.push((op, Vec::new()));
+            }
+        }
+        let proxy = RClass::new(ruby.class_object())?;
+        for (op, handlers) in reflected_ops {
+            proxy.define_method_fn(op, 1, move |ruby, rb_proxy, args, _block| {
+                let other: Value = RObject::try_convert(rb_proxy)?.ivar_get("@value")?;
+                for handler in &handlers {
+                    if let Some(res) = handler(ruby, other, args[0]) {
+                        return res;
+                    }
+                }
+                Err(Error::new(
+                    ruby.exception_type_error(),
+                    format!(
+                        "{} can't be coerced into {}",
+                        unsafe { args[0].classname() },
+                        unsafe { other.classname() },
+                    ),
+                ))
+            })?;
+        }
+        // the proxy class is anonymous and only referenced from the `coerce`
+        // closure, so must be rooted to keep it alive and stop it moving
+        gc::register_mark_object(proxy);
+        let proxy = Opaque::from(proxy);
+        class.define_method_fn("coerce", 1, move |ruby, rb_self, args, _block| {
+            let rb_proxy = RObject::try_convert(ruby.get_inner(proxy).new_instance(())?)?;
+            rb_proxy.ivar_set("@value", args[0])?;
+            Ok((rb_proxy, rb_self))
+        })
+    }
+}
+
+macro_rules! binary_operators {
+    ($($trait:ident, $func:ident, $rfunc:ident, $op:literal;)*) => {
+        const BINARY_OPERATORS: &[&str] = &[$($op),*];
+
+        impl<T> Operators<T>
+        where
+            T: TypedData + Clone + 'static,
+        {
+            $(
+                #[doc = concat!("Declare the operator `", $op, "` for `T ", $op, " R`, from `T`'s [`", stringify!($trait), "<R>`] impl.")]
+                pub fn $func<R>(&mut self) -> &mut Self
+                where
+                    T: $trait<R>,
+                    R: TryConvert + 'static,
+                    <T as $trait<R>>::Output: IntoValue + 'static,
+                {
+                    self.binary::<R, _>($op, <T as $trait<R>>::$func)
+                }
+
+                #[doc = concat!("Declare the reflected operator `", $op, "` for `L ", $op, " T`, from `L`'s [`", stringify!($trait), "<T>`] impl.")]
+                pub fn $rfunc<L>(&mut self) -> &mut Self
+                where
+                    L: $trait<T> + TryConvert + 'static,
+                    <L as $trait<T>>::Output: IntoValue + 'static,
+                {
+                    self.reflected::<L, _>($op, <L as $trait<T>>::$func)
+                }
+            )*
+        }
+    };
+}
+
+use ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Rem, Shl, Shr, Sub};
+
+binary_operators! {
+    Add, add, radd, "+";
+    Sub, sub, rsub, "-";
+    Mul, mul, rmul, "*";
+    Div, div, rdiv, "/";
+    Rem, rem, rrem, "%";
+    Pow, pow, rpow, "**";
+    BitAnd, bitand, rbitand, "&";
+    BitOr, bitor, rbitor, "|";
+    BitXor, bitxor, rbitxor, "^";
+    Shl, shl, rshl, "<<";
+    Shr, shr, rshr, ">>";
+}
+
+impl<T> fmt::Debug for Operators<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Operators")
+            .field("binary", &names(&self.binary_ops))
+            .field("reflected", &names(&self.reflected_ops))
+            .field("unary", &names(&self.unary_ops))
+            .finish()
+    }
+}
+
+fn names<F>(ops: &[(&'static str, F)]) -> Vec<&'static str> {
+    ops.iter().map(|(op, _)| *op).collect()
+}
+
+/// Groups handlers by operator, preserving the order of declaration.
+fn group<F>(ops: Vec<(&'static str, F)>) -> Vec<(&'static str, Vec<F>)> {
+    let mut groups: Vec<(&'static str, Vec<F>)> = Vec::new();
+    for (op, func) in ops {
+        match groups.iter_mut().find(|(o, _)| *o == op) {
+            Some((_, funcs)) => funcs.push(func),
+            None => groups.push((op, vec![func])),
+        }
+    }
+    groups
+}
@@ -23,6 +23,7 @@ use crate::{
     into_value::{IntoValue, IntoValueFromNative},
     object::Object,
     try_convert::{TryConvert, TryConvertOwned},
+    typed_data::borrow,
     value::{
         Fixnum, NonZeroValue, QUNDEF, ReprValue, Value,
         private::{self, ReprValue as _},
@@ -60,6 +61,7 @@ where
     unsafe fn call_handle_error(self, key: Value, value: Value) -> ForEach {
         unsafe {
             let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _borrows = borrow::Scope::new();
                 self.call_convert_value(key, value)
             })) {
                 Ok(v) => v,
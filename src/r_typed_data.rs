@@ -376,6 +376,17 @@ impl RTypedData {
     /// `'static`.
     #[inline]
     pub(crate) unsafe fn get_unconstrained<'a, T>(self) -> Result<&'a T, Error>
+    where
+        T: TypedData,
+    {
+        unsafe { self.get_ptr::<T>().map(|ptr| &*ptr) }
+    }
+
+    /// Get a pointer to the Rust type wrapped in the Ruby object `self`.
+    ///
+    /// The returned pointer is non-null.
+    #[inline]
+    pub(crate) fn get_ptr<T>(self) -> Result<*mut T, Error>
     where
         T: TypedData,
     {
@@ -384,11 +395,11 @@ impl RTypedData {
             let handle = Ruby::get_with(self);
             let mut res = None;
             let _ = protect(|| {
-                res = (rb_check_typeddata(
+                let ptr = rb_check_typeddata(
                     self.as_rb_value(),
                     T::data_type().as_rb_data_type() as *const _,
-                ) as *const T)
-                    .as_ref();
+                ) as *mut T;
+                res = (!ptr.is_null()).then_some(ptr);
                 handle.qnil()
             });
             res.ok_or_else(|| {
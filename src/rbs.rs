@@ -0,0 +1,1010 @@
+//! Generating [RBS](https://github.com/ruby/rbs) type signatures for Rust
+//! functions bound as Ruby methods.
+//!
+//! Signatures are built from the Rust types of a function's parameters and
+//! return value, via the [`RbsType`] trait, with the
+//! [`method_signature`](crate::method_signature!) and
+//! [`function_signature`](crate::function_signature!) macros. These take the
+//! same arguments as the [`method`](crate::method!) and
+//! [`function`](crate::function!) macros.
+//!
+//! Signatures are collected in a [`Signatures`] value, which can be written
+//! out as a `.rbs` file for use with tools such as Steep.
+//!
+//! With the `rbs` feature enabled the [`method`](crate::method!) and
+//! [`function`](crate::function!) macros also capture the signature of the
+//! wrapped function, and methods defined with
+//! [`define_method`](crate::module::Module::define_method),
+//! [`define_singleton_method`](crate::Object::define_singleton_method) and
+//! [`define_module_function`](crate::RModule::define_module_function) on a
+//! named class or module are recorded automatically. The recorded signatures
+//! are returned by [`Signatures::bound`]. This requires every argument and
+//! return type of every bound function to implement [`RbsType`].
+//!
+//! # Type mapping
+//!
+//! | Rust                                        | RBS                   |
+//! |---------------------------------------------|-----------------------|
+//! | `bool`                                      | `bool`                |
+//! | `i8`…`i128`, `u8`…`u128`, `isize`, `usize`  | `Integer`             |
+//! | [`Integer`], [`Fixnum`], [`RBignum`]        | `Integer`             |
+//! | `f32`, `f64`, [`Float`], [`RFloat`]         | `Float`               |
+//! | `String`, `&str`, `char`, `PathBuf`, [`RString`] | `String`         |
+//! | [`Symbol`], [`StaticSymbol`]                | `Symbol`              |
+//! | `()`                                        | `void` (`nil` in `T?`) |
+//! | [`Value`], [`RTypedData`]                   | `untyped`             |
+//! | [`RObject`]                                 | `Object`              |
+//! | `Option<T>`                                 | `T?`                  |
+//! | `Vec<T>`, `[T; N]`, [`TypedArray<T>`]       | `Array[T]`            |
+//! | [`RArray`]                                  | `Array[untyped]`      |
+//! | `(T, U, ...)`                               | `[T, U, ...]`         |
+//! | `HashMap<K, V>`, `BTreeMap<K, V>`           | `Hash[K, V]`          |
+//! | [`RHash`]                                   | `Hash[untyped, untyped]` |
+//! | [`Range`]                                   | `Range[untyped]`      |
+//! | [`Proc`]                                    | `Proc`                |
+//! | [`Enumerator`]                              | `Enumerator[untyped, untyped]` |
+//! | [`Yield<I>`], [`YieldValues<I>`], [`YieldSplat<I>`] | `Enumerator[untyped, nil]?` |
+//! | [`Exception`]                               | `Exception`           |
+//! | [`ExceptionClass`]                          | `singleton(Exception)` |
+//! | [`RClass`], [`RModule`]                     | `Class`, `Module`     |
+//! | [`Time`]                                    | `Time`                |
+//! | [`Opaque<T>`]                               | `T`                   |
+//! | [`RustIterator`](crate::iterator::RustIterator) | `Enumerable[untyped]` |
+//! | [`Obj<T>`], `&T`, `&mut T`                  | [`TypedData::RBS_TYPE`] |
+//! | `Result<T, E>`                              | `T`                   |
+//!
+//! Types wrapped with [`#[wrap]`](macro@crate::wrap) or
+//! [`#[derive(TypedData)]`](macro@crate::TypedData) implement [`RbsType`],
+//! and set [`TypedData::RBS_TYPE`], as their Ruby class. Other
+//! [`TypedData`] types are `untyped` unless they set
+//! [`TypedData::RBS_TYPE`]. Enums deriving
+//! [`SymbolEnum`](macro@crate::SymbolEnum) implement [`RbsType`] as a union
+//! of their symbols.
+//!
+//! Methods with arity `-1` or `-2` are given the signature
+//! `(*untyped) -> T`. Optional arguments, with an arity such as `1..=3`, are
+//! marked optional with `?`.
+//!
+//! # Examples
+//!
+//! ```
+//! use magnus::rbs::Signatures;
+//!
+//! #[magnus::wrap(class = "Point")]
+//! struct Point {
+//!     x: isize,
+//!     y: isize,
+//! }
+//!
+//! impl Point {
+//!     fn new(x: isize, y: isize) -> Self {
+//!         Self { x, y }
+//!     }
+//!
+//!     fn x(&self) -> isize {
+//!         self.x
+//!     }
+//!
+//!     fn distance(&self, other: &Point) -> f64 {
+//!         (((other.x - self.x).pow(2) + (other.y - self.y).pow(2)) as f64).sqrt()
+//!     }
+//! }
+//!
+//! let mut signatures = Signatures::new();
+//! signatures
+//!     .class("Point")
+//!     .singleton_method("new", magnus::function_signature!(Point::new, 2))
+//!     .method("x", magnus::method_signature!(Point::x, 0))
+//!     .method("distance", magnus::method_signature!(Point::distance, 1));
+//!
+//! assert_eq!(
+//!     signatures.to_string(),
+//!     r#"class Point
+//!   def self.new: (Integer, Integer) -> Point
+//!   def x: () -> Integer
+//!   def distance: (Point) -> Float
+//! end
+//! "#
+//! );
+//! ```
+
+#[cfg(feature = "rbs")]
+use std::sync::Mutex;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    Enumerator, Exception, ExceptionClass, Fixnum, Float, Integer, RArray, RBignum, RClass, RFloat,
+    RHash, RModule, RObject, RString, RTypedData, Range, StaticSymbol, Symbol, Time, Value,
+    block::{Proc, Yield, YieldSplat, YieldValues},
+    r_array::TypedArray,
+    typed_data::{Obj, TypedData},
+    value::Opaque,
+};
+
+/// Trait for types that have a corresponding [RBS](self) type.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::rbs::RbsType;
+///
+/// assert_eq!(<Option<Vec<String>>>::rbs_type(), "Array[String]?");
+/// assert_eq!(<(i64, f64)>::rbs_type(), "[Integer, Float]");
+/// ```
+pub trait RbsType {
+    /// Returns the RBS type for `Self`.
+    fn rbs_type() -> String;
+}
+
+macro_rules! impl_rbs_type {
+    ($rbs:literal, $($type:ty),*) => {
+        $(
+            impl RbsType for $type {
+                fn rbs_type() -> String {
+                    $rbs.to_owned()
+                }
+            }
+        )*
+    };
+    ($rbs:literal, $($type:ty),*; $param:ident) => {
+        $(
+            impl<$param> RbsType for $type {
+                fn rbs_type() -> String {
+                    $rbs.to_owned()
+                }
+            }
+        )*
+    };
+}
+
+impl_rbs_type!("bool", bool);
+impl_rbs_type!(
+    "Integer", i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, Integer, Fixnum,
+    RBignum
+);
+impl_rbs_type!("Float", f32, f64, Float, RFloat);
+impl_rbs_type!("String", String, &str, char, PathBuf, RString);
+impl_rbs_type!("Symbol", Symbol, StaticSymbol);
+impl_rbs_type!("void", ());
+impl_rbs_type!("untyped", Value, RTypedData);
+impl_rbs_type!("Object", RObject);
+impl_rbs_type!("Array[untyped]", RArray);
+impl_rbs_type!("Hash[untyped, untyped]", RHash);
+impl_rbs_type!("Range[untyped]", Range);
+impl_rbs_type!("Proc", Proc);
+impl_rbs_type!("Enumerator[untyped, untyped]", Enumerator);
+impl_rbs_type!("Exception", Exception);
+impl_rbs_type!("singleton(Exception)", ExceptionClass);
+impl_rbs_type!("Class", RClass);
+impl_rbs_type!("Module", RModule);
+impl_rbs_type!("Time", Time);
+
+impl<T> RbsType for Opaque<T>
+where
+    T: RbsType,
+{
+    fn rbs_type() -> String {
+        T::rbs_type()
+    }
+}
+
+// without a block these return an Enumerator, with a block `nil`
+impl_rbs_type!("Enumerator[untyped, nil]?", Yield<I>, YieldValues<I>, YieldSplat<I>; I);
+
+impl<T> RbsType for Option<T>
+where
+    T: RbsType,
+{
+    fn rbs_type() -> String {
+        let ty = T::rbs_type();
+        if ty == "void" {
+            "nil".to_owned()
+        } else if ty == "untyped" || ty == "nil" || ty.ends_with('?') {
+            ty
+        } else if ty.contains(" | ") {
+            format!("({})?", ty)
+        } else {
+            format!("{}?", ty)
+        }
+    }
+}
+
+impl<T, E> RbsType for Result<T, E>
+where
+    T: RbsType,
+{
+    fn rbs_type() -> String {
+        T::rbs_type()
+    }
+}
+
+impl<T> RbsType for Vec<T>
+where
+    T: RbsType,
+{
+    fn rbs_type() -> String {
+        format!("Array[{}]", T::rbs_type())
+    }
+}
+
+impl<T, const N: usize> RbsType for [T; N]
+where
+    T: RbsType,
+{
+    fn rbs_type() -> String {
+        format!("Array[{}]", T::rbs_type())
+    }
+}
+
+impl<T> RbsType for TypedArray<T>
+where
+    T: RbsType,
+{
+    fn rbs_type() -> String {
+        format!("Array[{}]", T::rbs_type())
+    }
+}
+
+impl<K, V, S> RbsType for HashMap<K, V, S>
+where
+    K: RbsType,
+    V: RbsType,
+{
+    fn rbs_type() -> String {
+        format!("Hash[{}, {}]", K::rbs_type(), V::rbs_type())
+    }
+}
+
+impl<K, V> RbsType for BTreeMap<K, V>
+where
+    K: RbsType,
+    V: RbsType,
+{
+    fn rbs_type() -> String {
+        format!("Hash[{}, {}]", K::rbs_type(), V::rbs_type())
+    }
+}
+
+impl<T> RbsType for Obj<T>
+where
+    T: TypedData,
+{
+    fn rbs_type() -> String {
+        T::RBS_TYPE.to_owned()
+    }
+}
+
+impl<T> RbsType for &T
+where
+    T: TypedData,
+{
+    fn rbs_type() -> String {
+        T::RBS_TYPE.to_owned()
+    }
+}
+
+impl<T> RbsType for &mut T
+where
+    T: TypedData,
+{
+    fn rbs_type() -> String {
+        T::RBS_TYPE.to_owned()
+    }
+}
+
+macro_rules! impl_rbs_type_for_tuple {
+    ($($type:ident),*) => {
+        impl<$($type,)*> RbsType for ($($type,)*)
+        where
+            $($type: RbsType,)*
+        {
+            fn rbs_type() -> String {
+                let types: &[String] = &[$($type::rbs_type(),)*];
+                format!("[{}]", types.join(", "))
+            }
+        }
+    };
+}
+
+impl_rbs_type_for_tuple!(T0);
+impl_rbs_type_for_tuple!(T0, T1);
+impl_rbs_type_for_tuple!(T0, T1, T2);
+impl_rbs_type_for_tuple!(T0, T1, T2, T3);
+impl_rbs_type_for_tuple!(T0, T1, T2, T3, T4);
+impl_rbs_type_for_tuple!(T0, T1, T2, T3, T4, T5);
+impl_rbs_type_for_tuple!(T0, T1, T2, T3, T4, T5, T6);
+impl_rbs_type_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+
+/// The RBS signature of a single method.
+///
+/// See the [`method_signature`](crate::method_signature!) and
+/// [`function_signature`](crate::function_signature!) macros.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MethodSignature {
+    params: Vec<String>,
+    required: Option<usize>,
+    variadic: bool,
+    block: Option<bool>,
+    ret: String,
+}
+
+impl MethodSignature {
+    /// Create a new `MethodSignature` with the given positional parameter
+    /// types and return type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::rbs::MethodSignature;
+    ///
+    /// let sig = MethodSignature::new(vec!["Integer".to_owned()], "String".to_owned());
+    /// assert_eq!(sig.to_string(), "(Integer) -> String");
+    /// ```
+    pub fn new(params: Vec<String>, ret: String) -> Self {
+        Self {
+            params,
+            required: None,
+            variadic: false,
+            block: None,
+            ret,
+        }
+    }
+
+    /// Create a new `MethodSignature` for a method taking any number of
+    /// untyped arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::rbs::MethodSignature;
+    ///
+    /// let sig = MethodSignature::variadic("bool".to_owned());
+    /// assert_eq!(sig.to_string(), "(*untyped) -> bool");
+    /// ```
+    pub fn variadic(ret: String) -> Self {
+        Self {
+            params: Vec::new(),
+            required: None,
+            variadic: true,
+            block: None,
+            ret,
+        }
+    }
+
+    /// Mark the parameters after the first `required` as optional.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::rbs::MethodSignature;
+    ///
+    /// let sig = MethodSignature::new(
+    ///     vec!["Integer".to_owned(), "String?".to_owned()],
+    ///     "String".to_owned(),
+    /// )
+    /// .optional_after(1);
+    /// assert_eq!(sig.to_string(), "(Integer, ?String?) -> String");
+    /// ```
+    pub fn optional_after(mut self, required: usize) -> Self {
+        self.required = Some(required);
+        self
+    }
+
+    /// Mark the method as taking a block, either `required` or optional.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::rbs::MethodSignature;
+    ///
+    /// let sig = MethodSignature::new(vec!["Integer".to_owned()], "Array".to_owned())
+    ///     .with_block(false);
+    /// assert_eq!(
+    ///     sig.to_string(),
+    ///     "(Integer) ?{ (*untyped) -> untyped } -> Array"
+    /// );
+    /// ```
+    pub fn with_block(mut self, required: bool) -> Self {
+        self.block = Some(required);
+        self
+    }
+}
+
+impl fmt::Display for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let block = match self.block {
+            Some(true) => "{ (*untyped) -> untyped } ",
+            Some(false) => "?{ (*untyped) -> untyped } ",
+            None => "",
+        };
+        if self.variadic {
+            return write!(f, "(*untyped) {}-> {}", block, self.ret);
+        }
+        let params = self
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, p)| match self.required {
+                Some(required) if i >= required => format!("?{}", p),
+                _ => p.clone(),
+            })
+            .collect::<Vec<_>>();
+        write!(f, "({}) {}-> {}", params.join(", "), block, self.ret)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Kind {
+    Class { superclass: Option<String> },
+    Module,
+}
+
+/// The RBS declaration of a class or module.
+///
+/// See [`Signatures::class`] and [`Signatures::module`].
+#[derive(Clone, Debug)]
+pub struct Declaration {
+    name: String,
+    kind: Kind,
+    methods: Vec<(bool, String, Vec<MethodSignature>)>,
+}
+
+impl Declaration {
+    /// Set the superclass of a class declaration.
+    ///
+    /// Has no effect on module declarations.
+    pub fn superclass(&mut self, name: &str) -> &mut Self {
+        if let Kind::Class { superclass } = &mut self.kind {
+            *superclass = Some(name.to_owned());
+        }
+        self
+    }
+
+    /// Add the signature of an instance method.
+    ///
+    /// Adding more than one signature with the same name declares an
+    /// overloaded method.
+    pub fn method(&mut self, name: &str, sig: MethodSignature) -> &mut Self {
+        self.add(false, name, sig)
+    }
+
+    /// Add the signature of a singleton method, e.g. for a method defined
+    /// with [`define_singleton_method`](crate::Object::define_singleton_method)
+    /// or [`define_module_function`](crate::Module::define_module_function).
+    ///
+    /// Adding more than one signature with the same name declares an
+    /// overloaded method.
+    pub fn singleton_method(&mut self, name: &str, sig: MethodSignature) -> &mut Self {
+        self.add(true, name, sig)
+    }
+
+    fn add(&mut self, singleton: bool, name: &str, sig: MethodSignature) -> &mut Self {
+        match self
+            .methods
+            .iter_mut()
+            .find(|(s, n, _)| *s == singleton && n == name)
+        {
+            Some((_, _, sigs)) => sigs.push(sig),
+            None => self.methods.push((singleton, name.to_owned(), vec![sig])),
+        }
+        self
+    }
+}
+
+impl fmt::Display for Declaration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            Kind::Class {
+                superclass: Some(superclass),
+            } => writeln!(f, "class {} < {}", self.name, superclass)?,
+            Kind::Class { superclass: None } => writeln!(f, "class {}", self.name)?,
+            Kind::Module => writeln!(f, "module {}", self.name)?,
+        }
+        for (singleton, name, sigs) in &self.methods {
+            let prefix = if *singleton { "self." } else { "" };
+            let sigs = sigs
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(" | ");
+            writeln!(f, "  def {}{}: {}", prefix, name, sigs)?;
+        }
+        writeln!(f, "end")
+    }
+}
+
+/// A collection of RBS class and module declarations.
+///
+/// The [`Display`](fmt::Display) implementation outputs the declarations in
+/// RBS syntax, in the order they were first added.
+///
+/// See the [module level documentation](self) for an example.
+#[derive(Clone, Debug, Default)]
+pub struct Signatures {
+    decls: Vec<Declaration>,
+}
+
+impl Signatures {
+    /// Create a new, empty, `Signatures`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the signatures of all methods bound so far with
+    /// [`method`](crate::method!) or [`function`](crate::function!).
+    ///
+    /// Methods are recorded when defined on a named class or module with
+    /// [`define_method`](crate::module::Module::define_method),
+    /// [`define_singleton_method`](crate::Object::define_singleton_method) or
+    /// [`define_module_function`](crate::RModule::define_module_function).
+    /// Private and protected methods are not recorded. The returned value can
+    /// be further amended, e.g. to set a class's superclass, before it is
+    /// written out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, function, method, prelude::*, rbs::Signatures};
+    ///
+    /// fn rb_is_blank(rb_self: String) -> bool {
+    ///     rb_self.contains(|c: char| !c.is_whitespace())
+    /// }
+    ///
+    /// fn rb_greet(name: String) -> String {
+    ///     format!("Hello, {}!", name)
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.class_string()
+    ///         .define_method("blank?", method!(rb_is_blank, 0))?;
+    ///     let module = ruby.define_module("Greeter")?;
+    ///     module.define_module_function("greet", function!(rb_greet, 1))?;
+    ///
+    ///     assert_eq!(
+    ///         Signatures::bound().to_string(),
+    ///         r#"class String
+    ///   def blank?: () -> bool
+    /// end
+    ///
+    /// module Greeter
+    ///   def self.greet: (String) -> String
+    /// end
+    /// "#
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(feature = "rbs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rbs")))]
+    pub fn bound() -> Self {
+        BOUND.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Returns the declaration for the class `name`, creating it if needed.
+    ///
+    /// `name` may be a nested name such as `"Foo::Bar"`, in which case `Foo`
+    /// must also be declared (here or in another signature file).
+    pub fn class(&mut self, name: &str) -> &mut Declaration {
+        self.declaration(name, Kind::Class { superclass: None })
+    }
+
+    /// Returns the declaration for the module `name`, creating it if needed.
+    ///
+    /// `name` may be a nested name such as `"Foo::Bar"`, in which case `Foo`
+    /// must also be declared (here or in another signature file).
+    pub fn module(&mut self, name: &str) -> &mut Declaration {
+        self.declaration(name, Kind::Module)
+    }
+
+    fn declaration(&mut self, name: &str, kind: Kind) -> &mut Declaration {
+        match self.decls.iter().position(|d| d.name == name) {
+            Some(i) => &mut self.decls[i],
+            None => {
+                self.decls.push(Declaration {
+                    name: name.to_owned(),
+                    kind,
+                    methods: Vec::new(),
+                });
+                self.decls.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Write the declarations to the file at `path`, in RBS syntax.
+    ///
+    /// The file is created if it does not exist, and overwritten if it does.
+    pub fn write<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, self.to_string())
+    }
+}
+
+#[cfg(feature = "rbs")]
+static BOUND: Mutex<Signatures> = Mutex::new(Signatures { decls: Vec::new() });
+
+/// Record the signature of a method bound on `owner`, if `owner` is a named
+/// class or module.
+#[cfg(feature = "rbs")]
+pub(crate) fn record(owner: Value, singleton: bool, name: &str, sigs: Vec<MethodSignature>) {
+    use rb_sys::rb_mod_name;
+
+    use crate::{try_convert::TryConvert, value::private::ReprValue as _};
+
+    if sigs.is_empty() {
+        return;
+    }
+    let is_class = RClass::from_value(owner).is_some();
+    if !is_class && RModule::from_value(owner).is_none() {
+        return;
+    }
+    // nil for anonymous classes and modules
+    let owner_name = unsafe { Value::new(rb_mod_name(owner.as_rb_value())) };
+    let Ok(Some(owner_name)) = Option::<String>::try_convert(owner_name) else {
+        return;
+    };
+    let mut bound = BOUND.lock().unwrap_or_else(|e| e.into_inner());
+    let decl = if is_class {
+        bound.class(&owner_name)
+    } else {
+        bound.module(&owner_name)
+    };
+    for sig in sigs {
+        decl.add(singleton, name, sig);
+    }
+}
+
+impl fmt::Display for Signatures {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, decl) in self.decls.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            decl.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the [RBS](crate::rbs) signature of a Rust function that would be
+/// wrapped as a Ruby method with the [`method`](crate::method!) macro.
+///
+/// Takes the same arguments as [`method`](crate::method!), including ranges
+/// for optional arguments. The `self`
+/// argument, and a leading [`&Ruby`](crate::Ruby) argument, are not included
+/// in the signature. All other argument types, and the return type, must
+/// implement [`RbsType`].
+///
+/// # Examples
+///
+/// ```
+/// fn rb_is_blank(rb_self: String) -> bool {
+///     rb_self.contains(|c: char| !c.is_whitespace())
+/// }
+///
+/// let sig = magnus::method_signature!(rb_is_blank, 0);
+/// assert_eq!(sig.to_string(), "() -> bool");
+/// ```
+#[macro_export]
+macro_rules! method_signature {
+    ($name:expr_2021, -2) => {{
+        use $crate::method::{MethodRbAry, RubyMethodRbAry};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, -1) => {{
+        use $crate::method::{MethodCAry, RubyMethodCAry};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 0) => {{
+        use $crate::method::{Method0, MethodBlock0, RubyMethod0, RubyMethodBlock0};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 1) => {{
+        use $crate::method::{Method1, MethodBlock1, RubyMethod1, RubyMethodBlock1};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 2) => {{
+        use $crate::method::{Method2, MethodBlock2, RubyMethod2, RubyMethodBlock2};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 3) => {{
+        use $crate::method::{Method3, MethodBlock3, RubyMethod3, RubyMethodBlock3};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 4) => {{
+        use $crate::method::{Method4, MethodBlock4, RubyMethod4, RubyMethodBlock4};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 5) => {{
+        use $crate::method::{Method5, MethodBlock5, RubyMethod5, RubyMethodBlock5};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 6) => {{
+        use $crate::method::{Method6, MethodBlock6, RubyMethod6, RubyMethodBlock6};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 7) => {{
+        use $crate::method::{Method7, MethodBlock7, RubyMethod7, RubyMethodBlock7};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 8) => {{
+        use $crate::method::{Method8, MethodBlock8, RubyMethod8, RubyMethodBlock8};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 9) => {{
+        use $crate::method::{Method9, MethodBlock9, RubyMethod9, RubyMethodBlock9};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 10) => {{
+        use $crate::method::{Method10, MethodBlock10, RubyMethod10, RubyMethodBlock10};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 11) => {{
+        use $crate::method::{Method11, MethodBlock11, RubyMethod11, RubyMethodBlock11};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 12) => {{
+        use $crate::method::{Method12, MethodBlock12, RubyMethod12, RubyMethodBlock12};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 13) => {{
+        use $crate::method::{Method13, MethodBlock13, RubyMethod13, RubyMethodBlock13};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 14) => {{
+        use $crate::method::{Method14, MethodBlock14, RubyMethod14, RubyMethodBlock14};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 15) => {{
+        use $crate::method::{Method15, MethodBlock15, RubyMethod15, RubyMethodBlock15};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 16) => {{
+        use $crate::method::{Method16, RubyMethod16};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 17) => {{
+        use $crate::method::{Method17, RubyMethod17};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 18) => {{
+        use $crate::method::{Method18, RubyMethod18};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 19) => {{
+        use $crate::method::{Method19, RubyMethod19};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 20) => {{
+        use $crate::method::{Method20, RubyMethod20};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 21) => {{
+        use $crate::method::{Method21, RubyMethod21};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 22) => {{
+        use $crate::method::{Method22, RubyMethod22};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 23) => {{
+        use $crate::method::{Method23, RubyMethod23};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 24) => {{
+        use $crate::method::{Method24, RubyMethod24};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 25) => {{
+        use $crate::method::{Method25, RubyMethod25};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 26) => {{
+        use $crate::method::{Method26, RubyMethod26};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 27) => {{
+        use $crate::method::{Method27, RubyMethod27};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 28) => {{
+        use $crate::method::{Method28, RubyMethod28};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 29) => {{
+        use $crate::method::{Method29, RubyMethod29};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 30) => {{
+        use $crate::method::{Method30, RubyMethod30};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 31) => {{
+        use $crate::method::{Method31, RubyMethod31};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, *) => {{
+        use $crate::method::{MethodSplat, RubyMethodSplat};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, $required:tt..=$max:tt) => {
+        $crate::method_signature!($name, $max).optional_after($required)
+    };
+    ($name:expr_2021, $arity:expr_2021) => {
+        compile_error!("arity must be `*` or an integer literal between -2..=31")
+    };
+}
+
+/// Returns the [RBS](crate::rbs) signature of a Rust function that would be
+/// wrapped as a Ruby method with the [`function`](crate::function!) macro.
+///
+/// Takes the same arguments as [`function`](crate::function!). A leading
+/// [`&Ruby`](crate::Ruby) argument is not included in the signature. All
+/// other argument types, and the return type, must implement [`RbsType`].
+///
+/// # Examples
+///
+/// ```
+/// fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+///     ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+/// }
+///
+/// let sig = magnus::function_signature!(distance, 2);
+/// assert_eq!(sig.to_string(), "([Float, Float], [Float, Float]) -> Float");
+/// ```
+#[macro_export]
+macro_rules! function_signature {
+    ($name:expr_2021, -2) => {{
+        use $crate::method::{FunctionRbAry, RubyFunctionRbAry};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, -1) => {{
+        use $crate::method::{FunctionCAry, RubyFunctionCAry};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 0) => {{
+        use $crate::method::{Function0, FunctionBlock0, RubyFunction0, RubyFunctionBlock0};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 1) => {{
+        use $crate::method::{Function1, FunctionBlock1, RubyFunction1, RubyFunctionBlock1};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 2) => {{
+        use $crate::method::{Function2, FunctionBlock2, RubyFunction2, RubyFunctionBlock2};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 3) => {{
+        use $crate::method::{Function3, FunctionBlock3, RubyFunction3, RubyFunctionBlock3};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 4) => {{
+        use $crate::method::{Function4, FunctionBlock4, RubyFunction4, RubyFunctionBlock4};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 5) => {{
+        use $crate::method::{Function5, FunctionBlock5, RubyFunction5, RubyFunctionBlock5};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 6) => {{
+        use $crate::method::{Function6, FunctionBlock6, RubyFunction6, RubyFunctionBlock6};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 7) => {{
+        use $crate::method::{Function7, FunctionBlock7, RubyFunction7, RubyFunctionBlock7};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 8) => {{
+        use $crate::method::{Function8, FunctionBlock8, RubyFunction8, RubyFunctionBlock8};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 9) => {{
+        use $crate::method::{Function9, FunctionBlock9, RubyFunction9, RubyFunctionBlock9};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 10) => {{
+        use $crate::method::{Function10, FunctionBlock10, RubyFunction10, RubyFunctionBlock10};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 11) => {{
+        use $crate::method::{Function11, FunctionBlock11, RubyFunction11, RubyFunctionBlock11};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 12) => {{
+        use $crate::method::{Function12, FunctionBlock12, RubyFunction12, RubyFunctionBlock12};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 13) => {{
+        use $crate::method::{Function13, FunctionBlock13, RubyFunction13, RubyFunctionBlock13};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 14) => {{
+        use $crate::method::{Function14, FunctionBlock14, RubyFunction14, RubyFunctionBlock14};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 15) => {{
+        use $crate::method::{Function15, FunctionBlock15, RubyFunction15, RubyFunctionBlock15};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 16) => {{
+        use $crate::method::{Function16, RubyFunction16};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 17) => {{
+        use $crate::method::{Function17, RubyFunction17};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 18) => {{
+        use $crate::method::{Function18, RubyFunction18};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 19) => {{
+        use $crate::method::{Function19, RubyFunction19};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 20) => {{
+        use $crate::method::{Function20, RubyFunction20};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 21) => {{
+        use $crate::method::{Function21, RubyFunction21};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 22) => {{
+        use $crate::method::{Function22, RubyFunction22};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 23) => {{
+        use $crate::method::{Function23, RubyFunction23};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 24) => {{
+        use $crate::method::{Function24, RubyFunction24};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 25) => {{
+        use $crate::method::{Function25, RubyFunction25};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 26) => {{
+        use $crate::method::{Function26, RubyFunction26};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 27) => {{
+        use $crate::method::{Function27, RubyFunction27};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 28) => {{
+        use $crate::method::{Function28, RubyFunction28};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 29) => {{
+        use $crate::method::{Function29, RubyFunction29};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 30) => {{
+        use $crate::method::{Function30, RubyFunction30};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, 31) => {{
+        use $crate::method::{Function31, RubyFunction31};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, *) => {{
+        use $crate::method::{FunctionSplat, RubyFunctionSplat};
+        $name.rbs_signature()
+    }};
+    ($name:expr_2021, $required:tt..=$max:tt) => {
+        $crate::function_signature!($name, $max).optional_after($required)
+    };
+    ($name:expr_2021, $arity:expr_2021) => {
+        compile_error!("arity must be `*` or an integer literal between -2..=31")
+    };
+}
@@ -29,9 +29,13 @@ use crate::{
     error::{Error, bug_from_panic},
     gc::{self, Mark},
     into_value::IntoValue,
+    module::Module,
     object::Object,
+    r_array::RArray,
+    r_hash::RHash,
     r_typed_data::RTypedData,
     scan_args::{get_kwargs, scan_args},
+    symbol::Symbol,
     try_convert::TryConvert,
     value::{
         ReprValue, Value,
@@ -227,12 +231,70 @@ where
     }
 }
 
+/// Marks fields annotated with `#[magnus(mark)]` or `#[magnus(compact)]`.
+///
+/// Implemented by the [`TypedData`](macro@crate::TypedData) derive, and
+/// called in addition to [`DataTypeFunctions::mark`] and
+/// [`DataTypeFunctions::compact`], so annotated fields are marked whether
+/// `DataTypeFunctions` is derived or implemented by hand. Don't implement.
+#[doc(hidden)]
+pub trait MarkFields: DataTypeFunctions {
+    /// Mark the annotated fields.
+    fn mark_fields(&self, marker: &gc::Marker);
+
+    /// Update the fields annotated with `#[magnus(compact)]`.
+    fn compact_fields(&self, #[allow(unused_variables)] compactor: &gc::Compactor) {}
+
+    /// Extern wrapper for `mark_fields` and `mark`. Don't define or call.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a valid pointer to a `Self`, and must not be aliased.
+    ///
+    /// This function must not panic.
+    unsafe extern "C" fn extern_mark_fields(ptr: *mut c_void) {
+        unsafe {
+            let marker = gc::Marker::new();
+            if let Err(e) = catch_unwind(|| {
+                let data = &*(ptr as *mut Self);
+                data.mark_fields(&marker);
+                data.mark(&marker);
+            }) {
+                bug_from_panic(e, "panic in DataTypeFunctions::mark")
+            }
+        }
+    }
+
+    /// Extern wrapper for `compact_fields` and `compact`. Don't define or
+    /// call.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a valid pointer to a `Self`, and must not be aliased.
+    ///
+    /// This function must not panic.
+    unsafe extern "C" fn extern_compact_fields(ptr: *mut c_void) {
+        unsafe {
+            let compactor = gc::Compactor::new();
+            if let Err(e) = catch_unwind(|| {
+                let data = &*(ptr as *mut Self);
+                data.compact_fields(&compactor);
+                data.compact(&compactor);
+            }) {
+                bug_from_panic(e, "panic in DataTypeFunctions::compact")
+            }
+        }
+    }
+}
+
 /// A builder for [`DataType`].
 pub struct DataTypeBuilder<T> {
     name: &'static CStr,
     mark: bool,
+    mark_fields: Option<unsafe extern "C" fn(*mut c_void)>,
     size: bool,
     compact: bool,
+    compact_fields: Option<unsafe extern "C" fn(*mut c_void)>,
     free_immediately: bool,
     wb_protected: bool,
     frozen_shareable: bool,
@@ -280,8 +342,10 @@ where
         Self {
             name,
             mark: false,
+            mark_fields: None,
             size: false,
             compact: false,
+            compact_fields: None,
             free_immediately: false,
             wb_protected: false,
             frozen_shareable: false,
@@ -346,10 +410,10 @@ where
         if self.frozen_shareable {
             flags |= rbimpl_typeddata_flags::RUBY_TYPED_FROZEN_SHAREABLE as VALUE;
         }
-        let dmark = if self.mark {
-            Some(T::extern_mark as _)
-        } else {
-            None
+        let dmark = match self.mark_fields {
+            Some(f) => Some(f),
+            None if self.mark => Some(T::extern_mark as _),
+            None => None,
         };
         let dfree = Some(T::extern_free as _);
         let dsize = if self.size {
@@ -357,10 +421,10 @@ where
         } else {
             None
         };
-        let dcompact = if self.compact {
-            Some(T::extern_compact as _)
-        } else {
-            None
+        let dcompact = match self.compact_fields {
+            Some(f) => Some(f),
+            None if self.compact => Some(T::extern_compact as _),
+            None => None,
         };
         DataType(rb_data_type_t {
             wrap_struct_name: self.name.as_ptr() as _,
@@ -378,6 +442,31 @@ where
     }
 }
 
+impl<T> DataTypeBuilder<T>
+where
+    T: MarkFields,
+{
+    /// Mark fields with [`MarkFields::mark_fields`] as well as
+    /// `<T as DataTypeFunctions>::mark`. Used by the
+    /// [`TypedData`](macro@crate::TypedData) derive.
+    #[doc(hidden)]
+    pub const fn mark_fields(mut self) -> Self {
+        self.mark = true;
+        self.mark_fields = Some(T::extern_mark_fields);
+        self
+    }
+
+    /// Update fields with [`MarkFields::compact_fields`] as well as
+    /// `<T as DataTypeFunctions>::compact`. Used by the
+    /// [`TypedData`](macro@crate::TypedData) derive.
+    #[doc(hidden)]
+    pub const fn compact_fields(mut self) -> Self {
+        self.compact = true;
+        self.compact_fields = Some(T::extern_compact_fields);
+        self
+    }
+}
+
 impl<T> DataTypeBuilder<T>
 where
     T: DataTypeFunctions + Sync,
@@ -516,6 +605,46 @@ where
     fn class_for(ruby: &Ruby, value: &Self) -> RClass {
         Self::class(ruby)
     }
+
+    /// Whether borrows of `Self` from Ruby objects are checked at runtime,
+    /// allowing `&mut Self` to be used as an argument to functions bound with
+    /// the [`method`](crate::method!) and [`function`](crate::function!)
+    /// macros.
+    ///
+    /// When `true`, converting a Ruby object to `&mut Self` while any other
+    /// borrow of the same object is active (or to `&Self` while a mutable
+    /// borrow is active), for example when a block passed to a method calls
+    /// back into the same object, will result in a `RuntimeError`.
+    /// Converting a frozen object to `&mut Self` will result in a
+    /// `FrozenError`.
+    ///
+    /// Borrows are only tracked for the duration of a call from Ruby, such
+    /// as a method call, or a call to a block, `Proc`, or operator defined
+    /// in Rust, so converting to `&Self` or `&mut Self` outside of a call
+    /// from Ruby will result in a `RuntimeError`. [`Obj<Self>`](Obj) does not participate in
+    /// borrow checking, and should not be dereferenced while a mutable borrow
+    /// may be active.
+    ///
+    /// A method suspended in a Fiber, such as an external `Enumerator`,
+    /// keeps its borrows until it resumes and returns, or the Fiber is
+    /// garbage collected.
+    ///
+    /// Types with this set must not mark Ruby values with
+    /// [`DataTypeFunctions::mark`] or [`DataTypeFunctions::compact`], as they
+    /// read the data while a method may hold a mutable reference to it.
+    ///
+    /// Defaults to `false`. Set with the `mutable` attribute of the
+    /// [`wrap`](macro@crate::wrap)/[`TypedData`](macro@crate::TypedData)
+    /// macros.
+    const MUTABLE: bool = false;
+
+    /// The [RBS](crate::rbs) type of [`Obj<Self>`](Obj), `&Self`, and
+    /// `&mut Self` in method signatures.
+    ///
+    /// Defaults to `untyped`. The [`wrap`](macro@crate::wrap) and
+    /// [`TypedData`](macro@crate::TypedData) macros set this to the class
+    /// name.
+    const RBS_TYPE: &'static str = "untyped";
 }
 
 impl<T> TryConvert for &T
@@ -524,7 +653,7 @@ where
 {
     fn try_convert(val: Value) -> Result<Self, Error> {
         let handle = Ruby::get_with(val);
-        unsafe {
+        let data = unsafe {
             RTypedData::from_value(val)
                 .ok_or_else(|| {
                     Error::new(
@@ -536,8 +665,285 @@ where
                         ),
                     )
                 })?
-                .get_unconstrained()
+                .get_unconstrained::<T>()?
+        };
+        if T::MUTABLE {
+            borrow::shared::<T>(&handle, data as *const T as usize)?;
         }
+        Ok(data)
+    }
+}
+
+impl<T> TryConvert for &mut T
+where
+    T: TypedData,
+{
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        const {
+            assert!(
+                T::MUTABLE,
+                "TypedData::MUTABLE must be true to convert to a mutable reference"
+            )
+        };
+        let handle = Ruby::get_with(val);
+        let ptr = RTypedData::from_value(val)
+            .ok_or_else(|| {
+                Error::new(
+                    handle.exception_type_error(),
+                    format!(
+                        "no implicit conversion of {} into {}",
+                        unsafe { val.classname() },
+                        T::class(&handle)
+                    ),
+                )
+            })?
+            .get_ptr::<T>()?;
+        val.check_frozen()?;
+        borrow::exclusive::<T>(&handle, ptr as usize)?;
+        // safe as the borrow tracking above ensures there are no other
+        // references to the data for the duration of the method call
+        Ok(unsafe { &mut *ptr })
+    }
+}
+
+/// Runtime borrow tracking for types where [`TypedData::MUTABLE`] is `true`.
+pub(crate) mod borrow {
+    use std::{
+        cell::{Cell, RefCell},
+        collections::BTreeMap,
+        sync::Mutex,
+    };
+
+    use super::{DataType, DataTypeBuilder, DataTypeFunctions, Obj, TypedData};
+    use crate::{
+        Ruby,
+        class::{Class, RClass},
+        error::Error,
+        value::{Lazy, LazyId},
+    };
+
+    // Borrow state keyed by data pointer. Positive values count shared
+    // borrows, -1 is a mutable borrow. This is global rather than thread
+    // local as another Ruby thread may run while a method is blocked on a
+    // call to Ruby.
+    static BORROWS: Mutex<BTreeMap<usize, isize>> = Mutex::new(BTreeMap::new());
+
+    static FIBER_KEY: LazyId = LazyId::new("__magnus_borrows__");
+
+    thread_local! {
+        // Sequence number of the last borrow taken on this thread. A scope
+        // only has borrows to release if this has moved since it was opened.
+        static SEQ: Cell<u64> = const { Cell::new(0) };
+        // Number of scopes open on the running Fiber. Saved and restored
+        // around calls to Ruby by `protect`, as that may switch Fibers.
+        static DEPTH: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Number of scopes open on the running Fiber.
+    #[inline]
+    pub(crate) fn depth() -> usize {
+        DEPTH.with(Cell::get)
+    }
+
+    /// Restore the count of open scopes after a call to Ruby, which may
+    /// have switched Fibers, or abandoned scopes by unwinding past them.
+    #[inline]
+    pub(crate) fn restore_depth(depth: usize) {
+        DEPTH.with(|d| d.set(depth));
+    }
+
+    fn release(ptrs: impl IntoIterator<Item = usize>) {
+        let mut ptrs = ptrs.into_iter().peekable();
+        if ptrs.peek().is_none() {
+            return;
+        }
+        let mut borrows = BORROWS.lock().unwrap_or_else(|e| e.into_inner());
+        for ptr in ptrs {
+            match borrows.get_mut(&ptr) {
+                Some(-1) | Some(1) => {
+                    borrows.remove(&ptr);
+                }
+                Some(n) => *n -= 1,
+                None => (),
+            }
+        }
+    }
+
+    /// Borrows held by a single Fiber.
+    ///
+    /// Fibers interleave method calls on the same thread, so this is stored
+    /// in Fiber-local storage rather than a thread local. A Fiber that is
+    /// abandoned while suspended in a method never unwinds that method's
+    /// [`Scope`], so its borrows are instead released when the Fiber, and
+    /// this with it, is garbage collected.
+    ///
+    /// This is only created on the first borrow taken by a Fiber.
+    #[derive(Default)]
+    struct FiberBorrows {
+        // (sequence number, data pointer) for borrows held by this Fiber.
+        held: RefCell<Vec<(u64, usize)>>,
+    }
+
+    impl FiberBorrows {
+        fn get(ruby: &Ruby) -> Option<Obj<Self>> {
+            let key = LazyId::get_inner_with(&FIBER_KEY, ruby);
+            ruby.thread_current()
+                .local_aref::<_, Option<Obj<Self>>>(key)
+                .ok()
+                .flatten()
+        }
+
+        fn get_or_create(ruby: &Ruby) -> Option<Obj<Self>> {
+            if let Some(obj) = Self::get(ruby) {
+                return Some(obj);
+            }
+            let key = LazyId::get_inner_with(&FIBER_KEY, ruby);
+            let obj = ruby.obj_wrap(Self::default());
+            ruby.thread_current().local_aset(key, obj).ok()?;
+            Some(obj)
+        }
+    }
+
+    impl Drop for FiberBorrows {
+        fn drop(&mut self) {
+            release(self.held.get_mut().drain(..).map(|(_, ptr)| ptr));
+        }
+    }
+
+    impl DataTypeFunctions for FiberBorrows {}
+
+    unsafe impl TypedData for FiberBorrows {
+        fn class(ruby: &Ruby) -> RClass {
+            static CLASS: Lazy<RClass> = Lazy::new(|ruby| {
+                let class = RClass::new(ruby.class_object()).unwrap();
+                class.undef_default_alloc_func();
+                class
+            });
+            ruby.get_inner(&CLASS)
+        }
+
+        fn data_type() -> &'static DataType {
+            static DATA_TYPE: DataType =
+                DataTypeBuilder::<FiberBorrows>::new(c"magnus fiber borrows")
+                    .free_immediately()
+                    .build();
+            &DATA_TYPE
+        }
+    }
+
+    /// Tracks the borrows taken during a call from Ruby, and releases them
+    /// when dropped at the end of the call.
+    ///
+    /// Opening a scope is free, the Fiber's borrow state is only looked up
+    /// if a borrow was taken while the scope was open.
+    pub(crate) struct Scope {
+        start: u64,
+    }
+
+    impl Scope {
+        #[inline]
+        pub(crate) fn new() -> Self {
+            DEPTH.with(|d| d.set(d.get() + 1));
+            Self {
+                start: SEQ.with(Cell::get),
+            }
+        }
+    }
+
+    impl Drop for Scope {
+        #[inline]
+        fn drop(&mut self) {
+            DEPTH.with(|d| d.set(d.get().saturating_sub(1)));
+            if SEQ.with(Cell::get) != self.start {
+                self.release();
+            }
+        }
+    }
+
+    impl Scope {
+        #[cold]
+        fn release(&self) {
+            let ruby = unsafe { Ruby::get_unchecked() };
+            let Some(fiber) = FiberBorrows::get(&ruby) else {
+                return;
+            };
+            // borrows taken before this scope was opened, on this Fiber,
+            // have a lower sequence number. Any taken by nested scopes have
+            // already been released.
+            let mut released = Vec::new();
+            fiber.held.borrow_mut().retain(|&(seq, ptr)| {
+                if seq > self.start {
+                    released.push(ptr);
+                    false
+                } else {
+                    true
+                }
+            });
+            release(released);
+        }
+    }
+
+    enum Conflict {
+        NotInMethod,
+        Borrowed,
+        MutablyBorrowed,
+    }
+
+    impl Conflict {
+        fn into_error<T>(self, ruby: &Ruby, mutable: bool) -> Error
+        where
+            T: TypedData,
+        {
+            let class = T::class(ruby);
+            let msg = match self {
+                Self::NotInMethod if mutable => format!(
+                    "{} can only be mutably borrowed as an argument to a method",
+                    class
+                ),
+                Self::NotInMethod => {
+                    format!("{} can only be borrowed as an argument to a method", class)
+                }
+                Self::Borrowed => format!("{} is already borrowed", class),
+                Self::MutablyBorrowed => format!("{} is already mutably borrowed", class),
+            };
+            Error::new(ruby.exception_runtime_error(), msg)
+        }
+    }
+
+    // The lock must not be held while calling Ruby, as that may run the
+    // garbage collector, which releases the borrows of dead Fibers.
+    fn try_borrow(ruby: &Ruby, ptr: usize, mutable: bool) -> Result<(), Conflict> {
+        if depth() == 0 {
+            return Err(Conflict::NotInMethod);
+        }
+        let fiber = FiberBorrows::get_or_create(ruby).ok_or(Conflict::NotInMethod)?;
+        let mut borrows = BORROWS.lock().unwrap_or_else(|e| e.into_inner());
+        let count = match (borrows.get(&ptr).copied(), mutable) {
+            (Some(-1), _) => return Err(Conflict::MutablyBorrowed),
+            (Some(_), true) => return Err(Conflict::Borrowed),
+            (state, _) => state.unwrap_or(0),
+        };
+        let seq = SEQ.with(|s| {
+            s.set(s.get() + 1);
+            s.get()
+        });
+        fiber.held.borrow_mut().push((seq, ptr));
+        borrows.insert(ptr, if mutable { -1 } else { count + 1 });
+        Ok(())
+    }
+
+    pub(crate) fn shared<T>(ruby: &Ruby, ptr: usize) -> Result<(), Error>
+    where
+        T: TypedData,
+    {
+        try_borrow(ruby, ptr, false).map_err(|e| e.into_error::<T>(ruby, false))
+    }
+
+    pub(crate) fn exclusive<T>(ruby: &Ruby, ptr: usize) -> Result<(), Error>
+    where
+        T: TypedData,
+    {
+        try_borrow(ruby, ptr, true).map_err(|e| e.into_error::<T>(ruby, true))
     }
 }
 
@@ -1564,3 +1970,568 @@ where
         Ok(clone)
     }
 }
+
+/// Trait for Ruby-compatible `#deconstruct` and `#deconstruct_keys` methods,
+/// allowing wrapped types to be used with Ruby's pattern matching.
+///
+/// This is implemented by the `deconstruct` attribute of
+/// [`#[wrap]`](macro@crate::wrap) and
+/// [`#[derive(TypedData)]`](macro@crate::TypedData) for structs with named
+/// fields. Fields are converted to Ruby with [`IntoValue`] (after being
+/// cloned) only when requested.
+///
+/// See also [`Inspect`], [`Dup`], [`IsEql`], [`typed_data::Cmp`](Cmp), and
+/// [`typed_data::Hash`](Hash).
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, Ruby, function, method, prelude::*, rb_assert, typed_data::Deconstruct};
+///
+/// #[magnus::wrap(class = "Point", free_immediately, deconstruct)]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// impl Point {
+///     fn new(x: i64, y: i64) -> Self {
+///         Self { x, y }
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Point", ruby.class_object())?;
+///     class.define_singleton_method("new", function!(Point::new, 2))?;
+///     class.define_method("deconstruct", method!(Point::deconstruct, 0))?;
+///     class.define_method("deconstruct_keys", method!(Point::deconstruct_keys, 1))?;
+///
+///     rb_assert!(
+///         ruby,
+///         r#"
+///         case Point.new(1, 2)
+///         in {x: 0} then false
+///         in {x:, y:} then x == 1 && y == 2
+///         end
+///         "#
+///     );
+///     rb_assert!(ruby, "Point.new(1, 2) => [a, b]; a == 1 && b == 2");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub trait Deconstruct: Sized {
+    /// The names of the fields, in order.
+    const FIELDS: &'static [&'static str];
+
+    /// Returns the field at `index` in [`FIELDS`](Deconstruct::FIELDS),
+    /// converted to a Ruby value.
+    fn field(&self, ruby: &Ruby, index: usize) -> Value;
+
+    /// Returns all fields as an Array, for use as a Ruby `#deconstruct`
+    /// method.
+    fn deconstruct(ruby: &Ruby, rb_self: &Self) -> RArray {
+        ruby.ary_from_iter((0..Self::FIELDS.len()).map(|i| rb_self.field(ruby, i)))
+    }
+
+    /// Returns the fields named by the Symbols in `keys` as a Hash, or all
+    /// fields if `keys` is `nil`, for use as a Ruby `#deconstruct_keys`
+    /// method.
+    fn deconstruct_keys(ruby: &Ruby, rb_self: &Self, keys: Option<RArray>) -> Result<RHash, Error> {
+        let hash = ruby.hash_new();
+        let keys = match keys {
+            Some(keys) => keys,
+            None => {
+                for (i, name) in Self::FIELDS.iter().enumerate() {
+                    hash.aset(ruby.to_symbol(name), rb_self.field(ruby, i))?;
+                }
+                return Ok(hash);
+            }
+        };
+        for i in 0..keys.len() {
+            let key: Value = keys.entry(i as isize)?;
+            let Some(sym) = Symbol::from_value(key) else {
+                continue;
+            };
+            let name = sym.name()?;
+            if let Some(index) = Self::FIELDS.iter().position(|f| *f == name) {
+                hash.aset(key, rb_self.field(ruby, index))?;
+            }
+        }
+        Ok(hash)
+    }
+}
+
+/// Trait for wrapped types exposing their fields to Ruby with reader
+/// methods.
+///
+/// This is implemented by the `attr_readers` attribute of
+/// [`#[wrap]`](macro@crate::wrap) and
+/// [`#[derive(TypedData)]`](macro@crate::TypedData) for structs with named
+/// fields. Fields are converted to Ruby with [`IntoValue`] (after being
+/// cloned) each time they are read.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, Ruby, function, rb_assert, typed_data::AttrReaders};
+///
+/// #[magnus::wrap(class = "Point", free_immediately, attr_readers)]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+///     #[magnus(skip_attr_reader)]
+///     cache: Option<f64>,
+/// }
+///
+/// impl Point {
+///     fn new(x: i64, y: i64) -> Self {
+///         Self { x, y, cache: None }
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Point", ruby.class_object())?;
+///     class.define_singleton_method("new", function!(Point::new, 2))?;
+///     Point::define_attr_readers(class)?;
+///
+///     rb_assert!(ruby, "Point.new(1, 2).x == 1");
+///     rb_assert!(ruby, "Point.new(1, 2).y == 2");
+///     rb_assert!(ruby, "!Point.new(1, 2).respond_to?(:cache)");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub trait AttrReaders: TypedData {
+    /// The names of the fields with reader methods, in order.
+    const ATTRS: &'static [&'static str];
+
+    /// Returns the field at `index` in [`ATTRS`](AttrReaders::ATTRS),
+    /// converted to a Ruby value.
+    fn attr(&self, ruby: &Ruby, index: usize) -> Value;
+
+    /// Define a reader method on `class` for each field in
+    /// [`ATTRS`](AttrReaders::ATTRS).
+    fn define_attr_readers(class: RClass) -> Result<(), Error>
+    where
+        Self: 'static,
+    {
+        for (index, name) in Self::ATTRS.iter().enumerate() {
+            class.define_method_fn(*name, 0, move |ruby, rb_self, _args, _block| {
+                let rb_self = <&Self>::try_convert(rb_self)?;
+                Ok(rb_self.attr(ruby, index))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Define Ruby protocol methods on `class` from the standard Rust traits
+/// implemented by the wrapped type `T`.
+///
+/// The methods defined are:
+///
+/// | Rust trait                           | Ruby methods                       |
+/// |--------------------------------------|------------------------------------|
+/// | [`PartialEq`]                        | `==`                               |
+/// | [`Eq`] + [`Hash`](std::hash::Hash)   | `eql?`, `hash`                     |
+/// | [`PartialOrd`]                       | `<=>`, and includes `Comparable`   |
+/// | [`Display`](std::fmt::Display)       | `to_s`                             |
+/// | [`Debug`](std::fmt::Debug)           | `inspect`                          |
+/// | [`Clone`]                            | `dup`, `clone`                     |
+/// | [`IntoIterator`] for `&T`            | `each`, and includes `Enumerable`  |
+///
+/// The `IntoIterator` implementation for `&T` must iterate over references
+/// to a type implementing [`Clone`] and [`IntoValue`], each item is cloned
+/// before being yielded. Without a block `each` returns an `Enumerator`.
+///
+/// As Rust can only check which traits are implemented by a concrete type,
+/// this is a macro taking the class and the type, rather than a generic
+/// function. It returns `Result<(), Error>`.
+///
+/// See also [`typed_data::Cmp`](Cmp), [`Dup`], [`typed_data::Hash`](Hash),
+/// [`Inspect`], and [`IsEql`] for defining these methods individually.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt;
+///
+/// use magnus::{Error, Ruby, define_rust_protocols, function, prelude::*, rb_assert};
+///
+/// #[magnus::wrap(class = "Version", free_immediately)]
+/// #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Hash)]
+/// struct Version(Vec<u32>);
+///
+/// impl Version {
+///     fn new(s: String) -> Self {
+///         Self(s.split('.').filter_map(|p| p.parse().ok()).collect())
+///     }
+/// }
+///
+/// impl fmt::Display for Version {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         let parts = self.0.iter().map(u32::to_string).collect::<Vec<_>>();
+///         write!(f, "{}", parts.join("."))
+///     }
+/// }
+///
+/// impl<'a> IntoIterator for &'a Version {
+///     type Item = &'a u32;
+///     type IntoIter = std::slice::Iter<'a, u32>;
+///
+///     fn into_iter(self) -> Self::IntoIter {
+///         self.0.iter()
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Version", ruby.class_object())?;
+///     class.define_singleton_method("new", function!(Version::new, 1))?;
+///     define_rust_protocols!(class, Version)?;
+///
+///     rb_assert!(ruby, r#"Version.new("1.2") < Version.new("1.10")"#);
+///     rb_assert!(ruby, r#"Version.new("1.2") == Version.new("1.2")"#);
+///     rb_assert!(ruby, r#"Version.new("1.2").to_s == "1.2""#);
+///     rb_assert!(ruby, r#"Version.new("1.2.3").map { |p| p * 2 } == [2, 4, 6]"#);
+///     rb_assert!(ruby, r#"{Version.new("1") => true}[Version.new("1")]"#);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[macro_export]
+macro_rules! define_rust_protocols {
+    ($class:expr_2021, $ty:ty) => {{
+        #[allow(unused_imports)]
+        use $crate::typed_data::protocols::{
+            CloneProtocol, CmpProtocol, DebugProtocol, DisplayProtocol, EqProtocol, HashProtocol,
+            IterProtocol, NoCloneProtocol, NoCmpProtocol, NoDebugProtocol, NoDisplayProtocol,
+            NoEqProtocol, NoHashProtocol, NoIterProtocol,
+        };
+        let class: $crate::RClass = $class;
+        let probe = &&$crate::typed_data::protocols::Probe::<$ty>::new();
+        (|| {
+            probe.define_eq(class)?;
+            probe.define_hash(class)?;
+            probe.define_cmp(class)?;
+            probe.define_to_s(class)?;
+            probe.define_inspect(class)?;
+            probe.define_dup(class)?;
+            probe.define_each(class)?;
+            Ok::<(), $crate::Error>(())
+        })()
+    }};
+}
+
+/// Implementation details of the
+/// [`define_rust_protocols`](crate::define_rust_protocols!) macro.
+///
+/// Each protocol is a pair of traits with the same method, one implemented
+/// for `&Probe<T>` when `T` implements the required Rust traits, and a no-op
+/// fallback implemented for `Probe<T>`. Calling the method on a
+/// `&&Probe<T>` selects the first if it applies.
+#[doc(hidden)]
+pub mod protocols {
+    use std::{ffi::c_int, fmt, marker::PhantomData};
+
+    use super::{Cmp, Dup, Hash, Inspect, IsEql, Obj, TypedData};
+    use crate::{
+        Ruby,
+        class::RClass,
+        error::Error,
+        into_value::IntoValue,
+        method::{Method0, Method1, MethodCAry, RubyMethod0},
+        module::Module,
+        try_convert::TryConvert,
+        value::{ReprValue, Value},
+    };
+
+    pub struct Probe<T>(PhantomData<T>);
+
+    impl<T> Probe<T> {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    fn eq<T>(rb_self: &T, other: Value) -> bool
+    where
+        T: PartialEq + TypedData,
+    {
+        <&T>::try_convert(other)
+            .map(|o| rb_self == o)
+            .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn eq_cfn<T>(rb_self: Value, other: Value) -> Value
+    where
+        T: PartialEq + TypedData,
+    {
+        unsafe { eq::<T>.call_handle_error(rb_self, other) }
+    }
+
+    pub trait EqProtocol {
+        fn define_eq(&self, class: RClass) -> Result<(), Error>;
+    }
+
+    impl<T> EqProtocol for &Probe<T>
+    where
+        T: PartialEq + TypedData,
+    {
+        fn define_eq(&self, class: RClass) -> Result<(), Error> {
+            class.define_method(
+                "==",
+                eq_cfn::<T> as unsafe extern "C" fn(Value, Value) -> Value,
+            )
+        }
+    }
+
+    pub trait NoEqProtocol {
+        fn define_eq(&self, _class: RClass) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl<T> NoEqProtocol for Probe<T> {}
+
+    unsafe extern "C" fn eql_cfn<T>(rb_self: Value, other: Value) -> Value
+    where
+        T: Eq + std::hash::Hash + TypedData,
+    {
+        unsafe { <T as IsEql>::is_eql.call_handle_error(rb_self, other) }
+    }
+
+    unsafe extern "C" fn hash_cfn<T>(rb_self: Value) -> Value
+    where
+        T: Eq + std::hash::Hash + TypedData,
+    {
+        unsafe { <T as Hash>::hash.call_handle_error(rb_self) }
+    }
+
+    pub trait HashProtocol {
+        fn define_hash(&self, class: RClass) -> Result<(), Error>;
+    }
+
+    impl<T> HashProtocol for &Probe<T>
+    where
+        T: Eq + std::hash::Hash + TypedData,
+    {
+        fn define_hash(&self, class: RClass) -> Result<(), Error> {
+            class.define_method(
+                "eql?",
+                eql_cfn::<T> as unsafe extern "C" fn(Value, Value) -> Value,
+            )?;
+            class.define_method(
+                "hash",
+                hash_cfn::<T> as unsafe extern "C" fn(Value) -> Value,
+            )
+        }
+    }
+
+    pub trait NoHashProtocol {
+        fn define_hash(&self, _class: RClass) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl<T> NoHashProtocol for Probe<T> {}
+
+    unsafe extern "C" fn cmp_cfn<T>(rb_self: Value, other: Value) -> Value
+    where
+        T: PartialOrd + TypedData,
+    {
+        unsafe { <T as Cmp>::cmp.call_handle_error(rb_self, other) }
+    }
+
+    pub trait CmpProtocol {
+        fn define_cmp(&self, class: RClass) -> Result<(), Error>;
+    }
+
+    impl<T> CmpProtocol for &Probe<T>
+    where
+        T: PartialOrd + TypedData,
+    {
+        fn define_cmp(&self, class: RClass) -> Result<(), Error> {
+            class.define_method(
+                "<=>",
+                cmp_cfn::<T> as unsafe extern "C" fn(Value, Value) -> Value,
+            )?;
+            class.include_module(Ruby::get_with(class).module_comparable())
+        }
+    }
+
+    pub trait NoCmpProtocol {
+        fn define_cmp(&self, _class: RClass) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl<T> NoCmpProtocol for Probe<T> {}
+
+    fn to_s<T>(rb_self: &T) -> String
+    where
+        T: fmt::Display + TypedData,
+    {
+        rb_self.to_string()
+    }
+
+    unsafe extern "C" fn to_s_cfn<T>(rb_self: Value) -> Value
+    where
+        T: fmt::Display + TypedData,
+    {
+        unsafe { to_s::<T>.call_handle_error(rb_self) }
+    }
+
+    pub trait DisplayProtocol {
+        fn define_to_s(&self, class: RClass) -> Result<(), Error>;
+    }
+
+    impl<T> DisplayProtocol for &Probe<T>
+    where
+        T: fmt::Display + TypedData,
+    {
+        fn define_to_s(&self, class: RClass) -> Result<(), Error> {
+            class.define_method(
+                "to_s",
+                to_s_cfn::<T> as unsafe extern "C" fn(Value) -> Value,
+            )
+        }
+    }
+
+    pub trait NoDisplayProtocol {
+        fn define_to_s(&self, _class: RClass) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl<T> NoDisplayProtocol for Probe<T> {}
+
+    unsafe extern "C" fn inspect_cfn<T>(rb_self: Value) -> Value
+    where
+        T: fmt::Debug + TypedData,
+    {
+        unsafe { <T as Inspect>::inspect.call_handle_error(rb_self) }
+    }
+
+    pub trait DebugProtocol {
+        fn define_inspect(&self, class: RClass) -> Result<(), Error>;
+    }
+
+    impl<T> DebugProtocol for &Probe<T>
+    where
+        T: fmt::Debug + TypedData,
+    {
+        fn define_inspect(&self, class: RClass) -> Result<(), Error> {
+            class.define_method(
+                "inspect",
+                inspect_cfn::<T> as unsafe extern "C" fn(Value) -> Value,
+            )
+        }
+    }
+
+    pub trait NoDebugProtocol {
+        fn define_inspect(&self, _class: RClass) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl<T> NoDebugProtocol for Probe<T> {}
+
+    unsafe extern "C" fn dup_cfn<T>(rb_self: Value) -> Value
+    where
+        T: Clone + TypedData,
+    {
+        unsafe { <T as Dup>::dup.call_handle_error(rb_self) }
+    }
+
+    unsafe extern "C" fn clone_cfn<T>(argc: c_int, argv: *const Value, rb_self: Value) -> Value
+    where
+        T: Clone + TypedData,
+    {
+        unsafe { <T as Dup>::clone.call_handle_error(argc, argv, rb_self) }
+    }
+
+    pub trait CloneProtocol {
+        fn define_dup(&self, class: RClass) -> Result<(), Error>;
+    }
+
+    impl<T> CloneProtocol for &Probe<T>
+    where
+        T: Clone + TypedData,
+    {
+        fn define_dup(&self, class: RClass) -> Result<(), Error> {
+            class.define_method("dup", dup_cfn::<T> as unsafe extern "C" fn(Value) -> Value)?;
+            class.define_method(
+                "clone",
+                clone_cfn::<T> as unsafe extern "C" fn(c_int, *const Value, Value) -> Value,
+            )
+        }
+    }
+
+    pub trait NoCloneProtocol {
+        fn define_dup(&self, _class: RClass) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl<T> NoCloneProtocol for Probe<T> {}
+
+    fn each<T, U>(ruby: &Ruby, rb_self: Obj<T>) -> Result<Value, Error>
+    where
+        T: TypedData,
+        for<'a> &'a T: IntoIterator<Item = &'a U>,
+        U: Clone + IntoValue,
+    {
+        if !ruby.block_given() {
+            return Ok(rb_self.enumeratorize("each", ()).as_value());
+        }
+        // converting registers a shared borrow for `mutable` types, held
+        // until the method returns, so the block can't mutably borrow
+        // `rb_self` while it's being iterated
+        let this = <&T>::try_convert(rb_self.as_value())?;
+        for item in this {
+            ruby.yield_value::<_, Value>(item.clone())?;
+        }
+        Ok(rb_self.as_value())
+    }
+
+    unsafe extern "C" fn each_cfn<T, U>(rb_self: Value) -> Value
+    where
+        T: TypedData,
+        for<'a> &'a T: IntoIterator<Item = &'a U>,
+        U: Clone + IntoValue,
+    {
+        unsafe { each::<T, U>.call_handle_error(rb_self) }
+    }
+
+    pub trait IterProtocol {
+        fn define_each(&self, class: RClass) -> Result<(), Error>;
+    }
+
+    impl<T, U> IterProtocol for &Probe<T>
+    where
+        T: TypedData,
+        for<'a> &'a T: IntoIterator<Item = &'a U>,
+        U: Clone + IntoValue,
+    {
+        fn define_each(&self, class: RClass) -> Result<(), Error> {
+            class.define_method(
+                "each",
+                each_cfn::<T, U> as unsafe extern "C" fn(Value) -> Value,
+            )?;
+            class.include_module(Ruby::get_with(class).module_enumerable())
+        }
+    }
+
+    pub trait NoIterProtocol {
+        fn define_each(&self, _class: RClass) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl<T> NoIterProtocol for Probe<T> {}
+}
@@ -23,9 +23,9 @@ use rb_sys::{
     ID, RBasic, VALUE, rb_any_to_s, rb_block_call_kw, rb_check_funcall_kw, rb_check_id,
     rb_check_id_cstr, rb_check_symbol_cstr, rb_enumeratorize_with_size_kw, rb_eql, rb_equal,
     rb_funcall_with_block_kw, rb_funcallv_kw, rb_funcallv_public_kw, rb_gc_register_address,
-    rb_gc_unregister_address, rb_hash, rb_id2name, rb_id2sym, rb_inspect, rb_intern3, rb_ll2inum,
-    rb_obj_as_string, rb_obj_classname, rb_obj_freeze, rb_obj_is_kind_of, rb_obj_respond_to,
-    rb_sym2id, rb_ull2inum, ruby_fl_type, ruby_special_consts, ruby_value_type,
+    rb_gc_unregister_address, rb_hash, rb_id2name, rb_id2sym, rb_inspect, rb_intern3, rb_ivar_set,
+    rb_ll2inum, rb_obj_as_string, rb_obj_classname, rb_obj_freeze, rb_obj_is_kind_of,
+    rb_obj_respond_to, rb_sym2id, rb_ull2inum, ruby_fl_type, ruby_special_consts, ruby_value_type,
 };
 
 // These don't seem to appear consistently in bindgen output, not sure if they
@@ -39,7 +39,7 @@ use crate::{
     block::Proc,
     class::RClass,
     encoding::EncodingCapable,
-    enumerator::Enumerator,
+    enumerator::{self, Enumerator, EnumeratorSize},
     error::{Error, protect},
     gc,
     integer::{Integer, IntegerType},
@@ -1679,6 +1679,50 @@ pub trait ReprValue: private::ReprValue {
             ))
         }
     }
+
+    /// Generate an [`Enumerator`] from `method` on `self`, passing `args` to
+    /// `method`, with `size` returned by the Enumerator's `size` method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{EnumeratorSize, Error, Ruby, prelude::*, rb_assert};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("foo bar baz");
+    ///     let enumerator = s.enumeratorize_with_size("each_char", (), EnumeratorSize::Fixed(11));
+    ///     rb_assert!(ruby, "enumerator.size == 11", enumerator);
+    ///     rb_assert!(ruby, "enumerator.each_slice(4).size == 3", enumerator);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn enumeratorize_with_size<M, A>(self, method: M, args: A, size: EnumeratorSize) -> Enumerator
+    where
+        M: IntoSymbol,
+        A: ArgList,
+    {
+        let handle = Ruby::get_with(self);
+        let kw_splat = kw_splat(&args);
+        let args = args.into_arg_list_with(&handle);
+        let slice = args.as_ref();
+        let size = size.into_value_with(&handle);
+        unsafe {
+            let enumerator = Enumerator::from_rb_value_unchecked(rb_enumeratorize_with_size_kw(
+                self.as_rb_value(),
+                method.into_symbol_with(&handle).as_rb_value(),
+                slice.len() as c_int,
+                slice.as_ptr() as *const VALUE,
+                Some(enumerator::enumerator_size),
+                kw_splat as c_int,
+            ));
+            // a newly created Enumerator won't be frozen, so this can't fail
+            let id = enumerator::SIZE_IVAR.into_id_with(&handle);
+            rb_ivar_set(enumerator.as_rb_value(), id.as_rb_id(), size.as_rb_value());
+            enumerator
+        }
+    }
 }
 
 unsafe impl private::ReprValue for Value {}
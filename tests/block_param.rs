@@ -0,0 +1,48 @@
+use magnus::{Error, Ruby, block::Proc, function, method, prelude::*, rb_assert};
+
+fn times_map(n: usize, block: Proc) -> Result<Vec<i64>, Error> {
+    (0..n).map(|i| block.call((i,))).collect()
+}
+
+fn each_char_or_chars(ruby: &Ruby, rb_self: String, block: Option<Proc>) -> Result<usize, Error> {
+    match block {
+        Some(block) => {
+            for c in rb_self.chars() {
+                let _: magnus::Value = block.call((c,))?;
+            }
+            Ok(rb_self.chars().count())
+        }
+        None => Err(Error::new(ruby.exception_arg_error(), "no block")),
+    }
+}
+
+#[test]
+fn it_passes_the_block_as_a_param() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("times_map", function!(times_map, 1));
+    ruby.class_string()
+        .define_method("rs_each_char", method!(each_char_or_chars, 0))
+        .unwrap();
+
+    rb_assert!(ruby, "times_map(3) { |i| i * 2 } == [0, 2, 4]");
+    rb_assert!(
+        ruby,
+        r#"(times_map(3) rescue $!).message == "no block given (yield)""#
+    );
+    rb_assert!(ruby, "(times_map(3) rescue $!).class == LocalJumpError");
+
+    rb_assert!(
+        ruby,
+        r#"a = []; "ab".rs_each_char { |c| a << c } == 2 && a == ["a", "b"]"#
+    );
+    rb_assert!(
+        ruby,
+        r#"("ab".rs_each_char rescue $!).message == "no block""#
+    );
+
+    assert_eq!(
+        magnus::function_signature!(times_map, 1).to_string(),
+        "(Integer) { (*untyped) -> untyped } -> Array[Integer]"
+    );
+}
@@ -0,0 +1,80 @@
+use magnus::{Error, Ruby, Symbol, Value, error::Caught, function, prelude::*, rb_assert};
+
+fn first_negative(ruby: &Ruby, values: Vec<i64>) -> Result<Option<i64>, Error> {
+    for i in values {
+        if i < 0 {
+            return Err(ruby.throw(ruby.to_symbol("negative"), i));
+        }
+    }
+    Ok(None)
+}
+
+fn catch_in_rust(ruby: &Ruby, code: String) -> Result<(Symbol, Value), Error> {
+    match ruby.catch(ruby.to_symbol("stop"), |ruby| ruby.eval::<Value>(&code))? {
+        Caught::Returned(val) => Ok((ruby.to_symbol("returned"), val)),
+        Caught::Thrown(val) => Ok((ruby.to_symbol("thrown"), val)),
+    }
+}
+
+#[test]
+fn it_catches_and_throws() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("first_negative", function!(first_negative, 1));
+    ruby.define_global_function("catch_in_rust", function!(catch_in_rust, 1));
+
+    // throw from Rust to a Ruby catch
+    rb_assert!(
+        ruby,
+        "catch(:negative) { first_negative([1, -2, -3]) } == -2"
+    );
+    rb_assert!(ruby, "catch(:negative) { first_negative([1, 2]) }.nil?");
+    rb_assert!(
+        ruby,
+        "(first_negative([-1]) rescue $!).is_a?(UncaughtThrowError)"
+    );
+
+    // catch in Rust of a throw from Ruby
+    rb_assert!(ruby, "catch_in_rust('throw :stop, 1') == [:thrown, 1]");
+    rb_assert!(ruby, "catch_in_rust('throw :stop') == [:thrown, nil]");
+    rb_assert!(ruby, "catch_in_rust('2') == [:returned, 2]");
+    rb_assert!(
+        ruby,
+        "catch(:outer) { catch_in_rust('throw :outer, 3') } == 3"
+    );
+    rb_assert!(
+        ruby,
+        "(catch_in_rust('raise \"bang\"') rescue $!).message == 'bang'"
+    );
+
+    // catch and throw both in Rust
+    let tag: Value = ruby.eval("Object.new").unwrap();
+    let res = ruby
+        .catch(tag, |ruby| -> Result<i64, Error> {
+            Err(ruby.throw(tag, 4))
+        })
+        .unwrap();
+    let Caught::Thrown(val) = res else {
+        panic!("expected throw")
+    };
+    assert_eq!(i64::try_convert(val).unwrap(), 4);
+
+    // throws to other tags pass through
+    let outer = ruby.to_symbol("outer");
+    let res = ruby
+        .catch(outer, |ruby| {
+            let inner = ruby.catch(ruby.to_symbol("inner"), |ruby| {
+                ruby.eval::<Value>("throw :outer, 5")
+            });
+            assert!(inner.is_err());
+            inner.map(|_| 0)
+        })
+        .unwrap();
+    let Caught::Thrown(val) = res else {
+        panic!("expected throw")
+    };
+    assert_eq!(i64::try_convert(val).unwrap(), 5);
+
+    let res = ruby.catch(outer, |_ruby| Ok(6)).unwrap();
+    assert!(matches!(res, Caught::Returned(6)));
+}
@@ -0,0 +1,85 @@
+use std::cell::Cell;
+
+use magnus::{
+    Error, Ruby, TypedData, function, method, prelude::*, rb_assert, typed_data::Deconstruct,
+};
+
+#[derive(TypedData)]
+#[magnus(class = "Account", free_immediately, deconstruct)]
+struct Account {
+    name: String,
+    balance: i64,
+    #[magnus(skip_deconstruct)]
+    reads: Cell<usize>,
+}
+
+impl magnus::DataTypeFunctions for Account {}
+
+impl Account {
+    fn new(name: String, balance: i64) -> Self {
+        Self {
+            name,
+            balance,
+            reads: Cell::new(0),
+        }
+    }
+
+    fn keys(
+        ruby: &Ruby,
+        rb_self: &Self,
+        keys: Option<magnus::RArray>,
+    ) -> Result<magnus::RHash, Error> {
+        rb_self.reads.set(rb_self.reads.get() + 1);
+        Self::deconstruct_keys(ruby, rb_self, keys)
+    }
+
+    fn reads(&self) -> usize {
+        self.reads.get()
+    }
+}
+
+#[test]
+fn it_supports_pattern_matching() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class = ruby.define_class("Account", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Account::new, 2))
+        .unwrap();
+    class
+        .define_method("deconstruct", method!(Account::deconstruct, 0))
+        .unwrap();
+    class
+        .define_method("deconstruct_keys", method!(Account::keys, 1))
+        .unwrap();
+    class
+        .define_method("reads", method!(Account::reads, 0))
+        .unwrap();
+
+    assert_eq!(Account::FIELDS, &["name", "balance"]);
+
+    rb_assert!(ruby, r#"Account.new("a", 1).deconstruct == ["a", 1]"#);
+    rb_assert!(
+        ruby,
+        r#"Account.new("a", 1).deconstruct_keys(nil) == {name: "a", balance: 1}"#
+    );
+    rb_assert!(
+        ruby,
+        r#"Account.new("a", 1).deconstruct_keys([:balance, :reads, "name"]) == {balance: 1}"#
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        acct = Account.new("alice", 10)
+        res = case acct
+        in {balance: 0} then :empty
+        in {name: String => name, balance: Integer => balance} then [name, balance]
+        end
+        res == ["alice", 10] && acct.reads == 2
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"Account.new("b", 2) => [name, balance]; name == "b" && balance == 2"#
+    );
+}
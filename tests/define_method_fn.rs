@@ -0,0 +1,85 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use magnus::{TryConvert, Value, prelude::*, rb_assert};
+
+struct Config {
+    prefix: String,
+}
+
+#[test]
+fn it_defines_methods_from_closures() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class = ruby.define_class("Greeter", ruby.class_object()).unwrap();
+    let config = Arc::new(Config {
+        prefix: String::from("Hello"),
+    });
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    for (name, suffix) in [("greet", "!"), ("greet_politely", ", please.")] {
+        let config = config.clone();
+        let calls = calls.clone();
+        class
+            .define_method_fn(name, 1, move |_ruby, _rb_self, args, _block| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                let who = String::try_convert(args[0])?;
+                Ok(format!("{}, {}{}", config.prefix, who, suffix))
+            })
+            .unwrap();
+    }
+    class
+        .define_method_fn(
+            "itself_and_args",
+            -1,
+            |ruby, rb_self: Value, args, _block| {
+                let ary = ruby.ary_from_iter(args.iter().copied());
+                Ok((rb_self, ary))
+            },
+        )
+        .unwrap();
+
+    class
+        .define_method_fn(
+            "with_block",
+            1,
+            |_ruby, _rb_self, args, block| match block {
+                Some(block) => block.call::<_, Value>((args[0],)),
+                None => Ok(args[0]),
+            },
+        )
+        .unwrap();
+
+    rb_assert!(ruby, r#"Greeter.new.greet("world") == "Hello, world!""#);
+    rb_assert!(ruby, "Greeter.new.with_block(1) { |i| i + 1 } == 2");
+    rb_assert!(ruby, "Greeter.new.with_block(1) == 1");
+    rb_assert!(
+        ruby,
+        r#"Greeter.new.greet_politely("world") == "Hello, world, please.""#
+    );
+    rb_assert!(
+        ruby,
+        "g = Greeter.new; g.itself_and_args(1, 2) == [g, [1, 2]]"
+    );
+    rb_assert!(
+        ruby,
+        "(Greeter.new.greet rescue $!).message == 'wrong number of arguments (given 0, expected 1)'"
+    );
+    rb_assert!(ruby, "(Greeter.new.greet(1) rescue $!).is_a?(TypeError)");
+    rb_assert!(ruby, "Greeter.instance_method(:greet).arity == 1");
+    rb_assert!(
+        ruby,
+        "Greeter.instance_method(:itself_and_args).arity == -1"
+    );
+    assert!(
+        class
+            .define_method_fn("invalid", -2, |_ruby, rb_self: Value, _args, _block| {
+                Ok(rb_self)
+            })
+            .unwrap_err()
+            .is_kind_of(ruby.exception_arg_error())
+    );
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+}
@@ -0,0 +1,149 @@
+use std::cell::RefCell;
+
+use magnus::{
+    Error, Ruby, Value, block::Proc, function, prelude::*, rb_assert, rb_sys::AsRawValue,
+};
+
+thread_local! {
+    static LOG: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(s: &'static str) {
+    LOG.with(|l| l.borrow_mut().push(s));
+}
+
+fn take_log() -> Vec<&'static str> {
+    LOG.with(|l| l.borrow_mut().drain(..).collect())
+}
+
+fn with_cleanup(ruby: &Ruby, block: Proc) -> Result<Value, Error> {
+    ruby.ensure(
+        |_ruby| block.call(()),
+        |_ruby| {
+            log("cleanup");
+            Ok(())
+        },
+    )
+}
+
+fn rescue_io(ruby: &Ruby, block: Proc) -> Result<Value, Error> {
+    ruby.rescue(
+        &[ruby.exception_io_error(), ruby.exception_key_error()],
+        |_ruby| block.call(()),
+        |ruby, e| {
+            Ok(ruby
+                .str_new(&format!("rescued {}", e.message()?))
+                .as_value())
+        },
+    )
+}
+
+fn rescue_default(ruby: &Ruby, block: Proc) -> Result<Value, Error> {
+    ruby.rescue(
+        &[],
+        |_ruby| block.call(()),
+        |ruby, _e| Ok(ruby.to_symbol("rescued").as_value()),
+    )
+}
+
+fn rescue_errinfo(ruby: &Ruby, block: Proc) -> Result<Value, Error> {
+    ruby.rescue(&[], |_ruby| block.call(()), |ruby, _e| ruby.eval("$!"))
+}
+
+fn rescue_raw(ruby: &Ruby) -> Result<String, Error> {
+    ruby.rescue(
+        &[ruby.exception_runtime_error()],
+        |ruby| {
+            let e = ruby
+                .exception_runtime_error()
+                .new_instance((ruby.str_new("raw"),))?;
+            unsafe { rb_sys::rb_exc_raise(e.as_raw()) }
+        },
+        |_ruby, e| Ok(format!("rescued {}", e.message()?)),
+    )
+}
+
+#[test]
+fn it_ensures_and_rescues() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("with_cleanup", function!(with_cleanup, 0));
+    ruby.define_global_function("rescue_io", function!(rescue_io, 0));
+    ruby.define_global_function("rescue_default", function!(rescue_default, 0));
+    ruby.define_global_function("rescue_errinfo", function!(rescue_errinfo, 0));
+
+    // normal return
+    rb_assert!(ruby, "with_cleanup { 1 } == 1");
+    assert_eq!(take_log(), vec!["cleanup"]);
+
+    // exception
+    rb_assert!(
+        ruby,
+        "(with_cleanup { raise 'bang' } rescue $!).message == 'bang'"
+    );
+    assert_eq!(take_log(), vec!["cleanup"]);
+
+    // throw
+    rb_assert!(
+        ruby,
+        "catch(:done) { with_cleanup { throw :done, 2 }; 3 } == 2"
+    );
+    assert_eq!(take_log(), vec!["cleanup"]);
+
+    // break
+    rb_assert!(ruby, "with_cleanup { break 10; 11 } == 10");
+    assert_eq!(take_log(), vec!["cleanup"]);
+
+    // next
+    rb_assert!(ruby, "with_cleanup { next 4; 5 } == 4");
+    assert_eq!(take_log(), vec!["cleanup"]);
+
+    // return from a method
+    rb_assert!(
+        ruby,
+        "def returns_early; with_cleanup { return 6 }; 7; end; returns_early == 6"
+    );
+    assert_eq!(take_log(), vec!["cleanup"]);
+
+    // an error in cleanup replaces the result
+    let res: Result<i64, Error> = ruby.ensure(
+        |_ruby| Ok(1),
+        |ruby| Err(Error::new(ruby.exception_runtime_error(), "cleanup failed")),
+    );
+    assert!(res.unwrap_err().to_string().contains("cleanup failed"));
+
+    // panics run cleanup then carry on unwinding
+    let ary = ruby.ary_new();
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        ruby.ensure::<_, _, ()>(|_ruby| panic!("oh no"), |_ruby| ary.push(1))
+    }));
+    assert!(res.is_err());
+    assert_eq!(ary.len(), 1);
+
+    rb_assert!(ruby, "rescue_io { 1 } == 1");
+    rb_assert!(
+        ruby,
+        "rescue_io { raise IOError, 'disk' } == 'rescued disk'"
+    );
+    rb_assert!(ruby, "rescue_io { raise EOFError, 'eof' } == 'rescued eof'");
+    rb_assert!(
+        ruby,
+        "rescue_io { {}.fetch(:a) } == 'rescued key not found: :a'"
+    );
+    rb_assert!(
+        ruby,
+        "(rescue_io { raise ArgumentError } rescue $!).is_a?(ArgumentError)"
+    );
+    rb_assert!(ruby, "catch(:done) { rescue_io { throw :done, 8 } } == 8");
+
+    rb_assert!(ruby, "rescue_default { raise 'bang' } == :rescued");
+    rb_assert!(
+        ruby,
+        "e = RuntimeError.new('bang'); rescue_errinfo { raise e }.equal?(e)"
+    );
+    assert_eq!(rescue_raw(&ruby).unwrap(), "rescued raw");
+    rb_assert!(
+        ruby,
+        "begin; rescue_default { raise Interrupt }; rescue Exception => e; e.is_a?(Interrupt); end"
+    );
+}
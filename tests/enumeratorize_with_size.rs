@@ -0,0 +1,50 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use magnus::{EnumeratorSize, Error, RArray, Value, prelude::*, rb_assert};
+
+#[test]
+fn it_makes_a_sized_enumerator() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let ary: RArray = ruby.eval("[1, 2, 3, 4, 5]").unwrap();
+
+    let enumerator = ary.enumeratorize("each", ());
+    rb_assert!(ruby, "enumerator.size.nil?", enumerator);
+
+    let enumerator = ary.enumeratorize_with_size("each", (), EnumeratorSize::Fixed(5));
+    rb_assert!(ruby, "enumerator.size == 5", enumerator);
+    rb_assert!(ruby, "enumerator.each_slice(2).size == 3", enumerator);
+    rb_assert!(ruby, "enumerator.to_a == [1, 2, 3, 4, 5]", enumerator);
+    rb_assert!(ruby, "enumerator.instance_variables.empty?", enumerator);
+
+    let enumerator = ary.enumeratorize_with_size("cycle", (), EnumeratorSize::Infinite);
+    rb_assert!(ruby, "enumerator.size == Float::INFINITY", enumerator);
+    rb_assert!(
+        ruby,
+        "enumerator.first(7) == [1, 2, 3, 4, 5, 1, 2]",
+        enumerator
+    );
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let size =
+        ruby.proc_from_fn(move |_ruby, _args, _block| counter.fetch_add(1, Ordering::SeqCst) + 10);
+    let enumerator = ary.enumeratorize_with_size("each", (), EnumeratorSize::Lazy(size));
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    rb_assert!(ruby, "enumerator.size == 10", enumerator);
+    rb_assert!(ruby, "enumerator.size == 11", enumerator);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let size = ruby.proc_from_fn(|ruby, _args, _block| -> Result<Value, Error> {
+        Err(Error::new(ruby.exception_runtime_error(), "unknown size"))
+    });
+    let enumerator = ary.enumeratorize_with_size("each", (), EnumeratorSize::Lazy(size));
+    rb_assert!(
+        ruby,
+        r#"(enumerator.size rescue $!).message == "unknown size""#,
+        enumerator
+    );
+}
@@ -0,0 +1,62 @@
+use std::io;
+
+use magnus::{Error, Ruby, Value, error::IntoError, function, prelude::*, rb_assert};
+
+fn refuse(ruby: &Ruby, errno: i32) -> Result<(), Error> {
+    Err(ruby.error_from_errno(errno, Some("connect(2)")))
+}
+
+#[test]
+fn it_creates_errno_errors() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let econnrefused: i32 = ruby.eval("Errno::ECONNREFUSED::Errno").unwrap();
+
+    let err = ruby.error_from_errno(econnrefused, Some("connect(2)"));
+    assert!(err.is_kind_of(ruby.errno_class("ECONNREFUSED").unwrap()));
+    assert_eq!(err.errno(), Some(econnrefused));
+
+    let err = ruby.error_from_errno(econnrefused, None);
+    assert!(err.is_kind_of(ruby.errno_class("ECONNREFUSED").unwrap()));
+
+    // unknown error numbers fall back to SystemCallError
+    let err = ruby.error_from_errno(99999, None);
+    assert!(err.is_kind_of(ruby.exception_system_call_error()));
+    assert_eq!(err.errno(), Some(99999));
+
+    ruby.define_global_function("refuse", function!(refuse, 1));
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          refuse(n)
+          false
+        rescue Errno::ECONNREFUSED => e
+          e.errno == n && e.message.end_with?(" - connect(2)")
+        end
+        "#,
+        n = econnrefused
+    );
+
+    let err = io::Error::from_raw_os_error(econnrefused).into_error(&ruby);
+    assert!(err.is_kind_of(ruby.errno_class("ECONNREFUSED").unwrap()));
+
+    let _ = std::fs::File::open("/does/not/exist");
+    let err = ruby.error_sys_fail(Some("/does/not/exist"));
+    assert!(err.is_kind_of(ruby.errno_class("ENOENT").unwrap()));
+
+    // errno is only available for SystemCallError
+    let err = Error::new(ruby.exception_runtime_error(), "oops");
+    assert_eq!(err.errno(), None);
+    let err = ruby.eval::<Value>("raise 'oops'").unwrap_err();
+    assert_eq!(err.errno(), None);
+    let err = Error::new(ruby.errno_class("EPIPE").unwrap(), "pipe");
+    let epipe: i32 = ruby
+        .errno_class("EPIPE")
+        .unwrap()
+        .const_get("Errno")
+        .unwrap();
+    assert_eq!(err.errno(), Some(epipe));
+
+    assert!(ruby.errno_class("ENOTANERROR").is_err());
+}
@@ -0,0 +1,109 @@
+use std::{fmt, num::ParseIntError};
+
+use magnus::{Error, Exception, Ruby, exception::BacktraceOrder, function, prelude::*, rb_assert};
+
+#[derive(Debug)]
+enum AppError {
+    Config(ConfigError),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to start app")
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Config(e) => Some(e),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ConfigError(ParseIntError);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid port in config")
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn start(port: &str) -> Result<u16, AppError> {
+    port.parse().map_err(|e| AppError::Config(ConfigError(e)))
+}
+
+fn start_plain(ruby: &Ruby, port: String) -> Result<u16, Error> {
+    start(&port).map_err(|e| Error::from_error_chain(ruby.exception_runtime_error(), &e))
+}
+
+fn start_classified(ruby: &Ruby, port: String) -> Result<u16, Error> {
+    start(&port).map_err(|e| {
+        Error::from_error_chain_with(&e, |err| {
+            if err.is::<ParseIntError>() {
+                ruby.exception_arg_error()
+            } else if err.is::<ConfigError>() {
+                ruby.exception_key_error()
+            } else {
+                ruby.exception_runtime_error()
+            }
+        })
+    })
+}
+
+fn boxed(ruby: &Ruby) -> Result<(), Error> {
+    let err: Box<dyn std::error::Error + Send + Sync> =
+        Box::new(ConfigError("".parse::<u8>().unwrap_err()));
+    Err(Error::from_error_chain(ruby.exception_io_error(), &*err))
+}
+
+#[test]
+fn it_converts_the_source_chain_to_causes() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("start_plain", function!(start_plain, 1));
+    ruby.define_global_function("start_classified", function!(start_classified, 1));
+    ruby.define_global_function("boxed", function!(boxed, 0));
+
+    rb_assert!(ruby, r#"start_plain("8080") == 8080"#);
+
+    let e: Exception = ruby
+        .eval(r#"begin; start_plain("http"); rescue => e; e; end"#)
+        .unwrap();
+    assert!(e.is_kind_of(ruby.exception_runtime_error()));
+    assert_eq!(e.message().unwrap(), "failed to start app");
+    let config = e.cause().unwrap().unwrap();
+    assert!(config.is_kind_of(ruby.exception_runtime_error()));
+    assert_eq!(config.message().unwrap(), "invalid port in config");
+    let parse = config.cause().unwrap().unwrap();
+    assert_eq!(parse.message().unwrap(), "invalid digit found in string");
+    assert!(parse.cause().unwrap().is_none());
+
+    let full = e.full_message(false, BacktraceOrder::Top).unwrap();
+    assert!(full.contains("failed to start app (RuntimeError)"));
+    assert!(full.contains("invalid port in config (RuntimeError)"));
+    assert!(full.contains("invalid digit found in string (RuntimeError)"));
+
+    let e: Exception = ruby
+        .eval(r#"begin; start_classified("http"); rescue => e; e; end"#)
+        .unwrap();
+    assert!(e.is_kind_of(ruby.exception_runtime_error()));
+    let config = e.cause().unwrap().unwrap();
+    assert!(config.is_kind_of(ruby.exception_key_error()));
+    let parse = config.cause().unwrap().unwrap();
+    assert!(parse.is_kind_of(ruby.exception_arg_error()));
+
+    let e: Exception = ruby.eval("begin; boxed; rescue => e; e; end").unwrap();
+    assert!(e.is_kind_of(ruby.exception_io_error()));
+    assert_eq!(
+        e.cause().unwrap().unwrap().message().unwrap(),
+        "cannot parse integer from empty string"
+    );
+}
@@ -0,0 +1,137 @@
+use magnus::{Error, Exception, exception::BacktraceOrder, function, prelude::*};
+
+fn wrap_error(ruby: &magnus::Ruby, cause: Exception) -> Result<(), Error> {
+    Err(Error::new_with_cause(
+        ruby.exception_runtime_error(),
+        "wrapped",
+        Some(cause),
+    ))
+}
+
+fn no_cause(ruby: &magnus::Ruby) -> Result<(), Error> {
+    Err(Error::new_with_cause(
+        ruby.exception_runtime_error(),
+        "no cause",
+        None,
+    ))
+}
+
+#[test]
+fn it_accesses_exception_details_and_cause() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let e: Exception = ruby
+        .eval(
+            r#"
+            def fail_inner
+              raise IOError, "inner"
+            end
+
+            def fail_outer
+              fail_inner
+            rescue
+              raise ArgumentError, "outer"
+            end
+
+            begin
+              fail_outer
+            rescue => e
+              e
+            end
+            "#,
+        )
+        .unwrap();
+
+    assert_eq!(e.message().unwrap(), "outer");
+    let cause = e.cause().unwrap().unwrap();
+    assert!(cause.is_kind_of(ruby.exception_io_error()));
+    assert_eq!(cause.message().unwrap(), "inner");
+    assert!(cause.cause().unwrap().is_none());
+
+    let backtrace = e.backtrace().unwrap().unwrap();
+    assert!(backtrace[0].contains("fail_outer"));
+
+    let locations = cause.backtrace_locations().unwrap().unwrap();
+    assert_eq!(locations[0].base_label().unwrap(), "fail_inner");
+    assert_eq!(locations[0].lineno().unwrap(), 3);
+    assert_eq!(locations[1].base_label().unwrap(), "fail_outer");
+    assert!(locations[0].to_string().contains("fail_inner"));
+
+    let full = e.full_message(false, BacktraceOrder::Top).unwrap();
+    assert!(full.contains("outer (ArgumentError)"));
+    assert!(full.contains("inner (IOError)"));
+    assert!(!full.contains("\x1b["));
+    let highlighted = e.full_message(true, BacktraceOrder::Bottom).unwrap();
+    assert!(highlighted.contains("\x1b["));
+
+    let fresh: Exception = ruby.eval(r#"StandardError.new("fresh")"#).unwrap();
+    assert_eq!(fresh.backtrace().unwrap(), None);
+    assert!(fresh.backtrace_locations().unwrap().is_none());
+    fresh
+        .set_backtrace(vec!["a.rb:1:in 'a'", "b.rb:2:in 'b'"])
+        .unwrap();
+    assert_eq!(
+        fresh.backtrace().unwrap(),
+        Some(vec![
+            String::from("a.rb:1:in 'a'"),
+            String::from("b.rb:2:in 'b'")
+        ])
+    );
+    let cause: Exception = ruby.eval(r#"IOError.new("disk")"#).unwrap();
+    let e = ruby
+        .exception_runtime_error()
+        .new_instance_with_cause(("save",), Some(cause))
+        .unwrap();
+    assert_eq!(e.cause().unwrap().unwrap().message().unwrap(), "disk");
+
+    ruby.define_global_function("wrap_error", function!(wrap_error, 1));
+    ruby.define_global_function("no_cause", function!(no_cause, 0));
+
+    let e: Exception = ruby
+        .eval(r#"begin; wrap_error(IOError.new("disk")); rescue => e; e; end"#)
+        .unwrap();
+    assert_eq!(e.message().unwrap(), "wrapped");
+    assert_eq!(e.cause().unwrap().unwrap().message().unwrap(), "disk");
+
+    // an explicit cause takes precedence over the exception being handled
+    let e: Exception = ruby
+        .eval(
+            r#"
+            begin
+              begin
+                raise "handled"
+              rescue
+                wrap_error(IOError.new("explicit"))
+              end
+            rescue => e
+              e
+            end
+            "#,
+        )
+        .unwrap();
+    assert_eq!(e.cause().unwrap().unwrap().message().unwrap(), "explicit");
+
+    // `None` suppresses the automatic cause
+    let e: Exception = ruby
+        .eval(
+            r#"
+            begin
+              begin
+                raise "handled"
+              rescue
+                no_cause
+              end
+            rescue => e
+              e
+            end
+            "#,
+        )
+        .unwrap();
+    assert!(e.cause().unwrap().is_none());
+
+    #[cfg(ruby_gte_3_2)]
+    {
+        let e: Exception = ruby.eval(r#"KeyError.new("missing")"#).unwrap();
+        assert_eq!(e.detailed_message(false).unwrap(), "missing (KeyError)");
+    }
+}
@@ -0,0 +1,76 @@
+use magnus::{Error, Ruby, function, prelude::*, rb_assert, typed_data::AttrReaders};
+
+#[magnus::wrap(class = "Http::Error", free_immediately, attr_readers)]
+struct HttpError {
+    status: u16,
+    body: String,
+    #[magnus(skip_attr_reader)]
+    #[allow(dead_code)]
+    retries: u8,
+}
+
+#[magnus::wrap(class = "Http::Response", free_immediately)]
+struct Response;
+
+fn get(ruby: &Ruby, status: u16) -> Result<String, Error> {
+    if status == 200 {
+        return Ok(String::from("ok"));
+    }
+    Err(ruby.error_with_payload(
+        HttpError {
+            status,
+            body: format!("error {}", status),
+            retries: 3,
+        },
+        format!("request failed with {}", status),
+    ))
+}
+
+#[test]
+fn it_raises_exceptions_with_rust_payloads() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let http = ruby.define_module("Http").unwrap();
+    let class = http
+        .define_error("Error", ruby.exception_standard_error())
+        .unwrap();
+    HttpError::define_attr_readers(class.as_r_class()).unwrap();
+    http.define_class("Response", ruby.class_object()).unwrap();
+    ruby.define_global_function("get", function!(get, 1));
+
+    rb_assert!(ruby, "get(200) == 'ok'");
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          get(404)
+          false
+        rescue Http::Error => e
+          e.message == "request failed with 404" &&
+            e.status == 404 &&
+            e.body == "error 404" &&
+            !e.respond_to?(:retries) &&
+            e.backtrace.any? { |line| line.include?("get") }
+        end
+        "#
+    );
+    rb_assert!(ruby, "(get(500) rescue $!).is_a?(StandardError)");
+
+    let err = get(&ruby, 503).unwrap_err();
+    let payload = err.downcast_ref::<HttpError>().unwrap();
+    assert_eq!(payload.status, 503);
+    assert_eq!(payload.body, "error 503");
+    assert!(err.downcast_ref::<Response>().is_none());
+    assert!(
+        Error::new(ruby.exception_runtime_error(), "plain")
+            .downcast_ref::<HttpError>()
+            .is_none()
+    );
+
+    let err = ruby.error_with_payload(Response, "not an exception");
+    assert!(err.is_kind_of(ruby.exception_type_error()));
+    assert!(err.downcast_ref::<Response>().is_none());
+
+    let err: Error = ruby.eval::<magnus::Value>("get(418)").unwrap_err();
+    assert_eq!(err.downcast_ref::<HttpError>().unwrap().status, 418);
+}
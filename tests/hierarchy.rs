@@ -0,0 +1,56 @@
+use magnus::{Error, RModule, Ruby, function, hierarchy::Hierarchy, prelude::*, rb_assert};
+
+#[magnus::wrap(class = "Zoo::Animal")]
+struct Animal;
+
+#[magnus::wrap(class = "Zoo::Animals::Cat", superclass = "Zoo::Animal")]
+struct Cat;
+
+impl Cat {
+    fn new() -> Self {
+        Self
+    }
+}
+
+#[test]
+fn it_defines_in_dependency_order() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let mut hierarchy = Hierarchy::new();
+    hierarchy
+        .methods(
+            "Zoo::Animals::Cat",
+            |_ruby: &Ruby, class: magnus::RClass| {
+                class.define_singleton_method("new", function!(Cat::new, 0))
+            },
+        )
+        .constant("Zoo::Animals::COUNT", 1)
+        .wrapped::<Cat>()
+        .module("Zoo::Animals")
+        .wrapped::<Animal>()
+        .subclass("Zoo::Keeper", "Struct")
+        .module("Zoo")
+        .methods("Zoo", |_ruby: &Ruby, module: RModule| {
+            module.const_set("OPEN", true)
+        });
+    hierarchy.define(&ruby).unwrap();
+
+    rb_assert!(ruby, "Zoo::Animals::Cat.superclass == Zoo::Animal");
+    rb_assert!(ruby, "Zoo::Animals::Cat.new.is_a?(Zoo::Animal)");
+    rb_assert!(ruby, "Zoo::Keeper.superclass == Struct");
+    rb_assert!(ruby, "Zoo::Animals::COUNT == 1");
+    rb_assert!(ruby, "Zoo::OPEN");
+    rb_assert!(ruby, "(Zoo::Animal.new rescue $!).is_a?(TypeError)");
+
+    let mut hierarchy = Hierarchy::new();
+    hierarchy.subclass("A", "B").subclass("B", "A");
+    let err: Error = hierarchy.define(&ruby).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("cyclic namespace or superclass declarations: A, B")
+    );
+
+    let mut hierarchy = Hierarchy::new();
+    hierarchy.class("Missing::Thing");
+    assert!(hierarchy.define(&ruby).is_err());
+}
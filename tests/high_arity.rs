@@ -0,0 +1,97 @@
+use magnus::{RArray, function, method, prelude::*, rb_assert};
+
+#[allow(clippy::too_many_arguments)]
+fn row(
+    a: i64,
+    b: i64,
+    c: i64,
+    d: i64,
+    e: i64,
+    f: i64,
+    g: i64,
+    h: i64,
+    i: i64,
+    j: i64,
+    k: i64,
+    l: i64,
+    m: i64,
+    n: i64,
+    o: i64,
+    p: i64,
+    q: i64,
+    r: i64,
+    s: i64,
+    t: String,
+) -> String {
+    let sum = a + b + c + d + e + f + g + h + i + j + k + l + m + n + o + p + q + r + s;
+    format!("{}: {}", t, sum)
+}
+
+fn join(rb_self: String, (sep,): (String,), rest: Vec<String>) -> String {
+    let mut parts = vec![rb_self];
+    parts.extend(rest);
+    parts.join(&sep)
+}
+
+fn count((a, b): (i64, i64), rest: RArray) -> (i64, i64, usize) {
+    (a, b, rest.len())
+}
+
+#[allow(clippy::type_complexity)]
+fn sum_wide(
+    (a, b, c, d, e, f, g, h, i, j, k, l): (
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+    ),
+    rest: Vec<i64>,
+) -> i64 {
+    a + b + c + d + e + f + g + h + i + j + k + l + rest.iter().sum::<i64>()
+}
+
+#[test]
+fn it_wraps_high_arities_and_splats() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("row", function!(row, 20));
+    ruby.class_string()
+        .define_method("rs_join", method!(join, *))
+        .unwrap();
+    ruby.define_global_function("count", function!(count, *));
+    ruby.define_global_function("sum_wide", function!(sum_wide, *));
+    ruby.class_object()
+        .define_private_method("private_row", function!(row, 20))
+        .unwrap();
+
+    rb_assert!(ruby, r#"row(*(1..19).to_a, "total") == "total: 190""#);
+    rb_assert!(ruby, "method(:row).arity == 20");
+    rb_assert!(ruby, "Object.private_method_defined?(:private_row)");
+    rb_assert!(ruby, "method(:private_row).arity == 20");
+    rb_assert!(
+        ruby,
+        r#"(row(1, 2) rescue $!).message == "wrong number of arguments (given 2, expected 20)""#
+    );
+
+    rb_assert!(ruby, r#""a".rs_join("-", "b", "c") == "a-b-c""#);
+    rb_assert!(ruby, r#""a".rs_join(",") == "a""#);
+    rb_assert!(ruby, r#"("a".rs_join rescue $!).is_a?(ArgumentError)"#);
+
+    rb_assert!(ruby, "count(1, 2) == [1, 2, 0]");
+    rb_assert!(ruby, "count(1, 2, :x, :y) == [1, 2, 2]");
+
+    rb_assert!(ruby, "sum_wide(*1..12) == 78");
+    rb_assert!(ruby, "sum_wide(*1..14) == 105");
+    rb_assert!(
+        ruby,
+        r#"(sum_wide(1) rescue $!).message == "wrong number of arguments (given 1, expected 12+)""#
+    );
+}
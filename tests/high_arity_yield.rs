@@ -0,0 +1,46 @@
+use magnus::{Ruby, Value, block::Yield, method, prelude::*, rb_assert};
+
+#[allow(clippy::too_many_arguments)]
+fn each_of(
+    ruby: &Ruby,
+    rb_self: Value,
+    a: i64,
+    b: i64,
+    c: i64,
+    d: i64,
+    e: i64,
+    f: i64,
+    g: i64,
+    h: i64,
+    i: i64,
+    j: i64,
+    k: i64,
+    l: i64,
+    m: i64,
+    n: i64,
+    o: i64,
+    p: i64,
+) -> Yield<std::array::IntoIter<i64, 16>> {
+    let args = [a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p];
+    if ruby.block_given() {
+        Yield::Iter(args.into_iter())
+    } else {
+        Yield::Enumerator(rb_self.enumeratorize("each_of", args))
+    }
+}
+
+#[test]
+fn it_yields_from_high_arity_methods() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("each_of", method!(each_of, 16));
+
+    rb_assert!(ruby, "method(:each_of).arity == 16");
+    rb_assert!(
+        ruby,
+        "a = []; each_of(*1..16) { |i| a << i }; a == (1..16).to_a"
+    );
+    rb_assert!(ruby, "each_of(*1..16).is_a?(Enumerator)");
+    rb_assert!(ruby, "each_of(*1..16).to_a == (1..16).to_a");
+    rb_assert!(ruby, "each_of(*1..16).first(3) == [1, 2, 3]");
+}
@@ -0,0 +1,177 @@
+use std::ops::{Add, BitAnd, Div, Mul, Neg, Not, Sub};
+
+use magnus::{
+    function, method,
+    operators::{Operators, Pow},
+    prelude::*,
+    rb_assert,
+};
+
+#[magnus::wrap(class = "Money", free_immediately)]
+#[derive(Clone)]
+struct Money {
+    cents: i64,
+}
+
+impl Money {
+    fn new(cents: i64) -> Self {
+        Self { cents }
+    }
+
+    fn cents(&self) -> i64 {
+        self.cents
+    }
+}
+
+impl Add<&Money> for Money {
+    type Output = Money;
+
+    fn add(self, other: &Money) -> Money {
+        Money::new(self.cents + other.cents)
+    }
+}
+
+impl Sub<&Money> for Money {
+    type Output = Money;
+
+    fn sub(self, other: &Money) -> Money {
+        Money::new(self.cents - other.cents)
+    }
+}
+
+impl Sub<Money> for i64 {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money::new(self - other.cents)
+    }
+}
+
+impl Mul<i64> for Money {
+    type Output = Money;
+
+    fn mul(self, factor: i64) -> Money {
+        Money::new(self.cents * factor)
+    }
+}
+
+impl Mul<Money> for i64 {
+    type Output = Money;
+
+    fn mul(self, money: Money) -> Money {
+        money * self
+    }
+}
+
+impl Div<i64> for Money {
+    type Output = Money;
+
+    fn div(self, divisor: i64) -> Money {
+        Money::new(self.cents / divisor)
+    }
+}
+
+impl Div<&Money> for Money {
+    type Output = f64;
+
+    fn div(self, other: &Money) -> f64 {
+        self.cents as f64 / other.cents as f64
+    }
+}
+
+impl Pow<u32> for Money {
+    type Output = Money;
+
+    fn pow(self, exp: u32) -> Money {
+        Money::new(self.cents.pow(exp))
+    }
+}
+
+impl BitAnd<i64> for Money {
+    type Output = Money;
+
+    fn bitand(self, mask: i64) -> Money {
+        Money::new(self.cents & mask)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money::new(-self.cents)
+    }
+}
+
+impl Not for Money {
+    type Output = bool;
+
+    fn not(self) -> bool {
+        self.cents == 0
+    }
+}
+
+#[test]
+fn it_defines_operators_from_std_ops() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class = ruby.define_class("Money", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Money::new, 1))
+        .unwrap();
+    class
+        .define_method("cents", method!(Money::cents, 0))
+        .unwrap();
+
+    let mut operators = Operators::<Money>::new();
+    operators
+        .add::<&Money>()
+        .sub::<&Money>()
+        .rsub::<i64>()
+        .mul::<i64>()
+        .rmul::<i64>()
+        .div::<i64>()
+        .div::<&Money>()
+        .pow::<u32>()
+        .bitand::<i64>()
+        .neg()
+        .not()
+        .binary("<<", |money: Money, cents: i64| {
+            Money::new(money.cents + cents)
+        });
+    operators.define(class).unwrap();
+
+    rb_assert!(ruby, "(Money.new(150) + Money.new(50)).cents == 200");
+    rb_assert!(ruby, "(Money.new(150) - Money.new(50)).cents == 100");
+    rb_assert!(ruby, "(Money.new(150) * 2).cents == 300");
+    rb_assert!(ruby, "(Money.new(150) / 2).cents == 75");
+    rb_assert!(ruby, "Money.new(150) / Money.new(50) == 3.0");
+    rb_assert!(ruby, "(Money.new(3) ** 2).cents == 9");
+    rb_assert!(ruby, "(Money.new(7) & 3).cents == 3");
+    rb_assert!(ruby, "(-Money.new(150)).cents == -150");
+    rb_assert!(ruby, "~Money.new(0)");
+    rb_assert!(ruby, "(Money.new(150) << 5).cents == 155");
+
+    // reflected operators via coerce
+    // the reflected operator proxy class must survive collection and compaction
+    rb_assert!(
+        ruby,
+        "GC.start; GC.compact; (2 * Money.new(150)).cents == 300"
+    );
+    rb_assert!(ruby, "(2 * Money.new(150)).cents == 300");
+    rb_assert!(ruby, "(500 - Money.new(150)).cents == 350");
+    rb_assert!(
+        ruby,
+        r#"(2 + Money.new(150) rescue $!).message == "Money can't be coerced into Integer""#
+    );
+
+    // unknown right hand operands follow Ruby's coercion protocol
+    rb_assert!(
+        ruby,
+        r#"(Money.new(150) + "a" rescue $!).message == "String can't be coerced into Money""#
+    );
+    rb_assert!(ruby, "(Money.new(150) * nil rescue $!).is_a?(TypeError)");
+
+    // errors other than a TypeError converting the operand are raised
+    rb_assert!(ruby, "(Money.new(150) * 2**70 rescue $!).is_a?(RangeError)");
+}
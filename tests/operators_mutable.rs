@@ -0,0 +1,71 @@
+use std::ops::{Add, Mul};
+
+use magnus::{function, method, operators::Operators, prelude::*, rb_assert};
+
+#[magnus::wrap(class = "Point", free_immediately, mutable)]
+#[derive(Clone)]
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+impl Point {
+    fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    fn to_a(&self) -> (i64, i64) {
+        (self.x, self.y)
+    }
+
+    fn shift(&mut self, by: i64) {
+        self.x += by;
+        self.y += by;
+    }
+}
+
+impl Add<&Point> for Point {
+    type Output = Point;
+
+    fn add(self, other: &Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Mul<Point> for i64 {
+    type Output = Point;
+
+    fn mul(self, point: Point) -> Point {
+        Point::new(self * point.x, self * point.y)
+    }
+}
+
+#[test]
+fn it_defines_operators_for_mutable_types() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class = ruby.define_class("Point", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Point::new, 2))
+        .unwrap();
+    class
+        .define_method("to_a", method!(Point::to_a, 0))
+        .unwrap();
+    class
+        .define_method("shift", method!(Point::shift, 1))
+        .unwrap();
+
+    let mut operators = Operators::<Point>::new();
+    operators.add::<&Point>().rmul::<i64>();
+    operators.define(class).unwrap();
+
+    rb_assert!(ruby, "(Point.new(1, 2) + Point.new(3, 4)).to_a == [4, 6]");
+    // both operands borrow the same object
+    rb_assert!(ruby, "p = Point.new(1, 2); (p + p).to_a == [2, 4]");
+    rb_assert!(ruby, "(2 * Point.new(1, 2)).to_a == [2, 4]");
+    // borrows taken by the operators are released after each call
+    rb_assert!(
+        ruby,
+        "p = Point.new(1, 2); p + p; p.shift(1); (p + p).to_a == [4, 6]"
+    );
+}
@@ -0,0 +1,69 @@
+use magnus::{Error, Ruby, function, method, prelude::*, rb_assert};
+
+fn greet(greeting: String, name: Option<String>, punctuation: Option<char>) -> String {
+    format!(
+        "{}, {}{}",
+        greeting,
+        name.as_deref().unwrap_or("world"),
+        punctuation.unwrap_or('!')
+    )
+}
+
+fn repeat(ruby: &Ruby, rb_self: String, times: Option<usize>) -> Result<String, Error> {
+    match times {
+        Some(0) => Err(Error::new(ruby.exception_arg_error(), "times must be > 0")),
+        Some(n) => Ok(rb_self.repeat(n)),
+        None => Ok(rb_self.repeat(2)),
+    }
+}
+
+fn scale(x: i64, factor: i64) -> i64 {
+    x * factor
+}
+
+#[test]
+fn it_accepts_optional_arguments() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("greet", function!(greet, 1..=3));
+    ruby.class_string()
+        .define_method("rs_repeat", method!(repeat, 0..=1))
+        .unwrap();
+    ruby.define_global_function("scale", function!(scale, 1..=2));
+
+    rb_assert!(ruby, "method(:greet).arity == -2");
+    rb_assert!(ruby, r#""".method(:rs_repeat).arity == -1"#);
+    rb_assert!(ruby, "method(:scale).arity == -2");
+
+    rb_assert!(ruby, r#"greet("Hello") == "Hello, world!""#);
+    rb_assert!(ruby, r#"greet("Hi", "Ruby") == "Hi, Ruby!""#);
+    rb_assert!(ruby, r#"greet("Hi", "Ruby", "?") == "Hi, Ruby?""#);
+    rb_assert!(ruby, r#"greet("Hi", nil, ".") == "Hi, world.""#);
+    rb_assert!(
+        ruby,
+        r#"(greet rescue $!).message == "wrong number of arguments (given 0, expected 1..3)""#
+    );
+    rb_assert!(
+        ruby,
+        r#"(greet(1, 2, 3, 4) rescue $!).message == "wrong number of arguments (given 4, expected 1..3)""#
+    );
+
+    rb_assert!(ruby, r#""ab".rs_repeat == "abab""#);
+    rb_assert!(ruby, r#""ab".rs_repeat(3) == "ababab""#);
+    rb_assert!(
+        ruby,
+        r#"("ab".rs_repeat(0) rescue $!).is_a?(ArgumentError)"#
+    );
+
+    rb_assert!(ruby, "scale(2, 3) == 6");
+    rb_assert!(
+        ruby,
+        r#"(scale(2) rescue $!).message == "wrong number of arguments (given 1, expected 2+)""#
+    );
+    rb_assert!(ruby, "(scale(2, nil) rescue $!).is_a?(TypeError)");
+
+    assert_eq!(
+        magnus::function_signature!(greet, 1..=3).to_string(),
+        "(String, ?String?, ?String?) -> String"
+    );
+}
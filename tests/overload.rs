@@ -0,0 +1,134 @@
+use magnus::{
+    Error, Ruby, function, overloaded_function, overloaded_method, prelude::*, rb_assert,
+};
+
+#[magnus::wrap(class = "Vector", free_immediately)]
+struct Vector {
+    x: i64,
+    y: i64,
+}
+
+impl Vector {
+    fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    fn add_scalar(&self, n: i64) -> Self {
+        Self::new(self.x + n, self.y + n)
+    }
+
+    fn add_vector(&self, other: &Vector) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+
+    fn to_a(&self) -> (i64, i64) {
+        (self.x, self.y)
+    }
+}
+
+#[magnus::wrap(class = "Tally", free_immediately, mutable)]
+struct Tally {
+    count: i64,
+}
+
+impl Tally {
+    fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    fn add(&mut self, n: i64) -> i64 {
+        self.count += n;
+        self.count
+    }
+
+    fn describe(&self, label: String) -> String {
+        format!("{} {}", label, self.count)
+    }
+}
+
+fn describe_small(n: i8) -> String {
+    format!("small {}", n)
+}
+
+fn describe_int(ruby: &Ruby, i: i64) -> Result<String, Error> {
+    if i < 0 {
+        return Err(Error::new(ruby.exception_range_error(), "negative"));
+    }
+    Ok(format!("int {}", i))
+}
+
+fn describe_str(s: String) -> String {
+    format!("str {}", s)
+}
+
+fn describe_pair(a: String, b: String) -> String {
+    format!("pair {} {}", a, b)
+}
+
+#[test]
+fn it_dispatches_on_argument_types() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class = ruby.define_class("Vector", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Vector::new, 2))
+        .unwrap();
+    class
+        .define_method("to_a", magnus::method!(Vector::to_a, 0))
+        .unwrap();
+    class
+        .define_method(
+            "add",
+            overloaded_method!((Vector::add_scalar, 1), (Vector::add_vector, 1)),
+        )
+        .unwrap();
+    ruby.define_global_function(
+        "describe",
+        overloaded_function!((describe_int, 1), (describe_str, 1), (describe_pair, 2)),
+    );
+
+    let class = ruby.define_class("Tally", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Tally::new, 0))
+        .unwrap();
+    class
+        .define_method(
+            "tally",
+            overloaded_method!((Tally::add, 1), (Tally::describe, 1)),
+        )
+        .unwrap();
+    ruby.define_global_function(
+        "describe_small",
+        overloaded_function!((describe_small, 1), (describe_str, 1)),
+    );
+
+    rb_assert!(ruby, "Vector.new(1, 2).add(1).to_a == [2, 3]");
+    rb_assert!(
+        ruby,
+        "Vector.new(1, 2).add(Vector.new(3, 4)).to_a == [4, 6]"
+    );
+    rb_assert!(
+        ruby,
+        r#"(Vector.new(1, 2).add("a") rescue $!).message == "no overload matches (String), expected one of (Integer), (Vector)""#
+    );
+
+    // the mutable borrow taken for Tally::add is released when the argument
+    // doesn't match, so Tally::describe can borrow
+    rb_assert!(
+        ruby,
+        r#"t = Tally.new; t.tally(2); t.tally("count") == "count 2""#
+    );
+    // only a TypeError moves on to the next overload
+    rb_assert!(ruby, r#"describe_small(1) == "small 1""#);
+    rb_assert!(ruby, "(describe_small(1000) rescue $!).is_a?(RangeError)");
+
+    rb_assert!(ruby, r#"describe(1) == "int 1""#);
+    rb_assert!(ruby, r#"describe("a") == "str a""#);
+    rb_assert!(ruby, r#"describe("a", "b") == "pair a b""#);
+    // errors from a matched function are raised, not treated as a mismatch
+    rb_assert!(ruby, "(describe(-1) rescue $!).is_a?(RangeError)");
+    rb_assert!(
+        ruby,
+        r#"(describe rescue $!).message == "no overload matches (), expected one of (Integer), (String), (String, String)""#
+    );
+}
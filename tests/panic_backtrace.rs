@@ -0,0 +1,49 @@
+use magnus::{Exception, Ruby, error::PanicBacktrace, function, prelude::*};
+
+fn explode() {
+    panic!("kaboom")
+}
+
+fn rescue_panic(ruby: &Ruby) -> Exception {
+    let err = ruby.eval::<magnus::Value>("explode").unwrap_err();
+    match err.error_type() {
+        magnus::error::ErrorType::Exception(e) => *e,
+        _ => panic!("expected exception"),
+    }
+}
+
+#[test]
+fn it_attaches_rust_backtraces_to_panics() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("explode", function!(explode, 0));
+
+    assert_eq!(ruby.panic_backtrace(), PanicBacktrace::Off);
+    let e = rescue_panic(&ruby);
+    assert!(e.is_kind_of(ruby.exception_fatal()));
+    assert_eq!(e.message().unwrap(), "kaboom");
+    let backtrace = e.backtrace().unwrap().unwrap();
+    assert!(!backtrace.iter().any(|line| line.contains(".rs:")));
+
+    ruby.set_panic_backtrace(PanicBacktrace::Location);
+    let e = rescue_panic(&ruby);
+    assert_eq!(e.message().unwrap(), "kaboom");
+    let backtrace = e.backtrace().unwrap().unwrap();
+    assert!(backtrace[0].contains("tests/panic_backtrace.rs:4:in 'panic'"));
+    assert!(backtrace[1].starts_with("eval:1:in"));
+
+    ruby.set_panic_backtrace(PanicBacktrace::Full);
+    let e = rescue_panic(&ruby);
+    let backtrace = e.backtrace().unwrap().unwrap();
+    assert!(backtrace[0].contains("panic_backtrace.rs:4:in 'panic_backtrace::explode'"));
+    assert!(!backtrace.iter().any(|line| line.contains("std::panicking")));
+    assert!(backtrace.iter().any(|line| line.starts_with("eval:1:in")));
+
+    // a panic caught outside magnus leaves its details behind, they must not
+    // be attached to a later panic once the setting is off
+    let _ = std::panic::catch_unwind(|| panic!("elsewhere"));
+    ruby.set_panic_backtrace(PanicBacktrace::Off);
+    let e = rescue_panic(&ruby);
+    let backtrace = e.backtrace().unwrap().unwrap();
+    assert!(!backtrace.iter().any(|line| line.contains(".rs:")));
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use magnus::{Error, RArray, Ruby, Value, rbs::Signatures};
+
+#[magnus::wrap(class = "Temperature")]
+struct Temperature {
+    celsius: f64,
+}
+
+#[derive(magnus::SymbolEnum)]
+enum Scale {
+    Celsius,
+    Fahrenheit,
+}
+
+impl Temperature {
+    fn new(celsius: f64) -> Self {
+        Self { celsius }
+    }
+
+    fn parse(ruby: &Ruby, s: String) -> Result<Self, Error> {
+        s.parse()
+            .map(Self::new)
+            .map_err(|e| Error::new(ruby.exception_arg_error(), format!("{}", e)))
+    }
+
+    fn to(&self, scale: Scale) -> f64 {
+        match scale {
+            Scale::Celsius => self.celsius,
+            Scale::Fahrenheit => self.celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    fn warmer(&self, other: &Temperature) -> Option<bool> {
+        self.celsius.partial_cmp(&other.celsius).map(|o| o.is_gt())
+    }
+
+    fn summary(_rb_self: Value, _args: &[Value]) -> HashMap<String, Vec<f64>> {
+        HashMap::new()
+    }
+
+    fn pair(_rb_self: Value, _args: RArray) -> (i64, String) {
+        (0, String::new())
+    }
+}
+
+#[test]
+fn it_generates_rbs_signatures() {
+    let mut signatures = Signatures::new();
+    signatures.module("Weather");
+    signatures
+        .class("Temperature")
+        .superclass("Object")
+        .singleton_method("new", magnus::function_signature!(Temperature::new, 1))
+        .singleton_method("new", magnus::function_signature!(Temperature::parse, 1))
+        .method("to", magnus::method_signature!(Temperature::to, 1))
+        .method("warmer?", magnus::method_signature!(Temperature::warmer, 1))
+        .method(
+            "summary",
+            magnus::method_signature!(Temperature::summary, -1),
+        )
+        .method("pair", magnus::method_signature!(Temperature::pair, -2));
+
+    assert_eq!(
+        signatures.to_string(),
+        r#"module Weather
+end
+
+class Temperature < Object
+  def self.new: (Float) -> Temperature | (String) -> Temperature
+  def to: (:celsius | :fahrenheit) -> Float
+  def warmer?: (Temperature) -> bool?
+  def summary: (*untyped) -> Hash[String, Array[Float]]
+  def pair: (*untyped) -> [Integer, String]
+end
+"#
+    );
+}
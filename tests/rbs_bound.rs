@@ -0,0 +1,93 @@
+#![cfg(feature = "rbs")]
+
+use magnus::{
+    Error, RClass, Value, block::Proc, function, method, overloaded_method, prelude::*,
+    rbs::Signatures,
+};
+
+#[magnus::wrap(class = "Counter")]
+struct Counter(usize);
+
+impl Counter {
+    fn new(start: usize) -> Self {
+        Self(start)
+    }
+
+    fn count(&self) -> usize {
+        self.0
+    }
+
+    fn reset(&self) {}
+
+    fn add_count(&self, other: &Counter) -> usize {
+        self.0 + other.0
+    }
+
+    fn add_int(&self, n: usize) -> usize {
+        self.0 + n
+    }
+
+    fn each(&self, block: Proc) -> Result<(), Error> {
+        let _: Value = block.call((self.0,))?;
+        Ok(())
+    }
+}
+
+fn greet(name: String, greeting: Option<String>) -> Result<String, Error> {
+    Ok(format!(
+        "{}, {}!",
+        greeting.as_deref().unwrap_or("Hello"),
+        name
+    ))
+}
+
+#[test]
+fn it_records_bound_method_signatures() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class = ruby.define_class("Counter", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Counter::new, 1))
+        .unwrap();
+    class
+        .define_method("count", method!(Counter::count, 0))
+        .unwrap();
+    class
+        .define_method("reset", method!(Counter::reset, 0))
+        .unwrap();
+    class
+        .define_method(
+            "add",
+            overloaded_method!((Counter::add_count, 1), (Counter::add_int, 1)),
+        )
+        .unwrap();
+    class
+        .define_method("each", method!(Counter::each, 1))
+        .unwrap();
+    class
+        .define_private_method("secret", method!(Counter::count, 0))
+        .unwrap();
+    let module = ruby.define_module("Greeting").unwrap();
+    module
+        .define_module_function("greet", function!(greet, 1..=2))
+        .unwrap();
+    let anon = RClass::new(ruby.class_object()).unwrap();
+    anon.define_singleton_method("ignored", function!(Counter::new, 1))
+        .unwrap();
+
+    assert_eq!(
+        Signatures::bound().to_string(),
+        r#"class Counter
+  def self.new: (Integer) -> Counter
+  def count: () -> Integer
+  def reset: () -> void
+  def add: (Counter) -> Integer | (Integer) -> Integer
+  def each: (Proc) -> void
+end
+
+module Greeting
+  def self.greet: (String, ?String?) -> String
+end
+"#
+    );
+}
@@ -0,0 +1,130 @@
+use std::fmt;
+
+use magnus::{Error, ExceptionClass, error::RubyErrors, function, prelude::*, rb_assert};
+
+#[derive(Debug, magnus::RubyErrors)]
+#[magnus(class = "MyGem::Error")]
+enum MyGemError {
+    Timeout {
+        seconds: u64,
+    },
+    #[magnus(name = "HTTPError")]
+    Http {
+        status: u16,
+        #[magnus(skip_attr_reader)]
+        body: String,
+    },
+    Closed,
+    InvalidError(String),
+}
+
+impl fmt::Display for MyGemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Timeout { seconds } => write!(f, "timed out after {}s", seconds),
+            Self::Http { status, body } => write!(f, "{}: {}", status, body),
+            Self::Closed => write!(f, "connection closed"),
+            Self::InvalidError(s) => write!(f, "invalid: {}", s),
+        }
+    }
+}
+
+#[derive(Debug, magnus::RubyErrors)]
+#[magnus(class = "Nested::Deeply::Failure", superclass = "IOError")]
+enum NestedError {
+    Broken,
+}
+
+impl fmt::Display for NestedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "broken")
+    }
+}
+
+fn trigger(kind: String) -> Result<(), MyGemError> {
+    Err(match kind.as_str() {
+        "timeout" => MyGemError::Timeout { seconds: 30 },
+        "http" => MyGemError::Http {
+            status: 404,
+            body: String::from("not found"),
+        },
+        "closed" => MyGemError::Closed,
+        _ => MyGemError::InvalidError(kind),
+    })
+}
+
+fn broken() -> Result<(), NestedError> {
+    Err(NestedError::Broken)
+}
+
+#[test]
+fn it_defines_and_raises_error_hierarchy() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    MyGemError::define_errors(&ruby).unwrap();
+    NestedError::define_errors(&ruby).unwrap();
+    // defining again is a no-op
+    MyGemError::define_errors(&ruby).unwrap();
+
+    rb_assert!(ruby, "MyGem.instance_of?(Module)");
+    rb_assert!(ruby, "MyGem::Error.superclass == StandardError");
+    rb_assert!(ruby, "MyGem::TimeoutError.superclass == MyGem::Error");
+    rb_assert!(ruby, "MyGem::HTTPError.superclass == MyGem::Error");
+    rb_assert!(ruby, "MyGem::ClosedError.superclass == MyGem::Error");
+    rb_assert!(ruby, "MyGem::InvalidError.superclass == MyGem::Error");
+    rb_assert!(ruby, "Nested::Deeply::Failure.superclass == IOError");
+    rb_assert!(
+        ruby,
+        "Nested::Deeply::BrokenError.superclass == Nested::Deeply::Failure"
+    );
+
+    let base: ExceptionClass = ruby.eval("MyGem::Error").unwrap();
+    assert!(MyGemError::base_class(&ruby).equal(base).unwrap());
+    let closed: ExceptionClass = ruby.eval("MyGem::ClosedError").unwrap();
+    assert!(MyGemError::Closed.class(&ruby).equal(closed).unwrap());
+
+    ruby.define_global_function("trigger", function!(trigger, 1));
+    ruby.define_global_function("broken", function!(broken, 0));
+
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          trigger("timeout")
+        rescue MyGem::TimeoutError => e
+          e.message == "timed out after 30s" && e.seconds == 30
+        end
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          trigger("http")
+        rescue MyGem::Error => e
+          e.is_a?(MyGem::HTTPError) &&
+            e.message == "404: not found" &&
+            e.status == 404 &&
+            !e.respond_to?(:body)
+        end
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"(trigger("closed") rescue $!).message == "connection closed""#
+    );
+    rb_assert!(
+        ruby,
+        r#"(trigger("other") rescue $!).instance_of?(MyGem::InvalidError)"#
+    );
+    rb_assert!(
+        ruby,
+        "begin; broken; rescue IOError => e; e.instance_of?(Nested::Deeply::BrokenError); end"
+    );
+
+    let err: Error = MyGemError::Timeout { seconds: 5 }
+        .into_exception(&ruby)
+        .unwrap()
+        .into();
+    assert!(err.is_kind_of(base));
+}
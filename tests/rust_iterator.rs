@@ -0,0 +1,110 @@
+use magnus::{Ruby, function, iterator::RustIterator, rb_assert};
+
+struct Cursor {
+    rows: Vec<String>,
+}
+
+impl Iterator for Cursor {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.rows.pop()
+    }
+}
+
+fn cursor() -> RustIterator {
+    RustIterator::new(Cursor {
+        rows: vec![String::from("c"), String::from("b"), String::from("a")],
+    })
+}
+
+fn evens(limit: i64) -> RustIterator {
+    RustIterator::rewindable((0..limit).filter(|i| i % 2 == 0))
+}
+
+fn range(limit: i64) -> RustIterator {
+    RustIterator::rewindable(0..limit)
+}
+
+/// Rewinds `$iter` while producing its only item.
+#[derive(Clone)]
+struct Rewinder {
+    done: bool,
+}
+
+impl Iterator for Rewinder {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+        let ruby = Ruby::get().unwrap();
+        ruby.eval("$iter.rewind rescue $!.message").ok()
+    }
+}
+
+fn rewinder() -> RustIterator {
+    RustIterator::rewindable(Rewinder { done: false })
+}
+
+#[test]
+fn it_wraps_rust_iterators() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("cursor", function!(cursor, 0));
+    ruby.define_global_function("evens", function!(evens, 1));
+    ruby.define_global_function("range", function!(range, 1));
+    ruby.define_global_function("rewinder", function!(rewinder, 0));
+
+    rb_assert!(ruby, "cursor.is_a?(Enumerable)");
+    rb_assert!(ruby, r#"cursor.class.name == "Magnus::Iterator""#);
+    rb_assert!(ruby, r#"cursor.to_a == ["a", "b", "c"]"#);
+    rb_assert!(ruby, r#"cursor.map(&:upcase) == ["A", "B", "C"]"#);
+    rb_assert!(
+        ruby,
+        r#"cursor.each.with_index.to_a == [["a", 0], ["b", 1], ["c", 2]]"#
+    );
+
+    // external iteration
+    let c = ruby.eval::<magnus::Value>("cursor").unwrap();
+    rb_assert!(ruby, r#"c.next == "a""#, c);
+    rb_assert!(ruby, r#"c.peek == "b""#, c);
+    rb_assert!(ruby, r#"c.peek == "b""#, c);
+    rb_assert!(ruby, r#"c.next == "b""#, c);
+    rb_assert!(ruby, r#"c.to_a == ["c"]"#, c);
+    rb_assert!(ruby, "(c.next rescue $!).is_a?(StopIteration)", c);
+    rb_assert!(ruby, "(c.peek rescue $!).is_a?(StopIteration)", c);
+    rb_assert!(
+        ruby,
+        r#"(c.rewind rescue $!).message == "can't rewind a one-shot iterator""#,
+        c
+    );
+
+    // rewindable
+    let r = ruby.eval::<magnus::Value>("range(3)").unwrap();
+    rb_assert!(ruby, "r.to_a == [0, 1, 2]", r);
+    rb_assert!(ruby, "r.to_a == []", r);
+    rb_assert!(ruby, "r.rewind.equal?(r)", r);
+    rb_assert!(ruby, "r.next == 0", r);
+    rb_assert!(ruby, "r.to_a == [1, 2]", r);
+    rb_assert!(
+        ruby,
+        r#"$iter = rewinder; $iter.to_a == ["iterator is in use"]"#
+    );
+
+    // size
+    rb_assert!(ruby, "range(3).size == 3");
+    rb_assert!(ruby, "range(3).tap(&:peek).size == 3");
+    rb_assert!(ruby, "range(3).tap(&:next).size == 2");
+    rb_assert!(ruby, "evens(10).size.nil?");
+    rb_assert!(ruby, "cursor.size.nil?");
+
+    // lazy
+    rb_assert!(
+        ruby,
+        "range(1_000_000_000).lazy.map { |i| i * 3 }.select(&:even?).first(3) == [0, 6, 12]"
+    );
+    rb_assert!(ruby, "evens(10).lazy.reject(&:zero?).to_a == [2, 4, 6, 8]");
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+use magnus::{define_rust_protocols, function, method, prelude::*, rb_assert};
+
+#[magnus::wrap(class = "Money", free_immediately)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Hash)]
+struct Money {
+    cents: i64,
+}
+
+impl Money {
+    fn new(cents: i64) -> Self {
+        Self { cents }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "${}.{:02}", self.cents / 100, self.cents % 100)
+    }
+}
+
+#[magnus::wrap(class = "Bag", free_immediately, mutable)]
+struct Bag(Vec<String>);
+
+impl Bag {
+    fn new(items: Vec<String>) -> Self {
+        Self(items)
+    }
+
+    fn push(&mut self, item: String) {
+        self.0.push(item)
+    }
+}
+
+impl<'a> IntoIterator for &'a Bag {
+    type Item = &'a String;
+    type IntoIter = std::slice::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[test]
+fn it_defines_protocols_from_rust_traits() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class = ruby.define_class("Money", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Money::new, 1))
+        .unwrap();
+    define_rust_protocols!(class, Money).unwrap();
+
+    let class = ruby.define_class("Bag", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Bag::new, 1))
+        .unwrap();
+    class.define_method("push", method!(Bag::push, 1)).unwrap();
+    define_rust_protocols!(class, Bag).unwrap();
+
+    rb_assert!(ruby, "Money.new(150) == Money.new(150)");
+    rb_assert!(ruby, "Money.new(150) != Money.new(100)");
+    rb_assert!(ruby, "Money.new(150) != 150");
+    rb_assert!(ruby, "Money.new(150).eql?(Money.new(150))");
+    rb_assert!(ruby, "Money.new(150).hash == Money.new(150).hash");
+    rb_assert!(ruby, "[Money.new(1), Money.new(1)].uniq.length == 1");
+    rb_assert!(ruby, "Money.new(1) < Money.new(2)");
+    rb_assert!(ruby, "Money.new(5).between?(Money.new(1), Money.new(9))");
+    rb_assert!(ruby, "Money.include?(Comparable)");
+    rb_assert!(ruby, r#"Money.new(150).to_s == "$1.50""#);
+    rb_assert!(ruby, r#"Money.new(150).inspect == "Money { cents: 150 }""#);
+    rb_assert!(ruby, "m = Money.new(1); m.dup == m && !m.dup.equal?(m)");
+    rb_assert!(ruby, "!Money.include?(Enumerable)");
+
+    rb_assert!(ruby, r#"Bag.new(["a", "b"]).to_a == ["a", "b"]"#);
+    rb_assert!(ruby, r#"Bag.new(["a", "b"]).each.next == "a""#);
+    rb_assert!(ruby, r#"Bag.new(["a", "bc"]).map(&:length) == [1, 2]"#);
+    rb_assert!(ruby, "Bag.include?(Enumerable)");
+    rb_assert!(ruby, "!Bag.include?(Comparable)");
+    // each holds a borrow while iterating, so the block can't mutate the bag
+    rb_assert!(
+        ruby,
+        r#"b = Bag.new(["a"]); (b.each { b.push("x") } rescue $!).is_a?(RuntimeError) && b.to_a == ["a"]"#
+    );
+    rb_assert!(
+        ruby,
+        r#"b = Bag.new(["a"]); b.push("b"); b.to_a == ["a", "b"]"#
+    );
+    rb_assert!(ruby, "Bag.instance_method(:==).owner == BasicObject");
+}
@@ -0,0 +1,105 @@
+use magnus::{function, rb_assert};
+
+fn read_file(path: String) -> Result<String, std::io::Error> {
+    std::fs::read_to_string(path)
+}
+
+fn custom_io_error() -> Result<(), std::io::Error> {
+    Err(std::io::Error::other("pipe closed"))
+}
+
+fn kind_io_error(eof: bool) -> Result<(), std::io::Error> {
+    if eof {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "stream ended",
+        ))
+    } else {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "no such widget",
+        ))
+    }
+}
+
+fn parse_int(s: String) -> Result<i64, std::num::ParseIntError> {
+    s.parse()
+}
+
+fn parse_float(s: String) -> Result<f64, std::num::ParseFloatError> {
+    s.parse()
+}
+
+fn to_u8(i: i64) -> Result<u8, std::num::TryFromIntError> {
+    u8::try_from(i)
+}
+
+fn decode(bytes: Vec<u8>) -> Result<String, std::string::FromUtf8Error> {
+    String::from_utf8(bytes)
+}
+
+fn decode_str(bytes: Vec<u8>) -> Result<usize, std::str::Utf8Error> {
+    std::str::from_utf8(&bytes).map(str::len)
+}
+
+#[test]
+fn it_converts_std_errors() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("read_file", function!(read_file, 1));
+    ruby.define_global_function("custom_io_error", function!(custom_io_error, 0));
+    ruby.define_global_function("kind_io_error", function!(kind_io_error, 1));
+    ruby.define_global_function("parse_int", function!(parse_int, 1));
+    ruby.define_global_function("parse_float", function!(parse_float, 1));
+    ruby.define_global_function("to_u8", function!(to_u8, 1));
+    ruby.define_global_function("decode", function!(decode, 1));
+    ruby.define_global_function("decode_str", function!(decode_str, 1));
+
+    rb_assert!(
+        ruby,
+        r#"
+        e = begin; read_file("/magnus/does/not/exist"); rescue => e; e; end
+        e.class == Errno::ENOENT && e.errno == Errno::ENOENT::Errno
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        e = begin; custom_io_error; rescue => e; e; end
+        e.class == IOError && e.message == "pipe closed"
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        e = begin; kind_io_error(false); rescue => e; e; end
+        e.class == Errno::ENOENT && e.message.include?("no such widget")
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        e = begin; kind_io_error(true); rescue => e; e; end
+        e.class == EOFError && e.message == "stream ended"
+        "#
+    );
+
+    rb_assert!(ruby, r#"parse_int("42") == 42"#);
+    rb_assert!(
+        ruby,
+        r#"(parse_int("forty-two") rescue $!).is_a?(ArgumentError)"#
+    );
+    rb_assert!(ruby, r#"parse_float("1.5") == 1.5"#);
+    rb_assert!(
+        ruby,
+        r#"(parse_float("one") rescue $!).is_a?(ArgumentError)"#
+    );
+
+    rb_assert!(ruby, "to_u8(255) == 255");
+    rb_assert!(ruby, "(to_u8(256) rescue $!).is_a?(RangeError)");
+
+    rb_assert!(ruby, "decode([104, 105]) == 'hi'");
+    rb_assert!(ruby, "(decode([0xff]) rescue $!).is_a?(EncodingError)");
+    rb_assert!(ruby, "decode_str([104, 105]) == 2");
+    rb_assert!(ruby, "(decode_str([0xc3]) rescue $!).is_a?(EncodingError)");
+}
@@ -0,0 +1,37 @@
+use magnus::{SymbolEnum, function, rb_assert};
+
+#[derive(Debug, PartialEq, SymbolEnum)]
+enum Mode {
+    Fast,
+    Safe,
+    #[magnus(name = "very_safe")]
+    Paranoid,
+}
+
+fn round_trip(mode: Mode) -> Mode {
+    mode
+}
+
+#[test]
+fn it_converts_enums_to_and_from_symbols() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("round_trip", function!(round_trip, 1));
+
+    rb_assert!(ruby, "round_trip(:fast) == :fast");
+    rb_assert!(ruby, r#"round_trip("safe") == :safe"#);
+    rb_assert!(ruby, "round_trip(:very_safe) == :very_safe");
+    rb_assert!(ruby, r#"round_trip("fast".to_sym) == :fast"#);
+
+    assert_eq!(ruby.eval::<Mode>(":very_safe").unwrap(), Mode::Paranoid);
+
+    rb_assert!(
+        ruby,
+        r#"(round_trip(:slow) rescue $!).message == "invalid value :slow (expected one of :fast, :safe, :very_safe)""#
+    );
+    rb_assert!(
+        ruby,
+        "(round_trip(:paranoid) rescue $!).is_a?(ArgumentError)"
+    );
+    rb_assert!(ruby, "(round_trip(1) rescue $!).is_a?(TypeError)");
+}
@@ -0,0 +1,105 @@
+use std::{cell::Cell, collections::HashMap};
+
+use magnus::{
+    DataTypeFunctions, Error, RString, Ruby, TypedData, Value, embed::init, function, method,
+    prelude::*, rb_assert, value::Opaque,
+};
+
+#[derive(DataTypeFunctions, TypedData)]
+#[magnus(class = "Registry", free_immediately)]
+struct Registry {
+    #[magnus(mark)]
+    first: Opaque<RString>,
+    #[magnus(mark)]
+    values: Vec<Opaque<Value>>,
+    #[magnus(mark)]
+    named: HashMap<String, Opaque<Value>>,
+    #[magnus(compact)]
+    movable: Cell<Opaque<RString>>,
+}
+
+// marked fields are still marked with a hand written DataTypeFunctions
+#[derive(TypedData)]
+#[magnus(class = "Holder", free_immediately)]
+struct Holder {
+    #[magnus(mark)]
+    value: Opaque<RString>,
+}
+
+impl DataTypeFunctions for Holder {}
+
+impl Holder {
+    fn new(ruby: &Ruby) -> Self {
+        Self {
+            value: ruby.str_new(&"held".repeat(100)).into(),
+        }
+    }
+
+    fn check(ruby: &Ruby, rb_self: &Self) -> Result<bool, Error> {
+        Ok(ruby.get_inner(rb_self.value).to_string()? == "held".repeat(100))
+    }
+}
+
+impl Registry {
+    fn new(ruby: &Ruby) -> Self {
+        let mut named = HashMap::new();
+        named.insert(
+            "a".to_owned(),
+            ruby.str_new(&"a".repeat(100)).as_value().into(),
+        );
+        Self {
+            first: ruby.str_new(&"first".repeat(100)).into(),
+            values: (0..10)
+                .map(|i| ruby.str_new(&i.to_string().repeat(100)).as_value().into())
+                .collect(),
+            named,
+            movable: Cell::new(ruby.str_new(&"movable".repeat(100)).into()),
+        }
+    }
+
+    fn check(ruby: &Ruby, rb_self: &Self) -> Result<bool, Error> {
+        let mut ok = ruby.get_inner(rb_self.first).to_string()? == "first".repeat(100);
+        for (i, v) in rb_self.values.iter().enumerate() {
+            ok &=
+                RString::try_convert(ruby.get_inner(*v))?.to_string()? == i.to_string().repeat(100);
+        }
+        ok &= RString::try_convert(ruby.get_inner(rb_self.named["a"]))?.to_string()?
+            == "a".repeat(100);
+        ok &= ruby.get_inner(rb_self.movable.get()).to_string()? == "movable".repeat(100);
+        Ok(ok)
+    }
+}
+
+#[test]
+fn it_marks_annotated_fields() {
+    let ruby = unsafe { init() };
+
+    let class = ruby.define_class("Registry", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Registry::new, 0))
+        .unwrap();
+    class
+        .define_method("check", method!(Registry::check, 0))
+        .unwrap();
+
+    let class = ruby.define_class("Holder", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", function!(Holder::new, 0))
+        .unwrap();
+    class
+        .define_method("check", method!(Holder::check, 0))
+        .unwrap();
+
+    let registry: Value = ruby.eval("Registry.new").unwrap();
+    let holder: Value = ruby.eval("Holder.new").unwrap();
+    let _: Value = ruby
+        .eval(
+            r#"
+            10.times { 1000.times { "x" * 100 }; GC.start }
+            GC.compact if GC.respond_to?(:compact)
+            "#,
+        )
+        .unwrap();
+    rb_assert!(ruby, "registry.check", registry);
+    rb_assert!(ruby, "holder.check", holder);
+}
@@ -0,0 +1,138 @@
+use magnus::{
+    Error, Ruby, TryConvert, Value, block::Proc, embed::init, method, prelude::*, rb_assert,
+};
+
+#[magnus::wrap(class = "Counter", free_immediately, mutable)]
+struct Counter {
+    count: i64,
+}
+
+impl Counter {
+    fn count(&self) -> i64 {
+        self.count
+    }
+
+    fn incr(&mut self) -> i64 {
+        self.count += 1;
+        self.count
+    }
+
+    fn incr_with(ruby: &Ruby, rb_self: &mut Self) -> Result<i64, Error> {
+        let block: Proc = ruby.block_proc()?;
+        let by: i64 = block.call(())?;
+        rb_self.count += by;
+        Ok(rb_self.count)
+    }
+
+    fn incr_each(ruby: &Ruby, rb_self: &mut Self) -> Result<(), Error> {
+        for _ in 0..3 {
+            rb_self.count += 1;
+            let _: Value = ruby.yield_value(rb_self.count)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn it_checks_borrows_at_runtime() {
+    let ruby = unsafe { init() };
+
+    let class = ruby.define_class("Counter", ruby.class_object()).unwrap();
+    class
+        .define_method("count", method!(Counter::count, 0))
+        .unwrap();
+    class
+        .define_method("incr", method!(Counter::incr, 0))
+        .unwrap();
+    class
+        .define_method("incr_with", method!(Counter::incr_with, 0))
+        .unwrap();
+
+    class
+        .define_method("incr_each", method!(Counter::incr_each, 0))
+        .unwrap();
+
+    let counter = Counter { count: 0 };
+    let counter = ruby.obj_wrap(counter);
+
+    rb_assert!(ruby, "counter.incr == 1", counter);
+    rb_assert!(ruby, "counter.incr_with { 2 } == 3", counter);
+    rb_assert!(ruby, "counter.count == 3", counter);
+
+    // re-entrant borrows raise rather than panic
+    rb_assert!(
+        ruby,
+        r#"(counter.incr_with { counter.incr } rescue $!).message == "Counter is already mutably borrowed""#,
+        counter
+    );
+    rb_assert!(
+        ruby,
+        r#"(counter.incr_with { counter.count } rescue $!).is_a?(RuntimeError)"#,
+        counter
+    );
+    // borrows are released after an error
+    rb_assert!(ruby, "counter.incr == 4", counter);
+
+    // a method suspended in a Fiber keeps its borrow until it returns
+    rb_assert!(
+        ruby,
+        r#"
+        e = counter.enum_for(:incr_each)
+        e.next == 5 &&
+          (counter.incr rescue $!).message == "Counter is already mutably borrowed" &&
+          e.next == 6 &&
+          e.next == 7 &&
+          (e.next rescue $!).is_a?(StopIteration) &&
+          counter.incr == 8
+        "#,
+        counter
+    );
+
+    // or until the abandoned Fiber is garbage collected
+    rb_assert!(
+        ruby,
+        r#"
+        -> { counter.enum_for(:incr_each).tap(&:next) }.call
+        10.times.any? do
+          GC.start(full_mark: true, immediate_sweep: true)
+          counter.incr rescue nil
+        end
+        "#,
+        counter
+    );
+
+    // closures called from Ruby can borrow too
+    class
+        .define_method_fn("add", 1, |_ruby, rb_self, args, _block| {
+            let counter = <&mut Counter>::try_convert(rb_self)?;
+            counter.count += i64::try_convert(args[0])?;
+            Ok(counter.count)
+        })
+        .unwrap();
+    let incr = ruby.proc_from_fn(|_ruby, args, _block| {
+        let counter = <&mut Counter>::try_convert(args[0])?;
+        counter.count += 1;
+        Ok(counter.count)
+    });
+    rb_assert!(
+        ruby,
+        "count = counter.count; counter.add(2) == count + 2 && incr.call(counter) == count + 3",
+        counter,
+        incr
+    );
+    rb_assert!(
+        ruby,
+        r#"(counter.incr_with { incr.call(counter) } rescue $!).message == "Counter is already mutably borrowed""#,
+        counter,
+        incr
+    );
+
+    // borrows can't be held outside of a call from Ruby
+    assert!(<&Counter>::try_convert(counter.as_value()).is_err());
+
+    rb_assert!(
+        ruby,
+        "(counter.freeze.incr rescue $!).is_a?(FrozenError)",
+        counter
+    );
+}
@@ -0,0 +1,112 @@
+use magnus::{Error, RArray, Ruby, Value, error::WarningCategory, function, rb_assert};
+
+fn deprecated_method(ruby: &Ruby) -> Result<(), Error> {
+    ruby.warn_uplevel(
+        "deprecated_method is deprecated",
+        0,
+        Some(WarningCategory::Deprecated),
+    )
+}
+
+#[test]
+fn it_outputs_categorised_warnings() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let warnings: RArray = ruby
+        .eval(
+            r#"
+            $warnings = []
+            module Warning
+              def self.warn(msg, category: nil)
+                $warnings << [msg, category]
+              end
+            end
+            $warnings
+            "#,
+        )
+        .unwrap();
+    let take = || {
+        let ary = warnings.dup();
+        warnings.clear().unwrap();
+        ary
+    };
+
+    ruby.warn("plain").unwrap();
+    rb_assert!(ruby, r#"w == [["warning: plain\n", nil]]"#, w = take());
+
+    let _: Value = ruby.eval("Warning[:deprecated] = false").unwrap();
+    assert!(
+        !ruby
+            .warning_category_enabled(WarningCategory::Deprecated)
+            .unwrap()
+    );
+    ruby.warn_deprecated("old").unwrap();
+    rb_assert!(ruby, "w.empty?", w = take());
+
+    let _: Value = ruby.eval("Warning[:deprecated] = true").unwrap();
+    assert!(
+        ruby.warning_category_enabled(WarningCategory::Deprecated)
+            .unwrap()
+    );
+    ruby.warn_deprecated("old").unwrap();
+    rb_assert!(
+        ruby,
+        r#"w == [["warning: old\n", :deprecated]]"#,
+        w = take()
+    );
+
+    let _: Value = ruby.eval("Warning[:experimental] = true").unwrap();
+    ruby.warn_experimental("new").unwrap();
+    rb_assert!(
+        ruby,
+        r#"w == [["warning: new\n", :experimental]]"#,
+        w = take()
+    );
+
+    #[cfg(ruby_gte_3_3)]
+    {
+        let _: Value = ruby.eval("Warning[:performance] = true").unwrap();
+        ruby.warn_performance("slow").unwrap();
+        rb_assert!(
+            ruby,
+            r#"w == [["warning: slow\n", :performance]]"#,
+            w = take()
+        );
+    }
+
+    ruby.define_global_function("deprecated_method", function!(deprecated_method, 0));
+    let _: Value = ruby.eval("\n\ndeprecated_method").unwrap();
+    rb_assert!(
+        ruby,
+        r#"w == [["eval:3: warning: deprecated_method is deprecated\n", :deprecated]]"#,
+        w = take()
+    );
+    let _: Value = ruby.eval("Warning[:deprecated] = false").unwrap();
+    let _: Value = ruby.eval("deprecated_method").unwrap();
+    rb_assert!(ruby, "w.empty?", w = take());
+
+    // warnings disabled entirely
+    let _: Value = ruby.eval("$VERBOSE = nil").unwrap();
+    assert!(
+        !ruby
+            .warning_category_enabled(WarningCategory::Experimental)
+            .unwrap()
+    );
+    ruby.warn("plain").unwrap();
+    rb_assert!(ruby, "w.empty?", w = take());
+
+    // Warning.warn raising is returned as an error
+    let _: Value = ruby
+        .eval(
+            r#"
+            $VERBOSE = false
+            module Warning
+              def self.warn(msg, category: nil)
+                raise msg
+              end
+            end
+            "#,
+        )
+        .unwrap();
+    assert!(ruby.warn("fail").is_err());
+}